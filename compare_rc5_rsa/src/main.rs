@@ -3,7 +3,9 @@ extern crate openssl;
 use std::time::Instant;
 use rc5_algo::{RC5, RC5WordSize};
 use clap::Parser;
+use openssl::pkey::PKey;
 use openssl::rsa::{Rsa, Padding};
+use rsa::{stream, Digest};
 
 #[derive(Parser, Debug,)]
 #[command(author, version, about, long_about = None)]
@@ -25,6 +27,10 @@ struct Cli {
     /// Number of octets in key
     #[arg(short, long, default_value_t = 8)]
     bytes_key: u8,
+
+    /// Encrypt the whole file with RSA as a stream of OAEP blocks, not just its first block
+    #[arg(long)]
+    chunked: bool,
 }
 
 fn main() {
@@ -49,7 +55,20 @@ fn main() {
     let rsa_private_key = Rsa::private_key_from_pem(&rsa_private_key_pem)
         .expect("Failed to generate private key");
 
+    let rsa_pubkey_pkey = PKey::from_rsa(rsa_pubkey.clone())
+        .expect("Failed to generate public key");
+    let rsa_private_pkey = PKey::from_rsa(rsa_private_key.clone())
+        .expect("Failed to generate private key");
+
     let rc5_cypher: Vec<u8> = rc5.encrypt_cbc_pad(&data, &rc5_key).0;
+    let rsa_stream = if cli.chunked {
+        let mut cypher = Vec::new();
+        stream::encrypt(&rsa_pubkey_pkey, &data[..], &mut cypher, Digest::Sha256, Digest::Sha256)
+            .expect("RSA encryption failed");
+        cypher
+    } else {
+        Vec::new()
+    };
     let mut rsa_cypher = vec![0; rsa_pubkey.size() as usize];
     let mut rsa_decrypted = vec![0; rsa_keypair.size() as usize];
     
@@ -68,6 +87,22 @@ fn main() {
         let _ = rc5.decrypt_cbc_pad(&rc5_cypher, &rc5_key);
         rc5_dec_time += Instant::now().duration_since(start).as_micros();
 
+        if cli.chunked {
+            let start = Instant::now();
+            let mut cypher = Vec::with_capacity(rsa_stream.len());
+            stream::encrypt(&rsa_pubkey_pkey, &data[..], &mut cypher, Digest::Sha256, Digest::Sha256)
+                .expect("RSA encryption failed");
+            rsa_enc_time += Instant::now().duration_since(start).as_micros();
+
+            let start = Instant::now();
+            let mut decrypted = Vec::with_capacity(data.len());
+            stream::decrypt(&rsa_private_pkey, &rsa_stream[..], &mut decrypted)
+                .expect("RSA decryption failed");
+            rsa_dec_time += Instant::now().duration_since(start).as_micros();
+            continue;
+        }
+
+        // a single block, so the data must fit into one: at most 245 bytes with a 2048 bit key
         let start = Instant::now();
         let _ = rsa_pubkey.public_encrypt(&data, &mut rsa_cypher, Padding::PKCS1)
             .expect("RSA encryption failed");
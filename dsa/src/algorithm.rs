@@ -0,0 +1,117 @@
+// Signature algorithms next to classic DSA, all driven through PKey so signing
+// and serialization stay the same whatever the key
+use crate::signature::Digest;
+use clap::ValueEnum;
+use openssl::dsa::Dsa;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::error::ErrorStack;
+use openssl::nid::Nid;
+use openssl::pkey::{HasPublic, Id, PKey, PKeyRef, Private};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+    /// FIPS 186-4 DSA, legacy in OpenSSL 3
+    Dsa,
+    /// ECDSA over NIST P-256
+    EcdsaP256,
+    /// ECDSA over NIST P-384
+    EcdsaP384,
+    /// ECDSA over secp256k1
+    EcdsaSecp256k1,
+    /// Ed25519 (RFC 8032), hashes the message itself
+    Ed25519,
+    /// Ed448 (RFC 8032), hashes the message itself
+    Ed448,
+}
+
+impl Algorithm {
+    /// Named curve of the ECDSA algorithms.
+    pub fn curve(self) -> Option<Nid> {
+        match self {
+            Algorithm::EcdsaP256 => Some(Nid::X9_62_PRIME256V1),
+            Algorithm::EcdsaP384 => Some(Nid::SECP384R1),
+            Algorithm::EcdsaSecp256k1 => Some(Nid::SECP256K1),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Dsa => "DSA",
+            Algorithm::EcdsaP256 => "ECDSA P-256",
+            Algorithm::EcdsaP384 => "ECDSA P-384",
+            Algorithm::EcdsaSecp256k1 => "ECDSA secp256k1",
+            Algorithm::Ed25519 => "Ed25519",
+            Algorithm::Ed448 => "Ed448",
+        }
+    }
+
+    /// EdDSA signs the message itself, there's no digest to choose.
+    pub fn is_eddsa(self) -> bool {
+        matches!(self, Algorithm::Ed25519 | Algorithm::Ed448)
+    }
+
+    /// Digest signing uses unless told otherwise, as long as the group order.
+    pub fn default_digest(self) -> Option<Digest> {
+        match self {
+            Algorithm::Dsa | Algorithm::EcdsaP256 | Algorithm::EcdsaSecp256k1 => {
+                Some(Digest::Sha256)
+            }
+            Algorithm::EcdsaP384 => Some(Digest::Sha384),
+            Algorithm::Ed25519 | Algorithm::Ed448 => None,
+        }
+    }
+
+    /// New key pair, `bits` is the size of a DSA key and ignored otherwise.
+    pub fn generate(self, bits: u32) -> Result<PKey<Private>, ErrorStack> {
+        match self {
+            Algorithm::Dsa => PKey::from_dsa(Dsa::generate(bits)?),
+            Algorithm::Ed25519 => PKey::generate_ed25519(),
+            Algorithm::Ed448 => PKey::generate_ed448(),
+            _ => {
+                let group = EcGroup::from_curve_name(self.curve().unwrap())?;
+                PKey::from_ec_key(EcKey::generate(&group)?)
+            }
+        }
+    }
+
+    /// Algorithm of `key`, none for keys this tool doesn't sign with.
+    pub fn of_key<T: HasPublic>(key: &PKeyRef<T>) -> Option<Algorithm> {
+        match key.id() {
+            Id::DSA => Some(Algorithm::Dsa),
+            Id::ED25519 => Some(Algorithm::Ed25519),
+            Id::ED448 => Some(Algorithm::Ed448),
+            Id::EC => {
+                let curve = key.ec_key().ok()?.group().curve_name()?;
+                [
+                    Algorithm::EcdsaP256,
+                    Algorithm::EcdsaP384,
+                    Algorithm::EcdsaSecp256k1,
+                ]
+                .into_iter()
+                .find(|algorithm| algorithm.curve() == Some(curve))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_generated_keys_are_detected() {
+        for algorithm in Algorithm::value_variants() {
+            let key = algorithm.generate(1024).unwrap();
+            assert_eq!(Algorithm::of_key(&key), Some(*algorithm));
+
+            let public = PKey::public_key_from_pem(&key.public_key_to_pem().unwrap()).unwrap();
+            assert_eq!(Algorithm::of_key(&public), Some(*algorithm));
+        }
+
+        let rsa = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        assert_eq!(Algorithm::of_key(&rsa), None);
+    }
+}
@@ -0,0 +1,225 @@
+// Key recovery from the signatures this tool makes when their nonces are
+// flawed, for demonstration only. dsa_attacks works on r, s and the digest
+// modulo the group order, here they come out of signature files and the
+// candidates it finds are checked against the public key. `flawed_signatures`
+// makes something to attack by signing the way a broken implementation would.
+use crate::deterministic::{number, NonceSigner};
+use crate::signature::{Digest, Envelope, Error};
+use clap::ValueEnum;
+use dsa_attacks::Sample;
+use dsa_core::key::bits_to_int;
+use num_bigint::BigUint;
+use openssl::bn::{BigNum, BigNumContext};
+use openssl::dsa::Dsa;
+use openssl::ec::{EcKey, EcPoint};
+use openssl::hash::hash;
+use openssl::pkey::{HasPrivate, Id, PKey, PKeyRef, Private, Public};
+use rsa_core::prime::random_range;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Nonce {
+    /// One k for every signature
+    Reused,
+    /// Fresh k with its leading bits zero
+    Biased,
+}
+
+enum Kind {
+    Dsa(Dsa<Public>),
+    Ecdsa(EcKey<Public>),
+}
+
+fn is_ec_key(ec: &EcKey<Public>, x: &BigUint) -> Result<bool, Error> {
+    let group = ec.group();
+    let mut ctx = BigNumContext::new()?;
+    let mut point = EcPoint::new(group)?;
+    let scalar = BigNum::from_slice(&x.to_bytes_be())?;
+    point.mul_generator2(group, &scalar, &mut ctx)?;
+
+    Ok(point.eq(group, ec.public_key(), &mut ctx)?)
+}
+
+/// Public key the recovered x has to match.
+pub struct Target {
+    kind: Kind,
+    q: BigUint,
+}
+
+impl Target {
+    pub fn new(key: &PKeyRef<Public>) -> Result<Self, Error> {
+        match key.id() {
+            Id::DSA => {
+                let dsa = key.dsa()?;
+                Ok(Target {
+                    q: number(dsa.q()),
+                    kind: Kind::Dsa(dsa),
+                })
+            }
+            Id::EC => {
+                let ec = key.ec_key()?;
+                let (mut order, mut ctx) = (BigNum::new()?, BigNumContext::new()?);
+                ec.group().order(&mut order, &mut ctx)?;
+                Ok(Target {
+                    q: number(&order),
+                    kind: Kind::Ecdsa(ec),
+                })
+            }
+            _ => Err(Error::UnsupportedKey),
+        }
+    }
+
+    pub fn order(&self) -> &BigUint {
+        &self.q
+    }
+
+    /// Whether `x` is the private half, y = g^x mod p or Q = x * G.
+    pub fn is_key(&self, x: &BigUint) -> bool {
+        match &self.kind {
+            Kind::Dsa(dsa) => {
+                let (p, g) = (number(dsa.p()), number(dsa.g()));
+                g.modpow(x, &p) == number(dsa.pub_key())
+            }
+            Kind::Ecdsa(ec) => is_ec_key(ec, x).unwrap_or(false),
+        }
+    }
+
+    /// r, s and the digest of `data` as a number, out of its signature.
+    pub fn sample(&self, data: &[u8], envelope: &Envelope) -> Result<Sample, Error> {
+        let digest = envelope.digest.ok_or(Error::InvalidEnvelope(
+            "no digest, EdDSA has no nonce to attack",
+        ))?;
+        let signature = dsa_core::Signature::from_der(&envelope.signature)?;
+        let digest = hash(digest.message_digest(), data)?;
+
+        Ok(Sample {
+            r: signature.r,
+            s: signature.s,
+            z: bits_to_int(&digest, &self.q),
+        })
+    }
+
+    /// Private key of `x` next to the public one.
+    pub fn private_key(&self, x: &BigUint) -> Result<PKey<Private>, Error> {
+        let x = BigNum::from_slice(&x.to_bytes_be())?;
+
+        Ok(match &self.kind {
+            Kind::Dsa(dsa) => PKey::from_dsa(Dsa::from_private_components(
+                dsa.p().to_owned()?,
+                dsa.q().to_owned()?,
+                dsa.g().to_owned()?,
+                x,
+                dsa.pub_key().to_owned()?,
+            )?)?,
+            Kind::Ecdsa(ec) => PKey::from_ec_key(EcKey::from_private_components(
+                ec.group(),
+                &x,
+                ec.public_key(),
+            )?)?,
+        })
+    }
+}
+
+/// `count` messages with their signatures, all nonces reused or drawn with
+/// their `zero_bits` leading bits zero.
+pub fn flawed_signatures<T: HasPrivate>(
+    key: &PKeyRef<T>,
+    digest: Digest,
+    count: usize,
+    nonce: Nonce,
+    zero_bits: u32,
+) -> Result<Vec<(Vec<u8>, Envelope)>, Error> {
+    let signer = NonceSigner::new(key)?;
+    let q = signer.order();
+    if nonce == Nonce::Biased && zero_bits as u64 >= q.bits() {
+        return Err(dsa_core::Error::InvalidInput("zero bits leave nothing of the nonce").into());
+    }
+
+    let one = BigUint::from(1u8);
+    let bound = match nonce {
+        Nonce::Reused => q.clone(),
+        Nonce::Biased => &one << (q.bits() - zero_bits as u64),
+    };
+    let random = || random_range(&one, &bound).map_err(dsa_core::Error::from);
+    let reused = random()?;
+
+    let mut signatures = Vec::new();
+    while signatures.len() < count {
+        let k = match nonce {
+            Nonce::Reused => reused.clone(),
+            Nonce::Biased => random()?,
+        };
+        let message = format!("Message {}", signatures.len() + 1).into_bytes();
+        let digest_bytes = hash(digest.message_digest(), &message)?;
+
+        if let Some(signature) = signer.sign_with_nonce(&digest_bytes, &k)? {
+            let envelope = Envelope {
+                digest: Some(digest),
+                signature,
+            };
+            signatures.push((message, envelope));
+        }
+    }
+
+    Ok(signatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+    use crate::signature::verify;
+
+    fn target(key: &PKey<Private>) -> Target {
+        let public = PKey::public_key_from_pem(&key.public_key_to_pem().unwrap()).unwrap();
+        Target::new(&public).unwrap()
+    }
+
+    fn samples(target: &Target, signatures: &[(Vec<u8>, Envelope)]) -> Vec<Sample> {
+        signatures
+            .iter()
+            .map(|(message, envelope)| target.sample(message, envelope).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_reused_nonce_recovers_the_key() {
+        for algorithm in [Algorithm::Dsa, Algorithm::EcdsaP256] {
+            let key = algorithm.generate(2048).unwrap();
+            let digest = algorithm.default_digest().unwrap();
+            let signatures = flawed_signatures(&key, digest, 2, Nonce::Reused, 0).unwrap();
+            for (message, envelope) in &signatures {
+                assert!(verify(&key, message, envelope).unwrap());
+            }
+
+            let target = target(&key);
+            let samples = samples(&target, &signatures);
+            let recovery =
+                dsa_attacks::nonce_reuse::attack(target.order(), &samples[0], &samples[1], |x| {
+                    target.is_key(x)
+                })
+                .unwrap();
+
+            let recovered = target.private_key(&recovery.x).unwrap();
+            assert!(recovered.public_eq(&key));
+            let envelope = crate::signature::sign(&recovered, Some(digest), b"forged").unwrap();
+            assert!(verify(&key, b"forged", &envelope).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_biased_nonces_recover_the_key() {
+        let key = Algorithm::EcdsaP256.generate(0).unwrap();
+        let signatures = flawed_signatures(&key, Digest::Sha256, 36, Nonce::Biased, 10).unwrap();
+
+        let target = target(&key);
+        let x = dsa_attacks::biased_nonce::attack(
+            target.order(),
+            &samples(&target, &signatures),
+            10,
+            |x| target.is_key(x),
+        )
+        .unwrap();
+        assert!(target.private_key(&x).unwrap().public_eq(&key));
+        assert!(!target.is_key(&(x + 1u8)));
+    }
+}
@@ -0,0 +1,887 @@
+// RFC 6979 signatures: k comes from dsa_core's HMAC-DRBG over the private key
+// and message digest rather than the random generator. DSA keys sign through
+// dsa_core, ECDSA only borrows OpenSSL's constant time k * G and does the rest
+// over the group order the same way dsa_core does over q.
+use crate::signature::Error;
+use dsa_core::key::bits_to_int;
+use dsa_core::rfc6979::Nonces;
+use dsa_core::{DomainParameters, DsaPrivateKey, HashAlgorithm};
+use num_bigint::BigUint;
+use num_traits::Zero;
+use openssl::bn::{BigNum, BigNumContext, BigNumRef};
+use openssl::ec::{EcKey, EcPoint};
+use openssl::ecdsa::EcdsaSig;
+use openssl::pkey::{HasPrivate, Id, PKeyRef};
+use rsa_core::montgomery::Montgomery;
+
+pub fn number(value: &BigNumRef) -> BigUint {
+    BigUint::from_bytes_be(&value.to_vec())
+}
+
+enum Kind<T> {
+    Dsa(DsaPrivateKey),
+    Ecdsa(EcKey<T>),
+}
+
+/// DSA or ECDSA private key as numbers modulo its group order, signing with
+/// whatever nonce it's handed.
+pub struct NonceSigner<T> {
+    kind: Kind<T>,
+    q: BigUint,
+    x: BigUint,
+}
+
+impl<T: HasPrivate> NonceSigner<T> {
+    pub fn new(key: &PKeyRef<T>) -> Result<Self, Error> {
+        match key.id() {
+            Id::DSA => {
+                let dsa = key.dsa()?;
+                let params =
+                    DomainParameters::new(number(dsa.p()), number(dsa.q()), number(dsa.g()))?;
+                let key = DsaPrivateKey::from_components(
+                    params,
+                    number(dsa.priv_key()),
+                    number(dsa.pub_key()),
+                )?;
+
+                Ok(NonceSigner {
+                    q: key.to_public_key().params().q().clone(),
+                    x: key.x().clone(),
+                    kind: Kind::Dsa(key),
+                })
+            }
+            Id::EC => {
+                let ec = key.ec_key()?;
+                let (mut order, mut ctx) = (BigNum::new()?, BigNumContext::new()?);
+                ec.group().order(&mut order, &mut ctx)?;
+
+                Ok(NonceSigner {
+                    q: number(&order),
+                    x: number(ec.private_key()),
+                    kind: Kind::Ecdsa(ec),
+                })
+            }
+            _ => Err(Error::UnsupportedKey),
+        }
+    }
+
+    /// q for DSA, n for ECDSA.
+    pub fn order(&self) -> &BigUint {
+        &self.q
+    }
+
+    /// DER signature of `digest` with nonce `k`, none when r or s is zero.
+    pub fn sign_with_nonce(&self, digest: &[u8], k: &BigUint) -> Result<Option<Vec<u8>>, Error> {
+        let ec = match &self.kind {
+            Kind::Dsa(key) => return Ok(key.sign_with_nonce(digest, k).map(|s| s.to_der())),
+            Kind::Ecdsa(ec) => ec,
+        };
+
+        // r = x(k * G) mod n and s = k^-1 (z + dr) mod n
+        let group = ec.group();
+        let mut ctx = BigNumContext::new()?;
+        let mut point = EcPoint::new(group)?;
+        let scalar = BigNum::from_slice(&k.to_bytes_be())?;
+        point.mul_generator2(group, &scalar, &mut ctx)?;
+        let (mut x, mut y) = (BigNum::new()?, BigNum::new()?);
+        point.affine_coordinates(group, &mut x, &mut y, &mut ctx)?;
+
+        let (q, n) = (&self.q, self.q.bits() as usize);
+        let r = number(&x) % q;
+        let k_inv = Montgomery::new(q).pow(k, &(q - 2u8), n);
+        let s = k_inv * (bits_to_int(digest, q) + &self.x * &r) % q;
+
+        if r.is_zero() || s.is_zero() {
+            return Ok(None);
+        }
+
+        let signature = EcdsaSig::from_private_components(
+            BigNum::from_slice(&r.to_bytes_be())?,
+            BigNum::from_slice(&s.to_bytes_be())?,
+        )?;
+        Ok(Some(signature.to_der()?))
+    }
+
+    /// DER signature of `digest`, made with `hash`, under the RFC 6979 nonce.
+    pub fn sign_deterministic(&self, hash: HashAlgorithm, digest: &[u8]) -> Result<Vec<u8>, Error> {
+        for k in Nonces::new(hash, &self.q, &self.x, digest) {
+            if let Some(signature) = self.sign_with_nonce(digest, &k)? {
+                return Ok(signature);
+            }
+        }
+        unreachable!("RFC 6979 nonces never run out")
+    }
+}
+
+/// DER signature of `data` under a DSA or ECDSA `key`, hashed with `hash`.
+pub fn sign<T: HasPrivate>(
+    key: &PKeyRef<T>,
+    hash: HashAlgorithm,
+    data: &[u8],
+) -> Result<Vec<u8>, Error> {
+    NonceSigner::new(key)?.sign_deterministic(hash, &hash.digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::Algorithm;
+    use crate::signature::{self, Digest};
+    use clap::ValueEnum;
+    use openssl::ec::{EcGroup, EcKey};
+    use openssl::nid::Nid;
+    use openssl::pkey::{PKey, Private};
+
+    // RFC 6979 appendix A.2.3 to A.2.7, A.2.8 and A.2.13 curves and private
+    // keys. The other K and B curves sign the same way, OpenSSL does the point
+    // arithmetic over binary fields too.
+    type Key = (Nid, &'static str);
+
+    const P192: Key = (
+        Nid::X9_62_PRIME192V1,
+        "6FAB034934E4C0FC9AE67F5B5659A9D7D1FEFD187EE09FD4",
+    );
+    const P224: Key = (
+        Nid::SECP224R1,
+        "F220266E1105BFE3083E03EC7A3A654651F45E37167E88600BF257C1",
+    );
+    const P256: Key = (
+        Nid::X9_62_PRIME256V1,
+        "C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721",
+    );
+    const P384: Key = (
+        Nid::SECP384R1,
+        concat!(
+            "6B9D3DAD2E1B8C1C05B19875B6659F4DE23C3B667BF297BA",
+            "9AA47740787137D896D5724E4C70A825F872C9EA60D2EDF5"
+        ),
+    );
+    const P521: Key = (
+        Nid::SECP521R1,
+        concat!(
+            "0FAD06DAA62BA3B25D2FB40133DA757205DE67F5BB0018FEE8C86E1B68C7E75CAA",
+            "896EB32F1F47C70855836A6D16FCC1466F6D8FBEC67DB89EC0C08B0E996B83538"
+        ),
+    );
+    const K163: Key = (Nid::SECT163K1, "09A4D6792295A7F730FC3F2B49CBC0F62E862272F");
+    const B163: Key = (Nid::SECT163R2, "35318FC447D48D7E6BC93B48617DDDEDF26AA658F");
+
+    // key, digest, message, r, s
+    const RFC6979_SIGNATURES: [(&Key, HashAlgorithm, &str, &str, &str); 70] = [
+        (
+            &P192,
+            HashAlgorithm::Sha1,
+            "sample",
+            "98C6BD12B23EAF5E2A2045132086BE3EB8EBD62ABF6698FF",
+            "57A22B07DEA9530F8DE9471B1DC6624472E8E2844BC25B64",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha1,
+            "test",
+            "F2141A0EBBC44D2E1AF90A50EBCFCE5E197B3B7D4DE036D",
+            "EB18BC9E1F3D7387500CB99CF5F7C157070A8961E38700B7",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha224,
+            "sample",
+            "A1F00DAD97AEEC91C95585F36200C65F3C01812AA60378F5",
+            "E07EC1304C7C6C9DEBBE980B9692668F81D4DE7922A0F97A",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha224,
+            "test",
+            "6945A1C1D1B2206B8145548F633BB61CEF04891BAF26ED34",
+            "B7FB7FDFC339C0B9BD61A9F5A8EAF9BE58FC5CBA2CB15293",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha256,
+            "sample",
+            "4B0B8CE98A92866A2820E20AA6B75B56382E0F9BFD5ECB55",
+            "CCDB006926EA9565CBADC840829D8C384E06DE1F1E381B85",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha256,
+            "test",
+            "3A718BD8B4926C3B52EE6BBE67EF79B18CB6EB62B1AD97AE",
+            "5662E6848A4A19B1F1AE2F72ACD4B8BBE50F1EAC65D9124F",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha384,
+            "sample",
+            "DA63BF0B9ABCF948FBB1E9167F136145F7A20426DCC287D5",
+            "C3AA2C960972BD7A2003A57E1C4C77F0578F8AE95E31EC5E",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha384,
+            "test",
+            "B234B60B4DB75A733E19280A7A6034BD6B1EE88AF5332367",
+            "7994090B2D59BB782BE57E74A44C9A1C700413F8ABEFE77A",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha512,
+            "sample",
+            "4D60C5AB1996BD848343B31C00850205E2EA6922DAC2E4B8",
+            "3F6E837448F027A1BF4B34E796E32A811CBB4050908D8F67",
+        ),
+        (
+            &P192,
+            HashAlgorithm::Sha512,
+            "test",
+            "FE4F4AE86A58B6507946715934FE2D8FF9D95B6B098FE739",
+            "74CF5605C98FBA0E1EF34D4B5A1577A7DCF59457CAE52290",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha1,
+            "sample",
+            "22226F9D40A96E19C4A301CE5B74B115303C0F3A4FD30FC257FB57AC",
+            "66D1CDD83E3AF75605DD6E2FEFF196D30AA7ED7A2EDF7AF475403D69",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha1,
+            "test",
+            "DEAA646EC2AF2EA8AD53ED66B2E2DDAA49A12EFD8356561451F3E21C",
+            "95987796F6CF2062AB8135271DE56AE55366C045F6D9593F53787BD2",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha224,
+            "sample",
+            "1CDFE6662DDE1E4A1EC4CDEDF6A1F5A2FB7FBD9145C12113E6ABFD3E",
+            "A6694FD7718A21053F225D3F46197CA699D45006C06F871808F43EBC",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha224,
+            "test",
+            "C441CE8E261DED634E4CF84910E4C5D1D22C5CF3B732BB204DBEF019",
+            "902F42847A63BDC5F6046ADA114953120F99442D76510150F372A3F4",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha256,
+            "sample",
+            "61AA3DA010E8E8406C656BC477A7A7189895E7E840CDFE8FF42307BA",
+            "BC814050DAB5D23770879494F9E0A680DC1AF7161991BDE692B10101",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha256,
+            "test",
+            "AD04DDE87B84747A243A631EA47A1BA6D1FAA059149AD2440DE6FBA6",
+            "178D49B1AE90E3D8B629BE3DB5683915F4E8C99FDF6E666CF37ADCFD",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha384,
+            "sample",
+            "B115E5E36F0F9EC81F1325A5952878D745E19D7BB3EABFABA77E953",
+            "830F34CCDFE826CCFDC81EB4129772E20E122348A2BBD889A1B1AF1D",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha384,
+            "test",
+            "389B92682E399B26518A95506B52C03BC9379A9DADF3391A21FB0EA4",
+            "414A718ED3249FF6DBC5B50C27F71F01F070944DA22AB1F78F559AAB",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha512,
+            "sample",
+            "74BD1D979D5F32BF958DDC61E4FB4872ADCAFEB2256497CDAC30397",
+            "A4CECA196C3D5A1FF31027B33185DC8EE43F288B21AB342E5D8EB084",
+        ),
+        (
+            &P224,
+            HashAlgorithm::Sha512,
+            "test",
+            "49F050477C5ADD858CAC56208394B5A55BAEBBE887FDF765047C17C",
+            "77EB13E7005929CEFA3CD0403C7CDCC077ADF4E44F3C41B2F60ECFF",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha1,
+            "sample",
+            "61340C88C3AAEBEB4F6D667F672CA9759A6CCAA9FA8811313039EE4A35471D32",
+            "6D7F147DAC089441BB2E2FE8F7A3FA264B9C475098FDCF6E00D7C996E1B8B7EB",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha1,
+            "test",
+            "0CBCC86FD6ABD1D99E703E1EC50069EE5C0B4BA4B9AC60E409E8EC5910D81A89",
+            "01B9D7B73DFAA60D5651EC4591A0136F87653E0FD780C3B1BC872FFDEAE479B1",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha224,
+            "sample",
+            "53B2FFF5D1752B2C689DF257C04C40A587FABABB3F6FC2702F1343AF7CA9AA3F",
+            "B9AFB64FDC03DC1A131C7D2386D11E349F070AA432A4ACC918BEA988BF75C74C",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha224,
+            "test",
+            "C37EDB6F0AE79D47C3C27E962FA269BB4F441770357E114EE511F662EC34A692",
+            "C820053A05791E521FCAAD6042D40AEA1D6B1A540138558F47D0719800E18F2D",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha256,
+            "sample",
+            "EFD48B2AACB6A8FD1140DD9CD45E81D69D2C877B56AAF991C34D0EA84EAF3716",
+            "F7CB1C942D657C41D436C7A1B6E29F65F3E900DBB9AFF4064DC4AB2F843ACDA8",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha256,
+            "test",
+            "F1ABB023518351CD71D881567B1EA663ED3EFCF6C5132B354F28D3B0B7D38367",
+            "019F4113742A2B14BD25926B49C649155F267E60D3814B4C0CC84250E46F0083",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha384,
+            "sample",
+            "0EAFEA039B20E9B42309FB1D89E213057CBF973DC0CFC8F129EDDDC800EF7719",
+            "4861F0491E6998B9455193E34E7B0D284DDD7149A74B95B9261F13ABDE940954",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha384,
+            "test",
+            "83910E8B48BB0C74244EBDF7F07A1C5413D61472BD941EF3920E623FBCCEBEB6",
+            "8DDBEC54CF8CD5874883841D712142A56A8D0F218F5003CB0296B6B509619F2C",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha512,
+            "sample",
+            "8496A60B5E9B47C825488827E0495B0E3FA109EC4568FD3F8D1097678EB97F00",
+            "2362AB1ADBE2B8ADF9CB9EDAB740EA6049C028114F2460F96554F61FAE3302FE",
+        ),
+        (
+            &P256,
+            HashAlgorithm::Sha512,
+            "test",
+            "461D93F31B6540894788FD206C07CFA0CC35F46FA3C91816FFF1040AD1581A04",
+            "39AF9F15DE0DB8D97E72719C74820D304CE5226E32DEDAE67519E840D1194E55",
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha1,
+            "sample",
+            concat!(
+                "EC748D839243D6FBEF4FC5C4859A7DFFD7F3ABDDF7201454",
+                "0C16D73309834FA37B9BA002899F6FDA3A4A9386790D4EB2"
+            ),
+            concat!(
+                "A3BCFA947BEEF4732BF247AC17F71676CB31A847B9FF0CBC",
+                "9C9ED4C1A5B3FACF26F49CA031D4857570CCB5CA4424A443"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha1,
+            "test",
+            concat!(
+                "4BC35D3A50EF4E30576F58CD96CE6BF638025EE624004A1F",
+                "7789A8B8E43D0678ACD9D29876DAF46638645F7F404B11C7"
+            ),
+            concat!(
+                "D5A6326C494ED3FF614703878961C0FDE7B2C278F9A65FD8",
+                "C4B7186201A2991695BA1C84541327E966FA7B50F7382282"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha224,
+            "sample",
+            concat!(
+                "42356E76B55A6D9B4631C865445DBE54E056D3B3431766D0",
+                "509244793C3F9366450F76EE3DE43F5A125333A6BE060122"
+            ),
+            concat!(
+                "9DA0C81787064021E78DF658F2FBB0B042BF304665DB721F",
+                "077A4298B095E4834C082C03D83028EFBF93A3C23940CA8D"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha224,
+            "test",
+            concat!(
+                "E8C9D0B6EA72A0E7837FEA1D14A1A9557F29FAA45D3E7EE8",
+                "88FC5BF954B5E62464A9A817C47FF78B8C11066B24080E72"
+            ),
+            concat!(
+                "07041D4A7A0379AC7232FF72E6F77B6DDB8F09B16CCE0EC3",
+                "286B2BD43FA8C6141C53EA5ABEF0D8231077A04540A96B66"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha256,
+            "sample",
+            concat!(
+                "21B13D1E013C7FA1392D03C5F99AF8B30C570C6F98D4EA8E",
+                "354B63A21D3DAA33BDE1E888E63355D92FA2B3C36D8FB2CD"
+            ),
+            concat!(
+                "F3AA443FB107745BF4BD77CB3891674632068A10CA67E3D4",
+                "5DB2266FA7D1FEEBEFDC63ECCD1AC42EC0CB8668A4FA0AB0"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha256,
+            "test",
+            concat!(
+                "6D6DEFAC9AB64DABAFE36C6BF510352A4CC27001263638E5",
+                "B16D9BB51D451559F918EEDAF2293BE5B475CC8F0188636B"
+            ),
+            concat!(
+                "2D46F3BECBCC523D5F1A1256BF0C9B024D879BA9E838144C",
+                "8BA6BAEB4B53B47D51AB373F9845C0514EEFB14024787265"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha384,
+            "sample",
+            concat!(
+                "94EDBB92A5ECB8AAD4736E56C691916B3F88140666CE9FA7",
+                "3D64C4EA95AD133C81A648152E44ACF96E36DD1E80FABE46"
+            ),
+            concat!(
+                "99EF4AEB15F178CEA1FE40DB2603138F130E740A19624526",
+                "203B6351D0A3A94FA329C145786E679E7B82C71A38628AC8"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha384,
+            "test",
+            concat!(
+                "8203B63D3C853E8D77227FB377BCF7B7B772E97892A80F36",
+                "AB775D509D7A5FEB0542A7F0812998DA8F1DD3CA3CF023DB"
+            ),
+            concat!(
+                "DDD0760448D42D8A43AF45AF836FCE4DE8BE06B485E9B61B",
+                "827C2F13173923E06A739F040649A667BF3B828246BAA5A5"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha512,
+            "sample",
+            concat!(
+                "ED0959D5880AB2D869AE7F6C2915C6D60F96507F9CB3E047",
+                "C0046861DA4A799CFE30F35CC900056D7C99CD7882433709"
+            ),
+            concat!(
+                "512C8CCEEE3890A84058CE1E22DBC2198F42323CE8ACA913",
+                "5329F03C068E5112DC7CC3EF3446DEFCEB01A45C2667FDD5"
+            ),
+        ),
+        (
+            &P384,
+            HashAlgorithm::Sha512,
+            "test",
+            concat!(
+                "A0D5D090C9980FAF3C2CE57B7AE951D31977DD11C775D314",
+                "AF55F76C676447D06FB6495CD21B4B6E340FC236584FB277"
+            ),
+            concat!(
+                "976984E59B4C77B0E8E4460DCA3D9F20E07B9BB1F63BEEFA",
+                "F576F6B2E8B224634A2092CD3792E0159AD9CEE37659C736"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha1,
+            "sample",
+            concat!(
+                "343B6EC45728975EA5CBA6659BBB6062A5FF89EEA58BE3C80B619F322C87910FE",
+                "092F7D45BB0F8EEE01ED3F20BABEC079D202AE677B243AB40B5431D497C55D75D"
+            ),
+            concat!(
+                "E7B0E675A9B24413D448B8CC119D2BF7B2D2DF032741C096634D6D65D0DBE3D56",
+                "94625FB9E8104D3B842C1B0E2D0B98BEA19341E8676AEF66AE4EBA3D5475D5D16"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha1,
+            "test",
+            concat!(
+                "13BAD9F29ABE20DE37EBEB823C252CA0F63361284015A3BF430A46AAA80B87B069",
+                "3F0694BD88AFE4E661FC33B094CD3B7963BED5A727ED8BD6A3A202ABE009D0367"
+            ),
+            concat!(
+                "1E9BB81FF7944CA409AD138DBBEE228E1AFCC0C890FC78EC8604639CB0DBDC90F7",
+                "17A99EAD9D272855D00162EE9527567DD6A92CBD629805C0445282BBC916797FF"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha224,
+            "sample",
+            concat!(
+                "1776331CFCDF927D666E032E00CF776187BC9FDD8E69D0DABB4109FFE1B5E2A307",
+                "15F4CC923A4A5E94D2503E9ACFED92857B7F31D7152E0F8C00C15FF3D87E2ED2E"
+            ),
+            concat!(
+                "50CB5265417FE2320BBB5A122B8E1A32BD699089851128E360E620A30C7E17BA4",
+                "1A666AF126CE100E5799B153B60528D5300D08489CA9178FB610A2006C254B41F"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha224,
+            "test",
+            concat!(
+                "1C7ED902E123E6815546065A2C4AF977B22AA8EADDB68B2C1110E7EA44D42086BF",
+                "E4A34B67DDC0E17E96536E358219B23A706C6A6E16BA77B65E1C595D43CAE17FB"
+            ),
+            concat!(
+                "177336676304FCB343CE028B38E7B4FBA76C1C1B277DA18CAD2A8478B2A9A9F5BE",
+                "C0F3BA04F35DB3E4263569EC6AADE8C92746E4C82F8299AE1B8F1739F8FD519A4"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha256,
+            "sample",
+            concat!(
+                "1511BB4D675114FE266FC4372B87682BAECC01D3CC62CF2303C92B3526012659D1",
+                "6876E25C7C1E57648F23B73564D67F61C6F14D527D54972810421E7D87589E1A7"
+            ),
+            concat!(
+                "4A171143A83163D6DF460AAF61522695F207A58B95C0644D87E52AA1A347916E4",
+                "F7A72930B1BC06DBE22CE3F58264AFD23704CBB63B29B931F7DE6C9D949A7ECFC"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha256,
+            "test",
+            concat!(
+                "E871C4A14F993C6C7369501900C4BC1E9C7B0B4BA44E04868B30B41D8071042EB",
+                "28C4C250411D0CE08CD197E4188EA4876F279F90B3D8D74A3C76E6F1E4656AA8"
+            ),
+            concat!(
+                "CD52DBAA33B063C3A6CD8058A1FB0A46A4754B034FCC644766CA14DA8CA5CA9FD",
+                "E00E88C1AD60CCBA759025299079D7A427EC3CC5B619BFBC828E7769BCD694E86"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha384,
+            "sample",
+            concat!(
+                "1EA842A0E17D2DE4F92C15315C63DDF72685C18195C2BB95E572B9C5136CA4B4B5",
+                "76AD712A52BE9730627D16054BA40CC0B8D3FF035B12AE75168397F5D50C67451"
+            ),
+            concat!(
+                "1F21A3CEE066E1961025FB048BD5FE2B7924D0CD797BABE0A83B66F1E35EEAF5FD",
+                "E143FA85DC394A7DEE766523393784484BDF3E00114A1C857CDE1AA203DB65D61"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha384,
+            "test",
+            concat!(
+                "14BEE21A18B6D8B3C93FAB08D43E739707953244FDBE924FA926D76669E7AC8C89",
+                "DF62ED8975C2D8397A65A49DCC09F6B0AC62272741924D479354D74FF6075578C"
+            ),
+            concat!(
+                "133330865C067A0EAF72362A65E2D7BC4E461E8C8995C3B6226A21BD1AA78F0ED9",
+                "4FE536A0DCA35534F0CD1510C41525D163FE9D74D134881E35141ED5E8E95B979"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha512,
+            "sample",
+            concat!(
+                "C328FAFCBD79DD77850370C46325D987CB525569FB63C5D3BC53950E6D4C5F174",
+                "E25A1EE9017B5D450606ADD152B534931D7D4E8455CC91F9B15BF05EC36E377FA"
+            ),
+            concat!(
+                "617CCE7CF5064806C467F678D3B4080D6F1CC50AF26CA209417308281B68AF282",
+                "623EAA63E5B5C0723D8B8C37FF0777B1A20F8CCB1DCCC43997F1EE0E44DA4A67A"
+            ),
+        ),
+        (
+            &P521,
+            HashAlgorithm::Sha512,
+            "test",
+            concat!(
+                "13E99020ABF5CEE7525D16B69B229652AB6BDF2AFFCAEF38773B4B7D08725F10CD",
+                "B93482FDCC54EDCEE91ECA4166B2A7C6265EF0CE2BD7051B7CEF945BABD47EE6D"
+            ),
+            concat!(
+                "1FBD0013C674AA79CB39849527916CE301C66EA7CE8B80682786AD60F98F7E78A1",
+                "9CA69EFF5C57400E3B3A0AD66CE0978214D13BAF4E9AC60752F7B155E2DE4DCE3"
+            ),
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha1,
+            "sample",
+            "30C45B80BA0E1406C4EFBBB7000D6DE4FA465D505",
+            "38D87DF89493522FC4CD7DE1553BD9DBBA2123011",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha1,
+            "test",
+            "1375BEF93F21582F601497036A7DC8014A99C2B79",
+            "254B7F1472FFFEE9002D081BB8CE819CCE6E687F9",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha224,
+            "sample",
+            "38A2749F7EA13BD5DA0C76C842F512D5A65FFAF32",
+            "64F841F70112B793FD773F5606BFA5AC2A04C1E8",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha224,
+            "test",
+            "110F17EF209957214E35E8C2E83CBE73B3BFDEE2C",
+            "57D5022392D359851B95DEC2444012502A5349CB",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha256,
+            "sample",
+            "113A63990598A3828C407C0F4D2438D990DF99A7F",
+            "1313A2E03F5412DDB296A22E2C455335545672D9F",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha256,
+            "test",
+            "354D5CD24F9C41F85D02E856FA2B0001C83AF53E",
+            "20B200677731CD4FE48612A92F72A19853A82B65",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha384,
+            "sample",
+            "34D4DE955871BB84FEA4E7D068BA5E9A11BD8B6C4",
+            "2BAAF4D4FD57F175C405A2F39F9755D9045C820BD",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha384,
+            "test",
+            "11B6A84206515495AD8DBB2E5785D6D018D75817E",
+            "1A7D4C1E17D4030A5D748ADEA785C77A54581F6D0",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha512,
+            "sample",
+            "38E487F218D696A7323B891F0CCF055D895B77ADC",
+            "972D7721093F9B3835A5EB7F0442FA8DCAA873C4",
+        ),
+        (
+            &K163,
+            HashAlgorithm::Sha512,
+            "test",
+            "148934745B351F6367FF5BB56B1848A2F508902A9",
+            "36214B19444FAB504DBA61D4D6FF2D2F9640F4837",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha1,
+            "sample",
+            "153FEBD179A69B6122DEBF5BC61EB947B24C93526",
+            "37AC9C670F8CF18045049BAE7DD35553545C19E49",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha1,
+            "test",
+            "256D4079C6C7169B8BC92529D701776A269D56308",
+            "341D3FFEC9F1EB6A6ACBE88E3C86A1C8FDEB8B8E1",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha224,
+            "sample",
+            "A379E69C44F9C16EA3215EA39EB1A9B5D58CC955",
+            "4BAFF5308DA2A7FE2C1742769265AD3ED1D24E74",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha224,
+            "test",
+            "28ECC6F1272CE80EA59DCF32F7AC2D861BA803393",
+            "AD4AE2C06E60183C1567D2B82F19421FE3053CE2",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha256,
+            "sample",
+            "134E00F78FC1CB9501675D91C401DE20DDF228CDC",
+            "373273AEC6C36CB7BAFBB1903A5F5EA6A1D50B624",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha256,
+            "test",
+            "227DF377B3FA50F90C1CB3CDCBBDBA552C1D35104",
+            "1F7BEAD92583FE920D353F368C1960D0E88B46A56",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha384,
+            "sample",
+            "29430B935AF8E77519B0CA4F6903B0B82E6A21A66",
+            "1EA1415306E9353FA5AA54BC7C2581DFBB888440D",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha384,
+            "test",
+            "11811DAFEEA441845B6118A0DFEE8A0061231337D",
+            "36258301865EE48C5C6F91D63F62695002AB55B57",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha512,
+            "sample",
+            "B2F177A99F9DF2D51CCAF55F015F326E4B65E7A0",
+            "DF1FB4487E9B120C5E970EFE48F55E406306C3A1",
+        ),
+        (
+            &B163,
+            HashAlgorithm::Sha512,
+            "test",
+            "3B6BB95CA823BE2ED8E3972FF516EB8972D765571",
+            "13DC6F420628969DF900C3FCC48220B38BE24A541",
+        ),
+    ];
+
+    // Private keys 1 and n - 1 with SHA-256, r and s match the bitcoin test
+    // vectors once s is folded to n - s, which this signer doesn't do
+    const SECP256K1: [Key; 2] = [
+        (Nid::SECP256K1, "01"),
+        (
+            Nid::SECP256K1,
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364140",
+        ),
+    ];
+    const SECP256K1_SIGNATURES: [(&Key, HashAlgorithm, &str, &str, &str); 3] = [
+        (
+            &SECP256K1[0],
+            HashAlgorithm::Sha256,
+            "Satoshi Nakamoto",
+            "934B1EA10A4B3C1757E2B0C017D0B6143CE3C9A7E6A4A49860D7A6AB210EE3D8",
+            "DBBD3162D46E9F9BEF7FEB87C16DC13B4F6568A87F4E83F728E2443BA586675C",
+        ),
+        (
+            &SECP256K1[0],
+            HashAlgorithm::Sha256,
+            "All those moments will be lost in time, like tears in rain. Time to die...",
+            "8600DBD41E348FE5C9465AB92D23E3DB8B98B873BEECD930736488696438CB6B",
+            "AB8019BBD8B6924CC4099FE625340FFB1EAAC34BF4477DAA39D0835429094520",
+        ),
+        (
+            &SECP256K1[1],
+            HashAlgorithm::Sha256,
+            "Satoshi Nakamoto",
+            "FD567D121DB66E382991534ADA77A6BD3106F0A1098C231E47993447CD6AF2D0",
+            "94C632F14E4379FC1EA610A3DF5A375152549736425EE17CEBE10ABBC2A2826C",
+        ),
+    ];
+
+    fn ec_key((curve, private): &Key) -> PKey<Private> {
+        let group = EcGroup::from_curve_name(*curve).unwrap();
+        let private = BigNum::from_hex_str(private).unwrap();
+        let mut public = EcPoint::new(&group).unwrap();
+        let mut ctx = BigNumContext::new().unwrap();
+        public.mul_generator2(&group, &private, &mut ctx).unwrap();
+
+        PKey::from_ec_key(EcKey::from_private_components(&group, &private, &public).unwrap())
+            .unwrap()
+    }
+
+    fn check_signatures(signatures: &[(&Key, HashAlgorithm, &str, &str, &str)]) {
+        for &(key, hash, message, r, s) in signatures {
+            let key = ec_key(key);
+            let signature =
+                EcdsaSig::from_der(&sign(&key, hash, message.as_bytes()).unwrap()).unwrap();
+
+            assert_eq!(
+                (signature.r(), signature.s()),
+                (
+                    &*BigNum::from_hex_str(r).unwrap(),
+                    &*BigNum::from_hex_str(s).unwrap()
+                ),
+                "{:?} {:?}",
+                hash,
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_rfc6979_ecdsa_signatures() {
+        check_signatures(&RFC6979_SIGNATURES);
+    }
+
+    #[test]
+    fn test_secp256k1_signatures() {
+        check_signatures(&SECP256K1_SIGNATURES);
+    }
+
+    #[test]
+    fn test_signatures_repeat_and_verify() {
+        let data = b"Some data to sign";
+
+        for &algorithm in Algorithm::value_variants() {
+            let key = algorithm.generate(2048).unwrap();
+            let digest = algorithm.default_digest();
+
+            let envelope = signature::sign_deterministic(&key, digest, data).unwrap();
+            assert_eq!(
+                envelope,
+                signature::sign_deterministic(&key, digest, data).unwrap()
+            );
+            assert!(signature::verify(&key, data, &envelope).unwrap());
+            assert!(!signature::verify(&key, b"Other data", &envelope).unwrap());
+        }
+
+        for algorithm in [Algorithm::Dsa, Algorithm::EcdsaP256] {
+            let key = algorithm.generate(2048).unwrap();
+            let digest = Some(Digest::Sha3_256);
+
+            let envelope = signature::sign_deterministic(&key, digest, data).unwrap();
+            assert_eq!(
+                envelope,
+                signature::sign_deterministic(&key, digest, data).unwrap()
+            );
+            assert!(signature::verify(&key, data, &envelope).unwrap());
+        }
+        let ed25519 = Algorithm::Ed25519.generate(0).unwrap();
+        assert!(sign(&ed25519, HashAlgorithm::Sha256, data).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use openssl::bn::BigNumRef;
+use openssl::error::ErrorStack;
+use openssl::hash::{hash, MessageDigest};
+use openssl::pkey::{HasPublic, PKeyRef};
+use serde::Serialize;
+
+use crate::algorithm::Algorithm;
+use crate::format::{self, Error};
+use pki::passphrase;
+
+#[derive(Serialize, Debug)]
+pub struct KeyInfo {
+    pub key_type: &'static str,
+    pub private: bool,
+    pub bits: u32,
+    /// Size of the subgroup order q, this and the domain parameters are DSA only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q_bits: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub p: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub g: Option<String>,
+    /// SHA-256 of the DER SubjectPublicKeyInfo
+    pub sha256_fingerprint: String,
+    /// MD5 of the DER SubjectPublicKeyInfo
+    pub md5_fingerprint: String,
+    #[serde(skip)]
+    spki: Vec<u8>,
+}
+
+fn fingerprint(digest: MessageDigest, data: &[u8]) -> Result<String, ErrorStack> {
+    let bytes: Vec<String> = hash(digest, data)?
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    Ok(bytes.join(":"))
+}
+
+fn describe<T: HasPublic>(key: &PKeyRef<T>, private: bool) -> Result<KeyInfo, Error> {
+    let algorithm = Algorithm::of_key(key).ok_or(Error::Unsupported(
+        "key types other than DSA, ECDSA and EdDSA",
+    ))?;
+    let dsa = match algorithm {
+        Algorithm::Dsa => Some(key.dsa()?),
+        _ => None,
+    };
+    let hex = |value: &BigNumRef| -> Result<String, ErrorStack> {
+        Ok(value.to_hex_str()?.to_lowercase())
+    };
+    let spki = key.public_key_to_der()?;
+
+    Ok(KeyInfo {
+        key_type: algorithm.name(),
+        private,
+        bits: key.bits(),
+        q_bits: dsa.as_ref().map(|dsa| dsa.q().num_bits()),
+        p: dsa.as_ref().map(|dsa| hex(dsa.p())).transpose()?,
+        q: dsa.as_ref().map(|dsa| hex(dsa.q())).transpose()?,
+        g: dsa.as_ref().map(|dsa| hex(dsa.g())).transpose()?,
+        sha256_fingerprint: fingerprint(MessageDigest::sha256(), &spki)?,
+        md5_fingerprint: fingerprint(MessageDigest::md5(), &spki)?,
+        spki,
+    })
+}
+
+/// Describes a private or public key in any format [`format`] reads.
+pub fn inspect(data: &[u8], passphrase: Option<&str>) -> Result<KeyInfo, Error> {
+    match format::load_private_key(data, passphrase) {
+        Ok(key) => describe(&key, true),
+        Err(e) if passphrase::is_encrypted(data) => Err(e),
+        Err(_) => {
+            let key = format::load_public_key(data)?;
+            describe(&key, false)
+        }
+    }
+}
+
+impl KeyInfo {
+    /// Whether both keys share the same public half.
+    pub fn matches(&self, other: &KeyInfo) -> bool {
+        self.spki == other.spki
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::dsa::Dsa;
+
+    #[test]
+    fn test_private_and_public_key_match() {
+        let dsa = Dsa::generate(2048).unwrap();
+        let private = inspect(&dsa.private_key_to_pem().unwrap(), None).unwrap();
+        let public = inspect(&dsa.public_key_to_pem().unwrap(), None).unwrap();
+        let other = inspect(
+            &Dsa::generate(2048).unwrap().public_key_to_pem().unwrap(),
+            None,
+        )
+        .unwrap();
+
+        assert!(private.private);
+        assert!(!public.private);
+        assert_eq!(private.bits, 2048);
+        assert_eq!(private.q_bits, Some(256));
+        assert_eq!(private.sha256_fingerprint.len(), 32 * 3 - 1);
+        assert!(private.matches(&public));
+        assert!(!private.matches(&other));
+        assert!(inspect(b"not a key", None).is_err());
+
+        let ec = Algorithm::EcdsaP256.generate(0).unwrap();
+        let info = inspect(&ec.public_key_to_pem().unwrap(), None).unwrap();
+        assert_eq!(info.key_type, "ECDSA P-256");
+        assert_eq!((info.bits, info.q_bits), (256, None));
+    }
+}
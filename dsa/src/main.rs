@@ -1,11 +1,31 @@
 extern crate openssl;
 
+mod algorithm;
+mod attack;
+mod deterministic;
+mod format;
+mod inspect;
+mod params;
+mod signature;
+
+use algorithm::Algorithm;
+use attack::{Nonce, Target};
 use clap::{Args, Parser, Subcommand};
-use openssl::dsa::Dsa;
-use openssl::hash::MessageDigest;
-use openssl::pkey::PKey;
-use openssl::sign::{Signer, Verifier};
+use dsa_attacks::Sample;
+use format::KeyFormat;
+use inspect::KeyInfo;
+use num_bigint::BigUint;
+use openssl::pkey::{HasPublic, PKeyRef};
+use openssl::x509::X509;
+use params::{Method, Parameters};
+use pki::passphrase::{self, Passphrase};
+use pki::x509::{self, Issuer};
+use serde::Serialize;
+use signature::{Digest, Envelope};
 use std::fs;
+use std::path::Path;
+use std::process;
+use zeroize::Zeroizing;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -20,6 +40,14 @@ enum Operation {
     Keys(KeysArgs),
     Sign(CryptoArgs),
     Verify(CryptoArgs),
+    Inspect(InspectArgs),
+    ChangePassphrase(ChangePassphraseArgs),
+    Convert(ConvertArgs),
+    Csr(CsrArgs),
+    Cert(CertArgs),
+    VerifyChain(VerifyChainArgs),
+    Params(ParamsArgs),
+    Attack(AttackArgs),
 }
 
 #[derive(Args, Debug)]
@@ -32,8 +60,276 @@ struct KeysArgs {
     #[arg(short, long, default_value = "")]
     save_path: String,
 
+    /// Signature algorithm of the key pair
+    #[arg(short, long, value_enum, default_value_t = Algorithm::Dsa)]
+    algorithm: Algorithm,
+
+    /// DSA key size, other algorithms have theirs fixed by the curve
     #[arg(short, long, default_value_t = 2048)]
     block_size: u32,
+
+    /// DSA domain parameters file from `params generate` to make the keys over
+    #[arg(long, conflicts_with = "block_size")]
+    params: Option<String>,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+
+    /// Prompt for a passphrase to encrypt the private key with
+    #[arg(long, conflicts_with_all = ["passphrase", "passphrase_file"])]
+    ask_passphrase: bool,
+
+    /// Key file format
+    #[arg(long, value_enum, default_value_t = KeyFormat::PemPkcs1)]
+    format: KeyFormat,
+}
+
+#[derive(Args, Debug)]
+struct PassphraseArgs {
+    /// Private key passphrase, prompted for when an encrypted key is read without one
+    #[arg(long, conflicts_with = "passphrase_file")]
+    passphrase: Option<String>,
+
+    /// File with the private key passphrase on its first line
+    #[arg(long)]
+    passphrase_file: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ChangePassphraseArgs {
+    /// File path to private key, rewritten in place in the same format
+    #[arg(short, long)]
+    keys_path: String,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+
+    /// New passphrase, an empty one stores the key unencrypted
+    #[arg(long, conflicts_with = "new_passphrase_file")]
+    new_passphrase: Option<String>,
+
+    /// File with the new passphrase on its first line
+    #[arg(long)]
+    new_passphrase_file: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ConvertArgs {
+    /// File path to private or public key in any supported format
+    #[arg(short, long)]
+    input_path: String,
+
+    /// File path to save the converted key
+    #[arg(short, long)]
+    output_path: String,
+
+    /// Output key format
+    #[arg(long, value_enum)]
+    format: KeyFormat,
+
+    /// Write only the public half of a private key
+    #[arg(long)]
+    public: bool,
+
+    /// Passphrase of the input key, also used to encrypt the output
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug)]
+struct CsrArgs {
+    /// File path to private key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Path to save the PEM request
+    #[arg(short, long)]
+    output_path: String,
+
+    #[command(flatten)]
+    profile: x509::Profile,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug)]
+struct CertArgs {
+    /// File path to the signing private key: the key itself when self-signing, the CA's key otherwise
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Path to save the PEM certificate
+    #[arg(short, long)]
+    output_path: String,
+
+    /// Request to certify, without it the certificate is self-signed
+    #[arg(short, long, requires = "issuer_path")]
+    request_path: Option<String>,
+
+    /// CA certificate matching the signing key
+    #[arg(short, long, requires = "request_path")]
+    issuer_path: Option<String>,
+
+    #[command(flatten)]
+    profile: x509::Profile,
+
+    #[command(flatten)]
+    validity: x509::Validity,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug)]
+struct VerifyChainArgs {
+    /// PEM certificates, the leaf first and its intermediates after it
+    #[arg(short, long)]
+    chain_path: String,
+
+    /// PEM certificates trusted as roots
+    #[arg(short, long)]
+    anchors_path: String,
+}
+
+#[derive(Args, Debug)]
+struct ParamsArgs {
+    #[command(subcommand)]
+    step: ParamsStep,
+}
+
+/// FIPS 186-4 DSA domain parameters, generated and validated without OpenSSL
+#[derive(Subcommand, Debug)]
+enum ParamsStep {
+    /// Generate p, q and g from a new seed and save them with it
+    Generate(GenerateParamsArgs),
+    /// Check parameters against their seed, and a key against the parameters
+    Validate(ValidateParamsArgs),
+}
+
+#[derive(Args, Debug)]
+struct GenerateParamsArgs {
+    /// Path to save the parameters and their seed as JSON
+    #[arg(short, long)]
+    output_path: String,
+
+    /// Bit length L of p
+    #[arg(short = 'L', long, default_value_t = 2048)]
+    p_bits: usize,
+
+    /// Bit length N of q
+    #[arg(short = 'N', long, default_value_t = 256)]
+    q_bits: usize,
+
+    /// Hash the seed is expanded with, at least N bits long
+    #[arg(long, value_enum, default_value_t = Digest::Sha256)]
+    digest: Digest,
+
+    /// How p and q are found
+    #[arg(long, value_enum, default_value_t = Method::Probable)]
+    method: Method,
+
+    /// Index g is derived from the seed with
+    #[arg(long, default_value_t = 1)]
+    index: u8,
+
+    /// Take the first h^((p-1)/q) above 1 for g instead, which can't be validated later
+    #[arg(long, conflicts_with = "index")]
+    unverifiable_generator: bool,
+}
+
+#[derive(Args, Debug)]
+struct ValidateParamsArgs {
+    /// Parameters file, from `params generate` or written the same way
+    #[arg(short, long)]
+    params_path: String,
+
+    /// DSA private or public key to check against the parameters
+    #[arg(short, long)]
+    keys_path: Option<String>,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug)]
+struct AttackArgs {
+    #[command(subcommand)]
+    attack: Attack,
+}
+
+/// Private key recovery from signatures with flawed nonces, for demonstration only
+#[derive(Subcommand, Debug)]
+enum Attack {
+    /// Sign numbered messages with reused or biased nonces, the way a broken signer would
+    FlawedSign(FlawedSignArgs),
+    /// Recover the key from two signatures of different messages sharing r
+    NonceReuse(RecoverKeyArgs),
+    /// Recover the key from many signatures whose nonces have known leading zero bits
+    BiasedNonce(BiasedNonceArgs),
+}
+
+#[derive(Args, Debug)]
+struct FlawedSignArgs {
+    /// File path to the DSA or ECDSA private key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Directory to save message-N.txt and its signature message-N.sig in
+    #[arg(short, long)]
+    save_path: String,
+
+    /// Number of messages to sign
+    #[arg(short, long, default_value_t = 2)]
+    count: usize,
+
+    /// How the nonces are flawed
+    #[arg(long, value_enum, default_value_t = Nonce::Reused)]
+    nonce: Nonce,
+
+    /// Leading bits left zero in every biased nonce
+    #[arg(long, default_value_t = 8)]
+    zero_bits: u32,
+
+    /// Message digest, as long as the key's order when left out
+    #[arg(long, value_enum)]
+    digest: Option<Digest>,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug)]
+struct RecoverKeyArgs {
+    /// File path to the signer's public key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Signed data, in the same order as the signatures
+    #[arg(short, long, required = true, num_args = 1..)]
+    data_path: Vec<String>,
+
+    /// Signatures of the data
+    #[arg(short, long, required = true, num_args = 1..)]
+    signature_path: Vec<String>,
+
+    /// File path to save the recovered private key
+    #[arg(short, long)]
+    output_path: String,
+
+    /// Recovered key format
+    #[arg(long, value_enum, default_value_t = KeyFormat::PemPkcs1)]
+    format: KeyFormat,
+}
+
+#[derive(Args, Debug)]
+struct BiasedNonceArgs {
+    #[command(flatten)]
+    key: RecoverKeyArgs,
+
+    /// Leading bits known to be zero in every nonce, an N-bit order takes around 1.5 N / zero-bits signatures
+    #[arg(long)]
+    zero_bits: u32,
 }
 
 #[derive(Args, Debug)]
@@ -49,74 +345,537 @@ struct CryptoArgs {
     /// File path to save generated keys
     #[arg(short, long, default_value = "")]
     keys_path: String,
+
+    /// Algorithm the key must be for, detected from the key when left out
+    #[arg(short, long, value_enum)]
+    algorithm: Option<Algorithm>,
+
+    /// Message digest, as long as the key's order when signing, read from the signature when verifying
+    #[arg(long, value_enum)]
+    digest: Option<Digest>,
+
+    /// Accept SHA-1, DSA keys under 2048 bits and digests shorter than the key's order
+    #[arg(long)]
+    allow_legacy: bool,
+
+    /// Derive the DSA or ECDSA nonce from the key and message (RFC 6979) when signing
+    #[arg(long)]
+    deterministic: bool,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug)]
+struct InspectArgs {
+    /// File path to private or public key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Public key to check against the inspected key
+    #[arg(short, long)]
+    public_key_path: Option<String>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Serialize)]
+struct InspectReport {
+    key: KeyInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<KeyInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches: Option<bool>,
+}
+
+impl PassphraseArgs {
+    /// Passphrase given on the command line or in a file, the argument is wiped on the way.
+    fn take(&mut self) -> Option<Passphrase> {
+        if let Some(passphrase) = self.passphrase.take() {
+            return Some(Zeroizing::new(passphrase));
+        }
+
+        self.passphrase_file.as_deref().map(|path| {
+            passphrase::read_file(path).expect("Unable to read passphrase from the file!")
+        })
+    }
+
+    /// Passphrase to read `pem` with, prompting when the key is encrypted and none is given.
+    fn for_key(&mut self, pem: &[u8]) -> Option<Passphrase> {
+        self.take().or_else(|| {
+            passphrase::is_encrypted(pem).then(|| {
+                passphrase::prompt("Private key passphrase: ", false)
+                    .expect("Failed to read passphrase!")
+            })
+        })
+    }
+}
+
+fn as_str(passphrase: &Option<Passphrase>) -> Option<&str> {
+    passphrase.as_ref().map(|p| p.as_str())
+}
+
+fn read_key_info(path: &str, passphrase: &mut PassphraseArgs) -> KeyInfo {
+    let pem_key_data = fs::read(path).expect("Unable to read key from the file!");
+    let key_passphrase = passphrase.for_key(&pem_key_data);
+
+    inspect::inspect(&pem_key_data, as_str(&key_passphrase))
+        .unwrap_or_else(|e| panic!("Failed to parse key from the file: {}", e))
+}
+
+fn read_certificates(path: &str) -> Vec<X509> {
+    let pem = fs::read(path).expect("Unable to read certificates from the file!");
+
+    X509::stack_from_pem(&pem)
+        .unwrap_or_else(|e| panic!("Failed to parse certificates in {}: {}", path, e))
+}
+
+fn key_algorithm<T: HasPublic>(key: &PKeyRef<T>, expected: Option<Algorithm>) -> Algorithm {
+    let algorithm = Algorithm::of_key(key).expect("Key of an unsupported type!");
+    if let Some(expected) = expected.filter(|&expected| expected != algorithm) {
+        panic!("Key is for {}, not {}!", algorithm.name(), expected.name());
+    }
+
+    algorithm
+}
+
+fn read_parameters(path: &str) -> Parameters {
+    let data = fs::read(path).expect("Unable to read parameters from the file!");
+
+    Parameters::from_json(&data).unwrap_or_else(|e| panic!("Failed to read parameters: {}", e))
+}
+
+fn run_params_step(step: ParamsStep) {
+    match step {
+        ParamsStep::Generate(args) => {
+            let index = (!args.unverifiable_generator).then_some(args.index);
+            let parameters =
+                Parameters::generate(args.p_bits, args.q_bits, args.digest, args.method, index)
+                    .unwrap_or_else(|e| panic!("Failed to generate parameters: {}", e));
+
+            fs::write(&args.output_path, parameters.to_json())
+                .expect("Failed to save parameters to file!");
+        }
+        ParamsStep::Validate(mut args) => {
+            let parameters = read_parameters(&args.params_path);
+            let validation = match parameters.validate() {
+                Ok(validation) => validation,
+                Err(e) => {
+                    println!("Failed validation! {}", e);
+                    process::exit(1);
+                }
+            };
+            println!("p and q: {}", params::describe(validation.primes));
+            println!("g: {}", params::describe(validation.generator));
+
+            if let Some(keys_path) = &args.keys_path {
+                let key_data = fs::read(keys_path).expect("Unable to read key from the file!");
+                let key_passphrase = args.passphrase.for_key(&key_data);
+
+                let checked = match format::load_private_key(&key_data, as_str(&key_passphrase)) {
+                    Ok(key) => params::validate_key(&key, Some(&key), &parameters.params),
+                    Err(_) => {
+                        let key = format::load_public_key(&key_data)
+                            .unwrap_or_else(|e| panic!("Failed to read key: {}", e));
+                        params::validate_key(&key, None, &parameters.params)
+                    }
+                };
+                match checked {
+                    Ok(()) => println!("Key: valid over these parameters"),
+                    Err(e) => {
+                        println!("Failed key validation! {}", e);
+                        process::exit(1);
+                    }
+                }
+            }
+
+            println!("Successfully passed validation!");
+        }
+    }
+}
+
+fn read_samples(target: &Target, args: &RecoverKeyArgs) -> Vec<Sample> {
+    if args.data_path.len() != args.signature_path.len() {
+        panic!(
+            "Every signature needs its data, got {} data files and {} signatures!",
+            args.data_path.len(),
+            args.signature_path.len()
+        );
+    }
+
+    args.data_path
+        .iter()
+        .zip(&args.signature_path)
+        .map(|(data_path, signature_path)| {
+            let data = fs::read(data_path).expect("Unable to read data from the file!");
+            let signature_data =
+                fs::read(signature_path).expect("Unable to read signature from the file!");
+            // bare signatures are from before the envelope, DSA with SHA-1
+            Envelope::from_bytes(&signature_data, Some(Digest::Sha1))
+                .and_then(|envelope| target.sample(&data, &envelope))
+                .unwrap_or_else(|e| panic!("Unusable signature {}: {}", signature_path, e))
+        })
+        .collect()
+}
+
+fn write_recovered_key(target: &Target, x: &BigUint, args: &RecoverKeyArgs) {
+    let key = target
+        .private_key(x)
+        .unwrap_or_else(|e| panic!("Failed to rebuild the private key: {}", e));
+    let private_key = format::private_key_to_bytes(&key, args.format, None)
+        .unwrap_or_else(|e| panic!("Failed to generate private key: {}", e));
+
+    fs::write(&args.output_path, private_key).expect("Failed to save private key to file!");
+    println!("Recovered the private key!");
+}
+
+fn run_attack(attack: Attack) {
+    match attack {
+        Attack::FlawedSign(mut args) => {
+            let pem_key_data =
+                fs::read(&args.keys_path).expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+            let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                .expect("Failed to generate private key from key file!");
+
+            let algorithm = key_algorithm(&private_key, None);
+            let Some(digest) = args.digest.or(algorithm.default_digest()) else {
+                panic!("{} has no nonce to flaw!", algorithm.name());
+            };
+
+            let signatures = attack::flawed_signatures(
+                &private_key,
+                digest,
+                args.count,
+                args.nonce,
+                args.zero_bits,
+            )
+            .unwrap_or_else(|e| panic!("Failed to generate the signatures: {}", e));
+
+            let directory = Path::new(&args.save_path);
+            for (i, (message, envelope)) in signatures.iter().enumerate() {
+                fs::write(directory.join(format!("message-{}.txt", i + 1)), message)
+                    .expect("Failed to save message to file");
+                fs::write(
+                    directory.join(format!("message-{}.sig", i + 1)),
+                    envelope.to_bytes(),
+                )
+                .expect("Failed to save data signature to file");
+            }
+        }
+        Attack::NonceReuse(args) => {
+            let target = read_target(&args.keys_path);
+            let samples = read_samples(&target, &args);
+            if samples.len() != 2 {
+                panic!("Nonce reuse attack needs exactly two signatures!");
+            }
+
+            let recovery =
+                dsa_attacks::nonce_reuse::attack(target.order(), &samples[0], &samples[1], |x| {
+                    target.is_key(x)
+                })
+                .unwrap_or_else(|e| panic!("Nonce reuse attack failed: {}", e));
+            println!("Reused nonce: {:x}", recovery.k);
+            write_recovered_key(&target, &recovery.x, &args);
+        }
+        Attack::BiasedNonce(args) => {
+            let target = read_target(&args.key.keys_path);
+            let samples = read_samples(&target, &args.key);
+
+            let x =
+                dsa_attacks::biased_nonce::attack(target.order(), &samples, args.zero_bits, |x| {
+                    target.is_key(x)
+                })
+                .unwrap_or_else(|e| panic!("Biased nonce attack failed: {}", e));
+            write_recovered_key(&target, &x, &args.key);
+        }
+    }
+}
+
+fn read_target(path: &str) -> Target {
+    let pem_key_data = fs::read(path).expect("Unable to read key from the file!");
+    let public_key = format::load_public_key(&pem_key_data)
+        .expect("Failed to generate public key from key file!");
+
+    Target::new(&public_key).unwrap_or_else(|e| panic!("Can't attack this key: {}", e))
+}
+
+fn print_key_info(info: &KeyInfo) {
+    let kind = if info.private { "private" } else { "public" };
+    println!("Type: {} {} key", info.key_type, kind);
+    match info.q_bits {
+        Some(q_bits) => println!("Bits: {} (q {} bits)", info.bits, q_bits),
+        None => println!("Bits: {}", info.bits),
+    }
+    for (name, value) in [("P", &info.p), ("Q", &info.q), ("G", &info.g)] {
+        if let Some(value) = value {
+            println!("{}: {}", name, value);
+        }
+    }
+    println!("SHA-256 fingerprint: {}", info.sha256_fingerprint);
+    println!("MD5 fingerprint: {}", info.md5_fingerprint);
 }
 
 fn main() {
     let cli = Cli::parse();
 
     match cli.operation {
-        Operation::Keys(args) => {
-            if !(args.block_size != 0 && (args.block_size & (args.block_size - 1)) == 0) {
+        Operation::Keys(mut args) => {
+            if args.algorithm == Algorithm::Dsa
+                && !(args.block_size != 0 && (args.block_size & (args.block_size - 1)) == 0)
+            {
                 panic!(
                     "Block size should be 2 in power of n. Current value is: {}",
                     args.block_size
                 );
             }
 
-            let dsa = Dsa::generate(args.block_size).expect("Failed to generate dsa keys!");
-            let private_key = dsa
-                .private_key_to_pem()
-                .expect("Failed to generate private pem key!");
-            let public_key = dsa
-                .public_key_to_pem()
-                .expect("Failed to generate public pem key!");
+            let key_passphrase = args.passphrase.take().or_else(|| {
+                args.ask_passphrase.then(|| {
+                    passphrase::prompt("New passphrase: ", true)
+                        .expect("Failed to read passphrase!")
+                })
+            });
 
-            fs::write(format!("{}_key.pem", args.keys_name), private_key)
-                .expect("Failed to save private pem key to file!");
-            fs::write(format!("{}_key.pem.pub", args.keys_name), public_key)
-                .expect("Failed to save public pem key to file!");
+            let key = match &args.params {
+                Some(path) if args.algorithm == Algorithm::Dsa => {
+                    read_parameters(path).generate_key()
+                }
+                Some(_) => panic!("Domain parameters are for DSA keys only!"),
+                None => args
+                    .algorithm
+                    .generate(args.block_size)
+                    .map_err(params::Error::from),
+            }
+            .unwrap_or_else(|e| panic!("Failed to generate {} keys: {}", args.algorithm.name(), e));
+            let private_key =
+                format::private_key_to_bytes(&key, args.format, as_str(&key_passphrase))
+                    .unwrap_or_else(|e| panic!("Failed to generate private key: {}", e));
+            let public_key = format::public_key_to_bytes(&key, args.format)
+                .unwrap_or_else(|e| panic!("Failed to generate public key: {}", e));
+
+            let file_name = args.format.file_name(&args.keys_name);
+            fs::write(&file_name, private_key).expect("Failed to save private key to file!");
+            fs::write(format!("{}.pub", file_name), public_key)
+                .expect("Failed to save public key to file!");
         }
-        Operation::Sign(args) => {
+        Operation::Sign(mut args) => {
             let data = fs::read(args.data_path).expect("Unable to read data from the file!");
             let pem_key_data = fs::read(args.keys_path).expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
 
-            let private_key = PKey::private_key_from_pem(&pem_key_data)
-                .expect("Failed to generate private key from pem file!");
+            let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                .expect("Failed to generate private key from key file!");
 
-            let mut signer = Signer::new(MessageDigest::sha1(), &private_key)
-                .expect("Failed to create DSA signer!");
+            let algorithm = key_algorithm(&private_key, args.algorithm);
+            if algorithm.is_eddsa() && args.digest.is_some() {
+                panic!(
+                    "{} hashes the message itself, drop --digest!",
+                    algorithm.name()
+                );
+            }
 
-            signer.update(&data).expect("Failed to pass data for verification!");
+            let digest = args.digest.or(algorithm.default_digest());
+            if !args.allow_legacy {
+                signature::check_strength(&private_key, digest)
+                    .unwrap_or_else(|e| panic!("{}, use --allow-legacy to sign anyway!", e));
+            }
 
-            let signature = signer
-                .sign_to_vec()
-                .expect("Failed to generate the signature!");
+            let envelope = match args.deterministic {
+                true => signature::sign_deterministic(&private_key, digest, &data),
+                false => signature::sign(&private_key, digest, &data),
+            }
+            .unwrap_or_else(|e| panic!("Failed to generate the signature: {}", e));
 
-            fs::write(args.signature_path, signature)
+            fs::write(args.signature_path, envelope.to_bytes())
                 .expect("Failed to save data signature to file");
         }
         Operation::Verify(args) => {
             let data = fs::read(args.data_path).expect("Unable to read data from the file!");
-            let signature =
+            let signature_data =
                 fs::read(args.signature_path).expect("Unable to read signature from the file!");
             let pem_key_data = fs::read(args.keys_path).expect("Unable to read key from the file!");
 
-            let public_key = PKey::public_key_from_pem(&pem_key_data)
-                .expect("Failed to generate public key from pem file!");
+            let public_key = format::load_public_key(&pem_key_data)
+                .expect("Failed to generate public key from key file!");
 
-            let mut verifier = Verifier::new(MessageDigest::sha1(), &public_key).unwrap();
-
-            verifier
-                .update(&data)
-                .expect("Failed to pass data for verification!");
+            let algorithm = key_algorithm(&public_key, args.algorithm);
+            // bare signatures are from before the envelope, DSA with SHA-1 unless told otherwise
+            let bare_digest = match algorithm.is_eddsa() {
+                true => None,
+                false => Some(args.digest.unwrap_or(Digest::Sha1)),
+            };
+            let envelope = Envelope::from_bytes(&signature_data, bare_digest)
+                .unwrap_or_else(|e| panic!("Failed to read the signature: {}", e));
+            if args.digest.is_some() && args.digest != envelope.digest {
+                panic!("Signature was made with another digest!");
+            }
+            if !args.allow_legacy {
+                signature::check_strength(&public_key, envelope.digest)
+                    .unwrap_or_else(|e| panic!("{}, use --allow-legacy to verify anyway!", e));
+            }
 
-            match verifier
-                .verify(&signature)
+            match signature::verify(&public_key, &data, &envelope)
                 .expect("Failed to verify the data by signature!")
             {
                 true => println!("Successfully passed verification!"),
                 false => println!("Failed verification!"),
             }
         }
+        Operation::Inspect(mut args) => {
+            let key = read_key_info(&args.keys_path, &mut args.passphrase);
+            let public_key = args
+                .public_key_path
+                .as_deref()
+                .map(|path| read_key_info(path, &mut args.passphrase));
+            let matches = public_key
+                .as_ref()
+                .map(|public_key| key.matches(public_key));
+
+            if args.json {
+                let report = InspectReport {
+                    key,
+                    public_key,
+                    matches,
+                };
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).expect("Failed to serialize report!")
+                );
+            } else {
+                print_key_info(&key);
+
+                if let Some(public_key) = &public_key {
+                    println!();
+                    print_key_info(public_key);
+                    println!();
+                    match matches {
+                        Some(true) => println!("Keys belong to the same pair!"),
+                        _ => println!("Keys don't belong to the same pair!"),
+                    }
+                }
+            }
+
+            if matches == Some(false) {
+                process::exit(1);
+            }
+        }
+        Operation::ChangePassphrase(mut args) => {
+            let pem_key_data =
+                fs::read(&args.keys_path).expect("Unable to read key from the file!");
+            let old_passphrase = args.passphrase.for_key(&pem_key_data);
+            let new_passphrase = match (args.new_passphrase.take(), &args.new_passphrase_file) {
+                (Some(new_passphrase), _) => Zeroizing::new(new_passphrase),
+                (None, Some(path)) => {
+                    passphrase::read_file(path).expect("Unable to read passphrase from the file!")
+                }
+                (None, None) => passphrase::prompt("New passphrase: ", true)
+                    .expect("Failed to read passphrase!"),
+            };
+            let new_passphrase = Some(new_passphrase).filter(|p| !p.is_empty());
+
+            let private_key = format::load_private_key(&pem_key_data, as_str(&old_passphrase))
+                .expect("Failed to generate private key from key file!");
+            let private_key = format::private_key_to_bytes(
+                &private_key,
+                format::private_key_format(&pem_key_data),
+                as_str(&new_passphrase),
+            )
+            .unwrap_or_else(|e| panic!("Failed to generate private key: {}", e));
+
+            fs::write(&args.keys_path, private_key).expect("Failed to save private key to file!");
+        }
+        Operation::Convert(mut args) => {
+            let key_data = fs::read(&args.input_path).expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&key_data);
+
+            let converted = match format::load_private_key(&key_data, as_str(&key_passphrase)) {
+                Ok(key) if args.public => format::public_key_to_bytes(&key, args.format),
+                Ok(key) => format::private_key_to_bytes(&key, args.format, as_str(&key_passphrase)),
+                Err(e) if passphrase::is_encrypted(&key_data) => {
+                    panic!("Failed to read private key: {}", e)
+                }
+                Err(_) => {
+                    let key = format::load_public_key(&key_data)
+                        .unwrap_or_else(|e| panic!("Failed to read key: {}", e));
+                    format::public_key_to_bytes(&key, args.format)
+                }
+            }
+            .unwrap_or_else(|e| panic!("Failed to convert key: {}", e));
+
+            fs::write(&args.output_path, converted).expect("Failed to save converted key to file!");
+        }
+        Operation::Csr(mut args) => {
+            let pem_key_data =
+                fs::read(&args.keys_path).expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+            let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                .expect("Failed to generate private key from key file!");
+
+            let request = x509::build_request(&private_key, &args.profile)
+                .unwrap_or_else(|e| panic!("Failed to build the request: {}", e));
+
+            fs::write(&args.output_path, request.to_pem().unwrap())
+                .expect("Failed to save request to file!");
+        }
+        Operation::Cert(mut args) => {
+            let pem_key_data =
+                fs::read(&args.keys_path).expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+            let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                .expect("Failed to generate private key from key file!");
+
+            let certificate = match (&args.request_path, &args.issuer_path) {
+                (Some(request_path), Some(issuer_path)) => {
+                    let request =
+                        fs::read(request_path).expect("Unable to read request from the file!");
+                    let request = x509::load_request(&request)
+                        .unwrap_or_else(|e| panic!("Failed to read the request: {}", e));
+                    let issuer = read_certificates(issuer_path)
+                        .into_iter()
+                        .next()
+                        .expect("No certificate in the issuer file!");
+
+                    x509::sign_request(
+                        &request,
+                        &args.profile,
+                        &args.validity,
+                        Issuer {
+                            certificate: &issuer,
+                            key: &private_key,
+                        },
+                    )
+                }
+                _ => x509::self_signed(&private_key, &args.profile, &args.validity),
+            }
+            .unwrap_or_else(|e| panic!("Failed to build the certificate: {}", e));
+
+            fs::write(&args.output_path, certificate.to_pem().unwrap())
+                .expect("Failed to save certificate to file!");
+        }
+        Operation::Params(args) => run_params_step(args.step),
+        Operation::Attack(args) => run_attack(args.attack),
+        Operation::VerifyChain(args) => {
+            let chain = read_certificates(&args.chain_path);
+            let anchors = read_certificates(&args.anchors_path);
+
+            match x509::verify_chain(&chain, &anchors) {
+                Ok(path) => {
+                    for (depth, certificate) in path.iter().enumerate() {
+                        println!("{}: {}", depth, x509::subject_line(certificate));
+                    }
+                    println!("Successfully passed verification!");
+                }
+                Err(e) => {
+                    println!("Failed verification! {}", e);
+                    process::exit(1);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,332 @@
+// FIPS 186-4 domain parameter files for dsa_core, JSON holding p, q and g as
+// hex with the seed and counters they were generated from:
+//
+//   { "digest": "sha256", "p": "..", "q": "..", "g": "..",
+//     "seed": { "method": "probable", "domain_parameter_seed": "..", "counter": 75 },
+//     "index": 1 }
+//
+// Parameters from elsewhere may leave out the seed, or the index when g
+// wasn't derived from the seed, and then only get partial validation.
+use crate::signature::Digest;
+use clap::ValueEnum;
+use dsa_core::params::{self, Assurance};
+use dsa_core::{DomainParameters, DsaPrivateKey, DsaPublicKey, HashAlgorithm, Seed};
+use num_bigint::BigUint;
+use openssl::bn::{BigNum, BigNumRef};
+use openssl::dsa::Dsa;
+use openssl::error::ErrorStack;
+use openssl::pkey::{HasPublic, PKey, PKeyRef, Private};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    /// Probable primes found by hashing the seed (A.1.1.2)
+    Probable,
+    /// Primes proven by Shawe-Taylor construction (A.1.2.1.2)
+    Provable,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Malformed(String),
+    /// Digest FIPS 186-4 doesn't generate parameters with
+    UnsupportedDigest,
+    Dsa(dsa_core::Error),
+    OpenSsl(ErrorStack),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Malformed(reason) => write!(f, "Malformed parameters file: {}", reason),
+            Error::UnsupportedDigest => write!(f, "FIPS 186-4 parameters take SHA-1 or SHA-2"),
+            Error::Dsa(e) => write!(f, "{}", e),
+            Error::OpenSsl(e) => write!(f, "OpenSSL error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<dsa_core::Error> for Error {
+    fn from(e: dsa_core::Error) -> Self {
+        Error::Dsa(e)
+    }
+}
+
+impl From<ErrorStack> for Error {
+    fn from(e: ErrorStack) -> Self {
+        Error::OpenSsl(e)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "kebab-case")]
+enum SeedFile {
+    Probable {
+        domain_parameter_seed: String,
+        counter: u32,
+    },
+    Provable {
+        first_seed: String,
+        p_seed: String,
+        q_seed: String,
+        p_counter: u32,
+        q_counter: u32,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct ParamsFile {
+    digest: String,
+    p: String,
+    q: String,
+    g: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<SeedFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    index: Option<u8>,
+}
+
+/// Domain parameters with what is known of their generation.
+#[derive(Debug, PartialEq)]
+pub struct Parameters {
+    pub params: DomainParameters,
+    pub digest: Digest,
+    pub seed: Option<Seed>,
+    pub index: Option<u8>,
+}
+
+pub fn hash_algorithm(digest: Digest) -> Result<HashAlgorithm, Error> {
+    match digest {
+        Digest::Sha3_256 => Err(Error::UnsupportedDigest),
+        digest => Ok(digest.hash_algorithm()),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(value: &str) -> Result<Vec<u8>, Error> {
+    let malformed = || Error::Malformed(format!("bad hex string {:?}", value));
+    if !value.len().is_multiple_of(2) {
+        return Err(malformed());
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(malformed)
+}
+
+fn number(value: &str) -> Result<BigUint, Error> {
+    BigUint::parse_bytes(value.as_bytes(), 16)
+        .ok_or_else(|| Error::Malformed(format!("bad hex number {:?}", value)))
+}
+
+fn from_bignum(value: &BigNumRef) -> BigUint {
+    BigUint::from_bytes_be(&value.to_vec())
+}
+
+fn to_bignum(value: &BigUint) -> Result<BigNum, ErrorStack> {
+    BigNum::from_slice(&value.to_bytes_be())
+}
+
+impl Parameters {
+    /// New parameters of `l` and `n` bits, g derived from the seed with `index` if given.
+    pub fn generate(
+        l: usize,
+        n: usize,
+        digest: Digest,
+        method: Method,
+        index: Option<u8>,
+    ) -> Result<Self, Error> {
+        let method = match method {
+            Method::Probable => params::Method::Probable,
+            Method::Provable => params::Method::Provable,
+        };
+        let (params, seed) = params::generate(l, n, hash_algorithm(digest)?, method, index)?;
+
+        Ok(Parameters {
+            params,
+            digest,
+            seed: Some(seed),
+            index,
+        })
+    }
+
+    /// Checks the parameters as far as the seed and index allow.
+    pub fn validate(&self) -> Result<params::Validation, Error> {
+        Ok(params::validate(
+            &self.params,
+            hash_algorithm(self.digest)?,
+            self.seed.as_ref(),
+            self.index,
+        )?)
+    }
+
+    pub fn to_json(&self) -> String {
+        let seed = self.seed.as_ref().map(|seed| match seed {
+            Seed::Probable { seed, counter } => SeedFile::Probable {
+                domain_parameter_seed: to_hex(seed),
+                counter: *counter,
+            },
+            Seed::Provable {
+                first_seed,
+                p_seed,
+                q_seed,
+                p_counter,
+                q_counter,
+            } => SeedFile::Provable {
+                first_seed: to_hex(first_seed),
+                p_seed: to_hex(p_seed),
+                q_seed: to_hex(q_seed),
+                p_counter: *p_counter,
+                q_counter: *q_counter,
+            },
+        });
+        let file = ParamsFile {
+            digest: self
+                .digest
+                .to_possible_value()
+                .unwrap()
+                .get_name()
+                .to_string(),
+            p: self.params.p().to_str_radix(16),
+            q: self.params.q().to_str_radix(16),
+            g: self.params.g().to_str_radix(16),
+            seed,
+            index: self.index,
+        };
+
+        serde_json::to_string_pretty(&file).unwrap()
+    }
+
+    pub fn from_json(data: &[u8]) -> Result<Self, Error> {
+        let file: ParamsFile =
+            serde_json::from_slice(data).map_err(|e| Error::Malformed(e.to_string()))?;
+        let digest = Digest::from_str(&file.digest, true)
+            .map_err(|_| Error::Malformed(format!("unknown digest {:?}", file.digest)))?;
+        let seed = file
+            .seed
+            .map(|seed| -> Result<Seed, Error> {
+                Ok(match seed {
+                    SeedFile::Probable {
+                        domain_parameter_seed,
+                        counter,
+                    } => Seed::Probable {
+                        seed: from_hex(&domain_parameter_seed)?,
+                        counter,
+                    },
+                    SeedFile::Provable {
+                        first_seed,
+                        p_seed,
+                        q_seed,
+                        p_counter,
+                        q_counter,
+                    } => Seed::Provable {
+                        first_seed: from_hex(&first_seed)?,
+                        p_seed: from_hex(&p_seed)?,
+                        q_seed: from_hex(&q_seed)?,
+                        p_counter,
+                        q_counter,
+                    },
+                })
+            })
+            .transpose()?;
+
+        Ok(Parameters {
+            params: DomainParameters::new(number(&file.p)?, number(&file.q)?, number(&file.g)?)?,
+            digest,
+            seed,
+            index: file.index,
+        })
+    }
+
+    /// New OpenSSL key pair over these parameters.
+    pub fn generate_key(&self) -> Result<PKey<Private>, Error> {
+        let dsa = Dsa::from_pqg(
+            to_bignum(self.params.p())?,
+            to_bignum(self.params.q())?,
+            to_bignum(self.params.g())?,
+        )?;
+
+        Ok(PKey::from_dsa(dsa.generate_key()?)?)
+    }
+}
+
+pub fn describe(assurance: Assurance) -> &'static str {
+    match assurance {
+        Assurance::Verified => "regenerated from the seed",
+        Assurance::Partial => "partially validated, nothing to regenerate them from",
+    }
+}
+
+/// Checks a DSA key is over `params` and validates it, the pair as well when
+/// `private` holds the private half.
+pub fn validate_key<T: HasPublic>(
+    key: &PKeyRef<T>,
+    private: Option<&PKeyRef<Private>>,
+    params: &DomainParameters,
+) -> Result<(), Error> {
+    let dsa = key.dsa()?;
+    let key_params = DomainParameters::new(
+        from_bignum(dsa.p()),
+        from_bignum(dsa.q()),
+        from_bignum(dsa.g()),
+    )?;
+    if key_params != *params {
+        return Err(dsa_core::Error::InvalidKey("key is over other domain parameters").into());
+    }
+
+    let y = from_bignum(dsa.pub_key());
+    match private {
+        Some(private) => {
+            let x = from_bignum(private.dsa()?.priv_key());
+            DsaPrivateKey::from_components(key_params, x, y)?;
+        }
+        None => {
+            DsaPublicKey::new(key_params, y)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_round_trip() {
+        for method in [Method::Probable, Method::Provable] {
+            let parameters =
+                Parameters::generate(1024, 160, Digest::Sha1, method, Some(1)).unwrap();
+            let read = Parameters::from_json(parameters.to_json().as_bytes()).unwrap();
+            assert_eq!(read, parameters);
+            assert_eq!(read.validate().unwrap().primes, Assurance::Verified);
+
+            let key = parameters.generate_key().unwrap();
+            assert!(validate_key(&key, Some(&key), &parameters.params).is_ok());
+        }
+
+        let other = Parameters::generate(1024, 160, Digest::Sha1, Method::Probable, None).unwrap();
+        let key = other.generate_key().unwrap();
+        assert!(validate_key(&key, None, &other.params).is_ok());
+        let read = Parameters::from_json(other.to_json().as_bytes()).unwrap();
+        assert_eq!(read.index, None);
+
+        let unrelated =
+            Parameters::generate(1024, 160, Digest::Sha1, Method::Probable, None).unwrap();
+        assert!(validate_key(&key, None, &unrelated.params).is_err());
+        assert!(Parameters::generate(1024, 160, Digest::Sha3_256, Method::Probable, None).is_err());
+        assert!(Parameters::from_json(b"{\"digest\": \"sha256\"}").is_err());
+    }
+}
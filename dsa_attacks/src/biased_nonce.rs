@@ -0,0 +1,151 @@
+// Biased nonces as a hidden number problem (Boneh and Venkatesan, then
+// Howgrave-Graham and Smart for DSA). Every signature gives
+// k_i = t_i x + u_i mod q with t_i = r_i / s_i and u_i = z_i / s_i, and when
+// each k_i stays below B = 2^(N - l) for its l leading zero bits, the vector
+//
+//   (W (k_1 - B/2), ..., W (k_m - B/2), x, M),  W = 2^l, M = 2^(N - 1)
+//
+// is unusually short in the lattice spanned by the rows
+//
+//   q W e_i                       for i = 1..m
+//   (W t_1, ..., W t_m, 1, 0)
+//   (W u'_1, ..., W u'_m, 0, M)   u'_i = u_i - B/2 mod q
+//
+// LLL turns it up once the signatures leak comfortably more bits between them
+// than q has, around 1.5 N / l signatures in practice.
+use crate::lattice;
+use crate::{Error, Sample};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+
+// k = t x + u mod q for one signature
+fn nonce_relation(sample: &Sample, q: &BigUint) -> Result<(BigUint, BigUint), Error> {
+    let s_inv = sample
+        .s
+        .modinv(q)
+        .ok_or(Error::InvalidInput("q isn't prime"))?;
+
+    Ok((&sample.r * &s_inv % q, &sample.z * &s_inv % q))
+}
+
+/// Recovers x from signatures whose nonces all have their `zero_bits` leading
+/// bits zero, `is_key` tells the right x among the candidates.
+pub fn attack(
+    q: &BigUint,
+    samples: &[Sample],
+    zero_bits: u32,
+    is_key: impl Fn(&BigUint) -> bool,
+) -> Result<BigUint, Error> {
+    let n = q.bits();
+    if zero_bits == 0 || zero_bits as u64 >= n {
+        return Err(Error::InvalidInput("zero bits must lie in [1, N - 1]"));
+    }
+    if samples.len() as u64 * zero_bits as u64 <= n {
+        return Err(Error::InvalidInput(
+            "signatures leak fewer bits than the key has, more are needed",
+        ));
+    }
+
+    let m = samples.len();
+    let bound = BigUint::from(1u8) << (n - zero_bits as u64);
+    let half = &bound >> 1;
+    let weight = BigInt::from(1u8) << zero_bits;
+    let embedding = BigInt::from(1u8) << (n - 1);
+    let big = |value: &BigUint| BigInt::from_biguint(Sign::Plus, value.clone());
+
+    let mut basis = vec![vec![BigInt::from(0u8); m + 2]; m + 2];
+    for (i, sample) in samples.iter().enumerate() {
+        sample.check(q)?;
+        let (t, u) = nonce_relation(sample, q)?;
+        let centered = (u + q - &half % q) % q;
+
+        basis[i][i] = big(q) * &weight;
+        basis[m][i] = big(&t) * &weight;
+        basis[m + 1][i] = big(&centered) * &weight;
+    }
+    basis[m][m] = BigInt::from(1u8);
+    basis[m + 1][m + 1] = embedding.clone();
+
+    lattice::lll(&mut basis);
+
+    let q = big(q);
+    for row in &basis {
+        let x = match &row[m + 1] {
+            last if *last == embedding => row[m].mod_floor(&q),
+            last if *last == -&embedding => (-&row[m]).mod_floor(&q),
+            _ => continue,
+        };
+
+        let x = x.to_biguint().unwrap();
+        if is_key(&x) {
+            return Ok(x);
+        }
+    }
+
+    Err(Error::NotVulnerable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::prime::random_range;
+
+    fn samples(q: &BigUint, x: &BigUint, count: usize, zero_bits: u32) -> Vec<Sample> {
+        let one = BigUint::from(1u8);
+        let bound = &one << (q.bits() - zero_bits as u64);
+
+        (0..count)
+            .map(|_| {
+                // r would come from g^k, the attack can't tell it's random
+                let k = random_range(&one, &bound).unwrap();
+                let r = random_range(&one, q).unwrap();
+                let z = random_range(&one, q).unwrap();
+                let s = k.modinv(q).unwrap() * (&z + x * &r) % q;
+                Sample { r, s, z }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_biased_nonces_give_the_key_away() {
+        // FIPS 186-4 sized 160 and 256-bit group orders, the RFC 6979 DSA q
+        // and the P-256 order
+        for (q, count, zero_bits) in [
+            ("996F967F6C8E388D9E28D01E205FBA957A5698B1", 30, 8),
+            (
+                "FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551",
+                36,
+                10,
+            ),
+        ] {
+            let q = BigUint::parse_bytes(q.as_bytes(), 16).unwrap();
+            let x = random_range(&BigUint::from(1u8), &q).unwrap();
+            let samples = samples(&q, &x, count, zero_bits);
+
+            assert_eq!(
+                attack(&q, &samples, zero_bits, |candidate| *candidate == x),
+                Ok(x.clone())
+            );
+            assert_eq!(
+                attack(&q, &samples, zero_bits, |_| false),
+                Err(Error::NotVulnerable)
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_few_signatures_are_refused() {
+        let q = BigUint::parse_bytes(b"996F967F6C8E388D9E28D01E205FBA957A5698B1", 16).unwrap();
+        let x = random_range(&BigUint::from(1u8), &q).unwrap();
+        let samples = samples(&q, &x, 20, 8);
+
+        assert!(matches!(
+            attack(&q, &samples, 8, |_| true),
+            Err(Error::InvalidInput(_))
+        ));
+        assert!(matches!(
+            attack(&q, &samples, 0, |_| true),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
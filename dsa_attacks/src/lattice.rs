@@ -0,0 +1,199 @@
+// LLL basis reduction (Lenstra, Lenstra and Lovász) in the floating point
+// style of Nguyen and Stehlé's L2: the basis and its Gram matrix stay exact big
+// integers and only the Gram-Schmidt coefficients are f64, recomputed from the
+// exact Gram matrix so rounding errors don't pile up over the run. Plenty for
+// the few dozen dimensions of the hidden number problem, whose Gram entries
+// fit an f64 exponent.
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, ToPrimitive};
+
+/// Lovász condition factor
+const DELTA: f64 = 0.99;
+/// Size reduction bound, a little over 1/2 for the rounding errors
+const ETA: f64 = 0.51;
+
+fn dot(a: &[BigInt], b: &[BigInt]) -> BigInt {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+struct Reduction<'a> {
+    basis: &'a mut [Vec<BigInt>],
+    gram: Vec<Vec<BigInt>>,
+    mu: Vec<Vec<f64>>,
+    // r[i][j] = mu[i][j] |b*_j|^2, r[i][i] = |b*_i|^2
+    r: Vec<Vec<f64>>,
+}
+
+impl Reduction<'_> {
+    // Cholesky row i of the Gram matrix, rows before it being current
+    fn orthogonalize(&mut self, i: usize) {
+        for j in 0..=i {
+            let mut value = self.gram[i][j].to_f64().unwrap();
+            for l in 0..j {
+                value -= self.mu[j][l] * self.r[i][l];
+            }
+            self.r[i][j] = value;
+            if j < i {
+                self.mu[i][j] = value / self.r[j][j];
+            }
+        }
+    }
+
+    // b_k -= c b_j, with the Gram matrix following along
+    fn subtract(&mut self, k: usize, j: usize, c: &BigInt) {
+        let (head, tail) = self.basis.split_at_mut(k);
+        for (target, value) in tail[0].iter_mut().zip(&head[j]) {
+            *target -= c * value;
+        }
+
+        let n = self.gram.len();
+        self.gram[k][k] = &self.gram[k][k] - 2 * c * &self.gram[k][j] + c * c * &self.gram[j][j];
+        for i in (0..n).filter(|&i| i != k) {
+            let value = &self.gram[k][i] - c * &self.gram[j][i];
+            self.gram[i][k] = value.clone();
+            self.gram[k][i] = value;
+        }
+    }
+
+    // until every |mu[k][j]| <= ETA, more than one pass when mu was too large
+    // for f64 to round exactly
+    fn size_reduce(&mut self, k: usize) {
+        loop {
+            self.orthogonalize(k);
+
+            let mut reduced = true;
+            for j in (0..k).rev() {
+                if self.mu[k][j].abs() <= ETA {
+                    continue;
+                }
+
+                let c = self.mu[k][j].round();
+                self.subtract(k, j, &BigInt::from_f64(c).unwrap());
+                for l in 0..j {
+                    self.mu[k][l] -= c * self.mu[j][l];
+                }
+                self.mu[k][j] -= c;
+                reduced = false;
+            }
+
+            if reduced {
+                return;
+            }
+        }
+    }
+
+    fn swap(&mut self, k: usize) {
+        self.basis.swap(k, k - 1);
+        self.gram.swap(k, k - 1);
+        for row in self.gram.iter_mut() {
+            row.swap(k, k - 1);
+        }
+    }
+}
+
+/// Reduces the linearly independent rows of `basis` in place, the first row
+/// ends up within 2^((n - 1) / 2) of the shortest lattice vector and usually
+/// much closer.
+pub fn lll(basis: &mut [Vec<BigInt>]) {
+    let n = basis.len();
+    if n < 2 {
+        return;
+    }
+
+    let gram = (0..n)
+        .map(|i| (0..n).map(|j| dot(&basis[i], &basis[j])).collect())
+        .collect();
+    let mut reduction = Reduction {
+        basis,
+        gram,
+        mu: vec![vec![0.0; n]; n],
+        r: vec![vec![0.0; n]; n],
+    };
+    reduction.orthogonalize(0);
+
+    let mut k = 1;
+    while k < n {
+        reduction.size_reduce(k);
+
+        let mu = reduction.mu[k][k - 1];
+        if reduction.r[k][k] >= (DELTA - mu * mu) * reduction.r[k - 1][k - 1] {
+            k += 1;
+            continue;
+        }
+
+        reduction.swap(k);
+        if k == 1 {
+            reduction.orthogonalize(0);
+        } else {
+            k -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::Signed;
+
+    fn rows(values: &[&[i64]]) -> Vec<Vec<BigInt>> {
+        values
+            .iter()
+            .map(|row| row.iter().map(|&v| BigInt::from(v)).collect())
+            .collect()
+    }
+
+    // determinant by fraction free Gaussian elimination (Bareiss)
+    fn determinant(matrix: &[Vec<BigInt>]) -> BigInt {
+        let mut m = matrix.to_vec();
+        let n = m.len();
+        let mut sign = BigInt::from(1);
+        let mut previous = BigInt::from(1);
+
+        for k in 0..n - 1 {
+            if m[k][k] == BigInt::from(0) {
+                let pivot = (k + 1..n).find(|&i| m[i][k] != BigInt::from(0)).unwrap();
+                m.swap(k, pivot);
+                sign = -sign;
+            }
+            for i in k + 1..n {
+                for j in k + 1..n {
+                    m[i][j] = (&m[i][j] * &m[k][k] - &m[i][k] * &m[k][j]) / &previous;
+                }
+            }
+            previous = m[k][k].clone();
+        }
+
+        sign * &m[n - 1][n - 1]
+    }
+
+    #[test]
+    fn test_reduces_known_basis() {
+        // (1, 1, 1), (-1, 0, 2), (3, 5, 6) reduce to (0, 1, 0), (1, 0, 1), (-1, 0, 2)
+        let mut basis = rows(&[&[1, 1, 1], &[-1, 0, 2], &[3, 5, 6]]);
+        let before = determinant(&basis);
+        lll(&mut basis);
+
+        assert_eq!(determinant(&basis).abs(), before.abs());
+        assert_eq!(basis[0], rows(&[&[0, 1, 0]])[0]);
+        assert!(basis.iter().all(|row| dot(row, row) <= BigInt::from(5)));
+    }
+
+    #[test]
+    fn test_recovers_short_basis_behind_large_entries() {
+        // a unimodular mix of the unit vectors with 200-bit entries, the
+        // lattice is still Z^3
+        let big: BigInt = BigInt::from(1u8) << 200u32;
+        let zero = BigInt::from(0);
+        let one = BigInt::from(1);
+        let mut basis = vec![
+            vec![one.clone(), big.clone(), zero.clone()],
+            vec![one.clone(), &big + 1, big.clone()],
+            vec![zero.clone(), zero, one.clone()],
+        ];
+        assert_eq!(determinant(&basis), one);
+        lll(&mut basis);
+
+        assert_eq!(determinant(&basis).abs(), one);
+        assert!(basis.iter().all(|row| dot(row, row) == one));
+    }
+}
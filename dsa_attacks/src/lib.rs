@@ -0,0 +1,49 @@
+// Private key recovery from DSA and ECDSA signatures with flawed nonces, for
+// teaching why k has to be unique, secret and uniformly random. The attacks
+// only see r, s and the digest modulo the group order q, so they work the
+// same on both and leave checking a candidate x to the caller.
+use num_bigint::BigUint;
+use num_traits::Zero;
+use std::fmt;
+
+pub mod biased_nonce;
+pub mod lattice;
+pub mod nonce_reuse;
+
+/// One signature as the attacks see it, z being the digest turned into a
+/// number the way the signer did (its leftmost N bits).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub r: BigUint,
+    pub s: BigUint,
+    pub z: BigUint,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The signatures don't give the key away
+    NotVulnerable,
+    InvalidInput(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotVulnerable => write!(f, "Signatures don't reveal the private key"),
+            Error::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Sample {
+    // 0 < r, s < q, anything else fails verification and can't be attacked
+    fn check(&self, q: &BigUint) -> Result<(), Error> {
+        if self.r.is_zero() || self.r >= *q || self.s.is_zero() || self.s >= *q {
+            return Err(Error::InvalidInput("r and s must lie in [1, q - 1]"));
+        }
+
+        Ok(())
+    }
+}
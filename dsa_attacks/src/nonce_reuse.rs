@@ -0,0 +1,119 @@
+// Nonce reuse: two signatures made with the same k share r, and subtracting
+// s1 = k^-1 (z1 + xr) from s2 = k^-1 (z2 + xr) leaves k = (z1 - z2) / (s1 - s2)
+// mod q, after which x = (s1 k - z1) / r. ECDSA gets the same r out of -k too,
+// which flips the sign of the second s.
+use crate::{Error, Sample};
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+#[derive(Debug, PartialEq)]
+pub struct Recovery {
+    /// Nonce of the first signature
+    pub k: BigUint,
+    pub x: BigUint,
+}
+
+// a - b mod q
+fn sub(a: &BigUint, b: &BigUint, q: &BigUint) -> BigUint {
+    (a % q + q - b % q) % q
+}
+
+/// Recovers the nonce and private key from two signatures of different
+/// messages sharing r, `is_key` tells the right x among the candidates.
+pub fn attack(
+    q: &BigUint,
+    first: &Sample,
+    second: &Sample,
+    is_key: impl Fn(&BigUint) -> bool,
+) -> Result<Recovery, Error> {
+    first.check(q)?;
+    second.check(q)?;
+    if first.r != second.r {
+        return Err(Error::InvalidInput(
+            "signatures don't share r, their nonces differ",
+        ));
+    }
+    if sub(&first.z, &second.z, q).is_zero() {
+        return Err(Error::InvalidInput(
+            "both signatures are of the same digest",
+        ));
+    }
+
+    let r_inv = first
+        .r
+        .modinv(q)
+        .ok_or(Error::InvalidInput("q isn't prime"))?;
+
+    for s2 in [second.s.clone(), q - &second.s] {
+        let Some(difference) = sub(&first.s, &s2, q).modinv(q) else {
+            continue;
+        };
+
+        let k = sub(&first.z, &second.z, q) * difference % q;
+        let x = sub(&(&first.s * &k), &first.z, q) * &r_inv % q;
+        if is_key(&x) {
+            return Ok(Recovery { k, x });
+        }
+    }
+
+    Err(Error::NotVulnerable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::prime::random_range;
+
+    // P-256 group order, the attack never looks past q
+    fn order() -> BigUint {
+        BigUint::parse_bytes(
+            b"FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551",
+            16,
+        )
+        .unwrap()
+    }
+
+    fn sign(q: &BigUint, x: &BigUint, k: &BigUint, r: &BigUint, z: &BigUint) -> Sample {
+        let s = k.modinv(q).unwrap() * (z + x * r) % q;
+        Sample {
+            r: r.clone(),
+            s,
+            z: z.clone(),
+        }
+    }
+
+    #[test]
+    fn test_reused_nonce_gives_the_key_away() {
+        let q = order();
+        let one = BigUint::from(1u8);
+        let x = random_range(&one, &q).unwrap();
+        let k = random_range(&one, &q).unwrap();
+        let r = random_range(&one, &q).unwrap();
+        let (z1, z2) = (BigUint::from(1234u32), BigUint::from(5678u32));
+
+        let first = sign(&q, &x, &k, &r, &z1);
+        let second = sign(&q, &x, &k, &r, &z2);
+        assert_eq!(
+            attack(&q, &first, &second, |candidate| *candidate == x),
+            Ok(Recovery {
+                k: k.clone(),
+                x: x.clone()
+            })
+        );
+
+        // ECDSA's -k has the same r
+        let negated = sign(&q, &x, &(&q - &k), &r, &z2);
+        assert_eq!(
+            attack(&q, &first, &negated, |candidate| *candidate == x).map(|found| found.x),
+            Ok(x.clone())
+        );
+
+        assert_eq!(
+            attack(&q, &first, &second, |_| false),
+            Err(Error::NotVulnerable)
+        );
+        assert!(attack(&q, &first, &first, |_| true).is_err());
+        let other = sign(&q, &x, &k, &(&r + 1u8), &z2);
+        assert!(attack(&q, &first, &other, |_| true).is_err());
+    }
+}
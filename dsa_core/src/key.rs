@@ -0,0 +1,530 @@
+// DSA key pairs over domain parameters (FIPS 186-4 B.1.2), their validation
+// after SP 800-89, and signatures (sections 4.6 and 4.7) DER encoded the way
+// OpenSSL writes them.
+use crate::params::DomainParameters;
+use crate::rfc6979::Nonces;
+use crate::Error;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use rsa_core::der::{self, Reader, SEQUENCE};
+use rsa_core::montgomery::Montgomery;
+use rsa_core::prime::random_range;
+use rsa_core::HashAlgorithm;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DsaPublicKey {
+    params: DomainParameters,
+    y: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DsaPrivateKey {
+    public: DsaPublicKey,
+    x: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+impl Signature {
+    /// Dss-Sig-Value: SEQUENCE { r INTEGER, s INTEGER }
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut content = der::write_integer(&self.r);
+        content.extend(der::write_integer(&self.s));
+
+        der::write(SEQUENCE, &content)
+    }
+
+    pub fn from_der(data: &[u8]) -> Result<Self, Error> {
+        let mut outer = Reader::new(data);
+        let mut reader = Reader::new(outer.read(SEQUENCE)?);
+        let r = reader.read_integer()?;
+        let s = reader.read_integer()?;
+
+        if !reader.is_empty() || !outer.is_empty() {
+            return Err(Error::InvalidInput("malformed DER"));
+        }
+
+        Ok(Signature { r, s })
+    }
+}
+
+/// Leftmost min(N, outlen) bits of `digest` as a number, section 4.6.
+pub fn bits_to_int(digest: &[u8], q: &BigUint) -> BigUint {
+    let n = q.bits() as usize;
+    let z = BigUint::from_bytes_be(digest);
+
+    match digest.len() * 8 {
+        outlen if outlen > n => z >> (outlen - n),
+        _ => z,
+    }
+}
+
+impl DsaPublicKey {
+    /// Public key `y`, checked as SP 800-89 section 5.3.2 asks: 1 < y < p - 1
+    /// and y^q = 1 mod p.
+    pub fn new(params: DomainParameters, y: BigUint) -> Result<Self, Error> {
+        let (p, q) = (params.p(), params.q());
+
+        if y < BigUint::from(2u8) || y > p - 2u8 {
+            return Err(Error::InvalidKey("y out of range"));
+        }
+        if !y.modpow(q, p).is_one() {
+            return Err(Error::InvalidKey("y isn't in the order q subgroup"));
+        }
+
+        Ok(DsaPublicKey { params, y })
+    }
+
+    pub fn params(&self) -> &DomainParameters {
+        &self.params
+    }
+
+    pub fn y(&self) -> &BigUint {
+        &self.y
+    }
+
+    pub fn verify(
+        &self,
+        hash: HashAlgorithm,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<(), Error> {
+        self.verify_digest(&hash.digest(message), signature)
+    }
+
+    pub fn verify_digest(&self, digest: &[u8], signature: &Signature) -> Result<(), Error> {
+        let (p, q, g) = (self.params.p(), self.params.q(), self.params.g());
+        let Signature { r, s } = signature;
+
+        if r.is_zero() || r >= q || s.is_zero() || s >= q {
+            return Err(Error::Verification);
+        }
+
+        let w = s.modpow(&(q - 2u8), q);
+        let u1 = bits_to_int(digest, q) * &w % q;
+        let u2 = r * &w % q;
+        let v = g.modpow(&u1, p) * self.y.modpow(&u2, p) % p % q;
+
+        if v == *r {
+            Ok(())
+        } else {
+            Err(Error::Verification)
+        }
+    }
+}
+
+impl DsaPrivateKey {
+    /// New key pair, x by testing candidates (B.1.2).
+    pub fn generate(params: DomainParameters) -> Result<Self, Error> {
+        let x = random_range(&BigUint::one(), params.q())?;
+        let y = Montgomery::new(params.p()).pow(params.g(), &x, params.q().bits() as usize);
+
+        Self::from_components(params, x, y)
+    }
+
+    /// Key pair from its parts, validated the way SP 800-89 section 5.3.3
+    /// does: y passes the public key checks, 0 < x < q and y = g^x mod p.
+    pub fn from_components(
+        params: DomainParameters,
+        x: BigUint,
+        y: BigUint,
+    ) -> Result<Self, Error> {
+        if x.is_zero() || x >= *params.q() {
+            return Err(Error::InvalidKey("x out of range"));
+        }
+        let n = params.q().bits() as usize;
+        if Montgomery::new(params.p()).pow(params.g(), &x, n) != y {
+            return Err(Error::InvalidKey("y doesn't match x"));
+        }
+
+        Ok(DsaPrivateKey {
+            public: DsaPublicKey::new(params, y)?,
+            x,
+        })
+    }
+
+    pub fn to_public_key(&self) -> DsaPublicKey {
+        self.public.clone()
+    }
+
+    pub fn x(&self) -> &BigUint {
+        &self.x
+    }
+
+    pub fn sign(&self, hash: HashAlgorithm, message: &[u8]) -> Result<Signature, Error> {
+        self.sign_digest(&hash.digest(message))
+    }
+
+    /// Signs with a new random k (B.2.2) until r and s both turn out nonzero.
+    pub fn sign_digest(&self, digest: &[u8]) -> Result<Signature, Error> {
+        loop {
+            let k = random_range(&BigUint::one(), self.public.params.q())?;
+
+            if let Some(signature) = self.sign_with_nonce(digest, &k) {
+                return Ok(signature);
+            }
+        }
+    }
+
+    /// Signs with k derived from x and the message (RFC 6979), signing the
+    /// same message again gives the same signature.
+    pub fn sign_deterministic(&self, hash: HashAlgorithm, message: &[u8]) -> Signature {
+        self.sign_digest_deterministic(hash, &hash.digest(message))
+    }
+
+    /// `digest` is made with `hash`, which also runs the nonce generator.
+    pub fn sign_digest_deterministic(&self, hash: HashAlgorithm, digest: &[u8]) -> Signature {
+        Nonces::new(hash, self.public.params.q(), &self.x, digest)
+            .find_map(|k| self.sign_with_nonce(digest, &k))
+            .unwrap()
+    }
+
+    /// r = (g^k mod p) mod q and s = k^-1 (z + xr) mod q, exponentiations with
+    /// k run in constant time; None when r or s is zero. Meant for the attack
+    /// demonstrations, a k that repeats or leaks any bits gives x away.
+    pub fn sign_with_nonce(&self, digest: &[u8], k: &BigUint) -> Option<Signature> {
+        let params = &self.public.params;
+        let (p, q, g) = (params.p(), params.q(), params.g());
+        let n = q.bits() as usize;
+
+        let r = Montgomery::new(p).pow(g, k, n) % q;
+        let k_inv = Montgomery::new(q).pow(k, &(q - 2u8), n);
+        let s = k_inv * (bits_to_int(digest, q) + &self.x * &r) % q;
+
+        if r.is_zero() || s.is_zero() {
+            return None;
+        }
+
+        Some(Signature { r, s })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::dsa::Dsa;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::sign::{Signer, Verifier};
+
+    fn number(value: &openssl::bn::BigNumRef) -> BigUint {
+        BigUint::from_bytes_be(&value.to_vec())
+    }
+
+    // RFC 6979 appendix A.2.1 and A.2.2 keys, p, q, g, x and y
+    struct Rfc6979Key {
+        p: &'static str,
+        q: &'static str,
+        g: &'static str,
+        x: &'static str,
+        y: &'static str,
+    }
+    const DSA_1024: Rfc6979Key = Rfc6979Key {
+        p: concat!(
+            "86F5CA03DCFEB225063FF830A0C769B9DD9D6153AD91D7CE27F787C43278B447",
+            "E6533B86B18BED6E8A48B784A14C252C5BE0DBF60B86D6385BD2F12FB763ED88",
+            "73ABFD3F5BA2E0A8C0A59082EAC056935E529DAF7C610467899C77ADEDFC846C",
+            "881870B7B19B2B58F9BE0521A17002E3BDD6B86685EE90B3D9A1B02B782B1779",
+        ),
+        q: "996F967F6C8E388D9E28D01E205FBA957A5698B1",
+        g: concat!(
+            "07B0F92546150B62514BB771E2A0C0CE387F03BDA6C56B505209FF25FD3C133D",
+            "89BBCD97E904E09114D9A7DEFDEADFC9078EA544D2E401AEECC40BB9FBBF78FD",
+            "87995A10A1C27CB7789B594BA7EFB5C4326A9FE59A070E136DB77175464ADCA4",
+            "17BE5DCE2F40D10A46A3A3943F26AB7FD9C0398FF8C76EE0A56826A8A88F1DBD",
+        ),
+        x: "411602CB19A6CCC34494D79D98EF1E7ED5AF25F7",
+        y: concat!(
+            "5DF5E01DED31D0297E274E1691C192FE5868FEF9E19A84776454B100CF16F653",
+            "92195A38B90523E2542EE61871C0440CB87C322FC4B4D2EC5E1E7EC766E1BE8D",
+            "4CE935437DC11C3C8FD426338933EBFE739CB3465F4D3668C5E473508253B1E6",
+            "82F65CBDC4FAE93C2EA212390E54905A86E2223170B44EAA7DA5DD9FFCFB7F3B",
+        ),
+    };
+
+    const DSA_2048: Rfc6979Key = Rfc6979Key {
+        p: concat!(
+            "9DB6FB5951B66BB6FE1E140F1D2CE5502374161FD6538DF1648218642F0B5C48",
+            "C8F7A41AADFA187324B87674FA1822B00F1ECF8136943D7C55757264E5A1A44F",
+            "FE012E9936E00C1D3E9310B01C7D179805D3058B2A9F4BB6F9716BFE6117C6B5",
+            "B3CC4D9BE341104AD4A80AD6C94E005F4B993E14F091EB51743BF33050C38DE2",
+            "35567E1B34C3D6A5C0CEAA1A0F368213C3D19843D0B4B09DCB9FC72D39C8DE41",
+            "F1BF14D4BB4563CA28371621CAD3324B6A2D392145BEBFAC748805236F5CA2FE",
+            "92B871CD8F9C36D3292B5509CA8CAA77A2ADFC7BFD77DDA6F71125A7456FEA15",
+            "3E433256A2261C6A06ED3693797E7995FAD5AABBCFBE3EDA2741E375404AE25B",
+        ),
+        q: "F2C3119374CE76C9356990B465374A17F23F9ED35089BD969F61C6DDE9998C1F",
+        g: concat!(
+            "5C7FF6B06F8F143FE8288433493E4769C4D988ACE5BE25A0E24809670716C613",
+            "D7B0CEE6932F8FAA7C44D2CB24523DA53FBE4F6EC3595892D1AA58C4328A06C4",
+            "6A15662E7EAA703A1DECF8BBB2D05DBE2EB956C142A338661D10461C0D135472",
+            "085057F3494309FFA73C611F78B32ADBB5740C361C9F35BE90997DB2014E2EF5",
+            "AA61782F52ABEB8BD6432C4DD097BC5423B285DAFB60DC364E8161F4A2A35ACA",
+            "3A10B1C4D203CC76A470A33AFDCBDD92959859ABD8B56E1725252D78EAC66E71",
+            "BA9AE3F1DD2487199874393CD4D832186800654760E1E34C09E4D155179F9EC0",
+            "DC4473F996BDCE6EED1CABED8B6F116F7AD9CF505DF0F998E34AB27514B0FFE7",
+        ),
+        x: "69C7548C21D0DFEA6B9A51C9EAD4E27C33D3B3F180316E5BCAB92C933F0E4DBC",
+        y: concat!(
+            "667098C654426C78D7F8201EAC6C203EF030D43605032C2F1FA937E5237DBD94",
+            "9F34A0A2564FE126DC8B715C5141802CE0979C8246463C40E6B6BDAA2513FA61",
+            "1728716C2E4FD53BC95B89E69949D96512E873B9C8F8DFD499CC312882561ADE",
+            "CB31F658E934C0C197F2C4D96B05CBAD67381E7B768891E4DA3843D24D94CDFB",
+            "5126E9B8BF21E8358EE0E0A30EF13FD6A664C0DCE3731F7FB49A4845A4FD8254",
+            "687972A2D382599C9BAC4E0ED7998193078913032558134976410B89D2C171D1",
+            "23AC35FD977219597AA7D15C1A9A428E59194F75C721EBCBCFAE44696A499AFA",
+            "74E04299F132026601638CB87AB79190D4A0986315DA8EEC6561C938996BEADF",
+        ),
+    };
+
+    // key, digest, message, r, s
+    const RFC6979_SIGNATURES: [(&Rfc6979Key, HashAlgorithm, &str, &str, &str); 20] = [
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha1,
+            "sample",
+            "2E1A0C2562B2912CAAF89186FB0F42001585DA55",
+            "29EFB6B0AFF2D7A68EB70CA313022253B9A88DF5",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha1,
+            "test",
+            "42AB2052FD43E123F0607F115052A67DCD9C5C77",
+            "183916B0230D45B9931491D4C6B0BD2FB4AAF088",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha224,
+            "sample",
+            "4BC3B686AEA70145856814A6F1BB53346F02101E",
+            "410697B92295D994D21EDD2F4ADA85566F6F94C1",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha224,
+            "test",
+            "6868E9964E36C1689F6037F91F28D5F2C30610F2",
+            "49CEC3ACDC83018C5BD2674ECAAD35B8CD22940F",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha256,
+            "sample",
+            "81F2F5850BE5BC123C43F71A3033E9384611C545",
+            "4CDD914B65EB6C66A8AAAD27299BEE6B035F5E89",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha256,
+            "test",
+            "22518C127299B0F6FDC9872B282B9E70D0790812",
+            "6837EC18F150D55DE95B5E29BE7AF5D01E4FE160",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha384,
+            "sample",
+            "07F2108557EE0E3921BC1774F1CA9B410B4CE65A",
+            "54DF70456C86FAC10FAB47C1949AB83F2C6F7595",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha384,
+            "test",
+            "854CF929B58D73C3CBFDC421E8D5430CD6DB5E66",
+            "91D0E0F53E22F898D158380676A871A157CDA622",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha512,
+            "sample",
+            "16C3491F9B8C3FBBDD5E7A7B667057F0D8EE8E1B",
+            "02C36A127A7B89EDBB72E4FFBC71DABC7D4FC69C",
+        ),
+        (
+            &DSA_1024,
+            HashAlgorithm::Sha512,
+            "test",
+            "8EA47E475BA8AC6F2D821DA3BD212D11A3DEB9A0",
+            "7C670C7AD72B6C050C109E1790008097125433E8",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha1,
+            "sample",
+            "3A1B2DBD7489D6ED7E608FD036C83AF396E290DBD602408E8677DAABD6E7445A",
+            "D26FCBA19FA3E3058FFC02CA1596CDBB6E0D20CB37B06054F7E36DED0CDBBCCF",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha1,
+            "test",
+            "C18270A93CFC6063F57A4DFA86024F700D980E4CF4E2CB65A504397273D98EA0",
+            "414F22E5F31A8B6D33295C7539C1C1BA3A6160D7D68D50AC0D3A5BEAC2884FAA",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha224,
+            "sample",
+            "DC9F4DEADA8D8FF588E98FED0AB690FFCE858DC8C79376450EB6B76C24537E2C",
+            "A65A9C3BC7BABE286B195D5DA68616DA8D47FA0097F36DD19F517327DC848CEC",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha224,
+            "test",
+            "272ABA31572F6CC55E30BF616B7A265312018DD325BE031BE0CC82AA17870EA3",
+            "E9CC286A52CCE201586722D36D1E917EB96A4EBDB47932F9576AC645B3A60806",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha256,
+            "sample",
+            "EACE8BDBBE353C432A795D9EC556C6D021F7A03F42C36E9BC87E4AC7932CC809",
+            "7081E175455F9247B812B74583E9E94F9EA79BD640DC962533B0680793A38D53",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha256,
+            "test",
+            "8190012A1969F9957D56FCCAAD223186F423398D58EF5B3CEFD5A4146A4476F0",
+            "7452A53F7075D417B4B013B278D1BB8BBD21863F5E7B1CEE679CF2188E1AB19E",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha384,
+            "sample",
+            "B2DA945E91858834FD9BF616EBAC151EDBC4B45D27D0DD4A7F6A22739F45C00B",
+            "19048B63D9FD6BCA1D9BAE3664E1BCB97F7276C306130969F63F38FA8319021B",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha384,
+            "test",
+            "239E66DDBE8F8C230A3D071D601B6FFBDFB5901F94D444C6AF56F732BEB954BE",
+            "6BD737513D5E72FE85D1C750E0F73921FE299B945AAD1C802F15C26A43D34961",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha512,
+            "sample",
+            "2016ED092DC5FB669B8EFB3D1F31A91EECB199879BE0CF78F02BA062CB4C942E",
+            "D0C76F84B5F091E141572A639A4FB8C230807EEA7D55C8A154A224400AFF2351",
+        ),
+        (
+            &DSA_2048,
+            HashAlgorithm::Sha512,
+            "test",
+            "89EC4BB1400ECCFF8E7D9AA515CD1DE7803F2DAFF09693EE7FD1353E90A68307",
+            "C9F0BDABCC0D880BB137A994CC7F3980CE91CC10FAF529FC46565B15CEA854E1",
+        ),
+    ];
+
+    #[test]
+    fn test_signatures_interoperate_with_openssl() {
+        let dsa = Dsa::generate(2048).unwrap();
+        let params =
+            DomainParameters::new(number(dsa.p()), number(dsa.q()), number(dsa.g())).unwrap();
+        let key =
+            DsaPrivateKey::from_components(params, number(dsa.priv_key()), number(dsa.pub_key()))
+                .unwrap();
+        let openssl_key = PKey::from_dsa(dsa).unwrap();
+        let data = b"Some data to sign";
+
+        let signature = key.sign(HashAlgorithm::Sha256, data).unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &openssl_key).unwrap();
+        verifier.update(data).unwrap();
+        assert!(verifier.verify(&signature.to_der()).unwrap());
+
+        let mut signer = Signer::new(MessageDigest::sha256(), &openssl_key).unwrap();
+        signer.update(data).unwrap();
+        let signature = Signature::from_der(&signer.sign_to_vec().unwrap()).unwrap();
+        let public_key = key.to_public_key();
+        assert_eq!(
+            public_key.verify(HashAlgorithm::Sha256, data, &signature),
+            Ok(())
+        );
+        assert_eq!(
+            public_key.verify(HashAlgorithm::Sha256, b"Other data", &signature),
+            Err(Error::Verification)
+        );
+        assert_eq!(
+            public_key.verify(HashAlgorithm::Sha1, data, &signature),
+            Err(Error::Verification)
+        );
+
+        // SHA-512 is cut down to the leftmost N bits
+        let signature = key.sign(HashAlgorithm::Sha512, data).unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha512(), &openssl_key).unwrap();
+        verifier.update(data).unwrap();
+        assert!(verifier.verify(&signature.to_der()).unwrap());
+    }
+
+    #[test]
+    fn test_key_pairs_are_validated() {
+        let dsa = Dsa::generate(1024).unwrap();
+        let params =
+            DomainParameters::new(number(dsa.p()), number(dsa.q()), number(dsa.g())).unwrap();
+        let (x, y) = (number(dsa.priv_key()), number(dsa.pub_key()));
+
+        let key = DsaPrivateKey::generate(params.clone()).unwrap();
+        assert!(DsaPublicKey::new(params.clone(), key.to_public_key().y().clone()).is_ok());
+        assert!(DsaPrivateKey::from_components(params.clone(), x.clone(), y.clone()).is_ok());
+
+        assert!(DsaPrivateKey::from_components(params.clone(), x.clone(), &y + 1u8).is_err());
+        assert!(DsaPrivateKey::from_components(params.clone(), params.q().clone(), y).is_err());
+        // p - 1 has order 2, not q
+        assert!(DsaPublicKey::new(params.clone(), params.p() - 1u8).is_err());
+        assert!(DsaPublicKey::new(params.clone(), params.p() - 2u8).is_err());
+
+        let signature = key.sign(HashAlgorithm::Sha1, b"data").unwrap();
+        assert_eq!(
+            Signature::from_der(&signature.to_der()),
+            Ok(signature.clone())
+        );
+        let mut extended = signature.to_der();
+        extended.push(0);
+        assert!(Signature::from_der(&extended).is_err());
+
+        let swapped = Signature {
+            r: signature.s.clone(),
+            s: signature.r.clone(),
+        };
+        let public_key = key.to_public_key();
+        assert!(public_key
+            .verify(HashAlgorithm::Sha1, b"data", &swapped)
+            .is_err());
+    }
+
+    fn hex_number(value: &str) -> BigUint {
+        BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+    }
+
+    #[test]
+    fn test_rfc6979_signatures() {
+        for (key, hash, message, r, s) in RFC6979_SIGNATURES {
+            let params =
+                DomainParameters::new(hex_number(key.p), hex_number(key.q), hex_number(key.g))
+                    .unwrap();
+            let key = DsaPrivateKey::from_components(params, hex_number(key.x), hex_number(key.y))
+                .unwrap();
+            let expected = Signature {
+                r: hex_number(r),
+                s: hex_number(s),
+            };
+
+            let signature = key.sign_deterministic(hash, message.as_bytes());
+            assert_eq!(signature, expected, "{:?} {:?}", hash, message);
+            assert_eq!(
+                key.to_public_key()
+                    .verify(hash, message.as_bytes(), &signature),
+                Ok(())
+            );
+        }
+    }
+}
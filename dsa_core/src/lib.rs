@@ -0,0 +1,52 @@
+// DSA without OpenSSL after FIPS 186-4: domain parameters generated from a
+// seed and checked against it, key pairs and their validation, signatures with
+// random or RFC 6979 deterministic nonces. Primality tests, hashes and DER come
+// from rsa_core.
+use std::fmt;
+
+pub mod key;
+pub mod params;
+pub mod rfc6979;
+
+pub use key::{DsaPrivateKey, DsaPublicKey, Signature};
+pub use params::{DomainParameters, Seed};
+pub use rsa_core::HashAlgorithm;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// (L, N) pair FIPS 186-4 doesn't allow
+    ParameterSizes(usize, usize),
+    InvalidParameters(&'static str),
+    InvalidKey(&'static str),
+    InvalidInput(&'static str),
+    /// Operating system random generator failed
+    Random,
+    Verification,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::ParameterSizes(l, n) => {
+                write!(f, "Unsupported parameter sizes: L = {}, N = {}", l, n)
+            }
+            Error::InvalidParameters(reason) => write!(f, "Invalid domain parameters: {}", reason),
+            Error::InvalidKey(reason) => write!(f, "Invalid DSA key: {}", reason),
+            Error::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+            Error::Random => write!(f, "Random generator failure"),
+            Error::Verification => write!(f, "Signature verification failed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+// rsa_core only fails on randomness and malformed DER here
+impl From<rsa_core::Error> for Error {
+    fn from(e: rsa_core::Error) -> Self {
+        match e {
+            rsa_core::Error::Random => Error::Random,
+            _ => Error::InvalidInput("malformed DER"),
+        }
+    }
+}
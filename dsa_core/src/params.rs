@@ -0,0 +1,892 @@
+// FIPS 186-4 domain parameters generated from a seed, so whoever holds the
+// seed can redo the generation and see that nothing was picked by hand:
+// p and q are either probable primes found by hashing the seed (A.1.1.2) or
+// primes proven by Shawe-Taylor construction (A.1.2.1.2), and g is either
+// derived from the seed too (A.2.3) or just some element of order q (A.2.1).
+use crate::Error;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+use rsa_core::prime::{is_probable_prime, random_bytes};
+use rsa_core::HashAlgorithm;
+
+/// (L, N) pairs of FIPS 186-4 section 4.2.
+pub const SIZES: [(usize, usize); 4] = [(1024, 160), (2048, 224), (2048, 256), (3072, 256)];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DomainParameters {
+    p: BigUint,
+    q: BigUint,
+    g: BigUint,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    /// A.1.1.2, primes tested with Miller-Rabin and Lucas
+    Probable,
+    /// A.1.2.1.2, primes proven by Pocklington's criterion as they are built
+    Provable,
+}
+
+/// What p and q were generated from, kept to prove it later.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Seed {
+    /// p was the `counter`th candidate after q
+    Probable { seed: Vec<u8>, counter: u32 },
+    /// Seeds and counters as the construction left them
+    Provable {
+        first_seed: Vec<u8>,
+        p_seed: Vec<u8>,
+        q_seed: Vec<u8>,
+        p_counter: u32,
+        q_counter: u32,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Assurance {
+    /// Redone from the seed and found to match
+    Verified,
+    /// Only what holds without a seed was checked
+    Partial,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Validation {
+    pub primes: Assurance,
+    pub generator: Assurance,
+}
+
+impl DomainParameters {
+    /// Parameters as given, with only the checks every operation relies on.
+    /// Use `validate` before trusting them.
+    pub fn new(p: BigUint, q: BigUint, g: BigUint) -> Result<Self, Error> {
+        if p.is_even() || q.is_even() || q >= p {
+            return Err(Error::InvalidParameters("p and q must be odd with q < p"));
+        }
+        if g < BigUint::from(2u8) || g >= p {
+            return Err(Error::InvalidParameters("g out of range"));
+        }
+
+        Ok(DomainParameters { p, q, g })
+    }
+
+    pub fn p(&self) -> &BigUint {
+        &self.p
+    }
+
+    pub fn q(&self) -> &BigUint {
+        &self.q
+    }
+
+    pub fn g(&self) -> &BigUint {
+        &self.g
+    }
+
+    /// (L, N), the bit lengths of p and q.
+    pub fn sizes(&self) -> (usize, usize) {
+        (self.p.bits() as usize, self.q.bits() as usize)
+    }
+}
+
+impl Seed {
+    /// domain_parameter_seed that g is derived from, firstseed || pseed ||
+    /// qseed for provable primes.
+    pub fn domain_parameter_seed(&self) -> Vec<u8> {
+        match self {
+            Seed::Probable { seed, .. } => seed.clone(),
+            Seed::Provable {
+                first_seed,
+                p_seed,
+                q_seed,
+                ..
+            } => [&first_seed[..], p_seed, q_seed].concat(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Seed::Probable { seed, .. } => seed.len(),
+            Seed::Provable { first_seed, .. } => first_seed.len(),
+        }
+    }
+}
+
+// FIPS 186-4 table C.1, Miller-Rabin rounds when a Lucas test is also made
+fn p_rounds(l: usize) -> usize {
+    if l >= 3072 {
+        2
+    } else {
+        3
+    }
+}
+
+fn q_rounds(n: usize) -> usize {
+    match n {
+        160 => 19,
+        224 => 24,
+        _ => 27,
+    }
+}
+
+fn check_sizes(l: usize, n: usize, hash: HashAlgorithm) -> Result<(), Error> {
+    if !SIZES.contains(&(l, n)) {
+        return Err(Error::ParameterSizes(l, n));
+    }
+    if hash.size() * 8 < n {
+        return Err(Error::InvalidInput("hash output is shorter than N"));
+    }
+
+    Ok(())
+}
+
+// the seed arithmetic wraps around at the seed's length
+fn seed_bytes(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = (value % (BigUint::one() << (8 * len))).to_bytes_be();
+    let mut out = vec![0; len - bytes.len()];
+    out.extend(bytes);
+    out
+}
+
+fn hash_at(hash: HashAlgorithm, seed: &BigUint, offset: usize, len: usize) -> BigUint {
+    BigUint::from_bytes_be(&hash.digest(&seed_bytes(&(seed + offset), len)))
+}
+
+// Hash(seed + offset) + Hash(seed + offset + 1) * 2^outlen + ... over `count` hashes
+fn hash_chain(
+    hash: HashAlgorithm,
+    seed: &BigUint,
+    offset: usize,
+    count: usize,
+    len: usize,
+) -> BigUint {
+    let outlen = hash.size() * 8;
+
+    (0..count).fold(BigUint::zero(), |acc, j| {
+        acc + (hash_at(hash, seed, offset + j, len) << (j * outlen))
+    })
+}
+
+// A.1.1.2 steps 6 to 11 for one seed: q from its hash, then p from the hashes
+// that follow it, the first prime among 4L candidates. None when q isn't
+// prime or no candidate is.
+fn probable_primes(
+    l: usize,
+    n: usize,
+    hash: HashAlgorithm,
+    seed: &[u8],
+) -> Result<Option<(BigUint, BigUint, u32)>, Error> {
+    let q_top = BigUint::one() << (n - 1);
+    let u = BigUint::from_bytes_be(&hash.digest(seed)) % &q_top;
+    let q = q_top + &u + 1u8 - (&u % 2u8);
+    if !is_probable_prime(&q, q_rounds(n))? {
+        return Ok(None);
+    }
+
+    let seed_value = BigUint::from_bytes_be(seed);
+    let blocks = l.div_ceil(hash.size() * 8);
+    let top = BigUint::one() << (l - 1);
+    let twice_q = &q << 1;
+    let mut offset = 1;
+
+    for counter in 0..4 * l as u32 {
+        // W is the low L - 1 bits of the hashes, X = W + 2^(L-1), and p the
+        // number below X that is 1 mod 2q
+        let x = hash_chain(hash, &seed_value, offset, blocks, seed.len()) % &top + &top;
+        let p = &x + 1u8 - (&x % &twice_q);
+
+        if p >= top && is_probable_prime(&p, p_rounds(l))? {
+            return Ok(Some((p, q, counter)));
+        }
+        offset += blocks;
+    }
+
+    Ok(None)
+}
+
+fn is_small_prime(c: u64) -> bool {
+    c >= 2
+        && (2..)
+            .take_while(|d| d * d <= c)
+            .all(|d| !c.is_multiple_of(d))
+}
+
+// Pocklington: z = a^((c-1)/c0) mod c proves c prime when c0 is a prime
+// above the square root of c
+fn pocklington(z: &BigUint, c: &BigUint, c0: &BigUint) -> bool {
+    !z.is_zero() && (z - 1u8).gcd(c).is_one() && z.modpow(c0, c).is_one()
+}
+
+struct Proven {
+    prime: BigUint,
+    seed: BigUint,
+    counter: u32,
+}
+
+// C.6 ST_Random_Prime: a prime of `length` bits built on a proven prime of
+// about half its size, down to one small enough for trial division. None
+// when the seed runs out of candidates.
+fn st_random_prime(
+    hash: HashAlgorithm,
+    length: usize,
+    input_seed: &BigUint,
+    len: usize,
+) -> Option<Proven> {
+    let top = BigUint::one() << (length - 1);
+
+    if length < 33 {
+        let mut prime_seed = input_seed.clone();
+        let mut counter = 0;
+
+        loop {
+            let c = hash_at(hash, &prime_seed, 0, len) ^ hash_at(hash, &prime_seed, 1, len);
+            let c = (c % &top + &top) | BigUint::one();
+            counter += 1;
+            prime_seed += 2u8;
+
+            if is_small_prime(c.to_u64().unwrap()) {
+                return Some(Proven {
+                    prime: c,
+                    seed: prime_seed,
+                    counter,
+                });
+            }
+            if counter > 4 * length as u32 {
+                return None;
+            }
+        }
+    }
+
+    let Proven {
+        prime: c0,
+        seed: mut prime_seed,
+        mut counter,
+    } = st_random_prime(hash, length.div_ceil(2) + 1, input_seed, len)?;
+    let blocks = length.div_ceil(hash.size() * 8);
+    let old_counter = counter;
+
+    let x = hash_chain(hash, &prime_seed, 0, blocks, len) % &top + &top;
+    prime_seed += blocks;
+    let step = &c0 << 1;
+    let mut t = Integer::div_ceil(&x, &step);
+
+    loop {
+        if &t * &step + 1u8 > BigUint::one() << length {
+            t = Integer::div_ceil(&top, &step);
+        }
+        let c = &t * &step + 1u8;
+        counter += 1;
+
+        let a = hash_chain(hash, &prime_seed, 0, blocks, len);
+        prime_seed += blocks;
+        let a = a % (&c - 3u8) + 2u8;
+        let z = a.modpow(&(&t << 1), &c);
+
+        if pocklington(&z, &c, &c0) {
+            return Some(Proven {
+                prime: c,
+                seed: prime_seed,
+                counter,
+            });
+        }
+        if counter >= 4 * length as u32 + old_counter {
+            return None;
+        }
+        t += 1u8;
+    }
+}
+
+// A.1.2.1.2 from `first_seed`: q and a half-size p0 from ST_Random_Prime,
+// then p = 2tqp0 + 1 proven prime by Pocklington
+fn provable_primes(
+    l: usize,
+    n: usize,
+    hash: HashAlgorithm,
+    first_seed: &[u8],
+) -> Option<(BigUint, BigUint, Seed)> {
+    let len = first_seed.len();
+    let q = st_random_prime(hash, n, &BigUint::from_bytes_be(first_seed), len)?;
+    let Proven {
+        prime: p0,
+        seed: mut p_seed,
+        counter: mut p_counter,
+    } = st_random_prime(hash, l.div_ceil(2) + 1, &q.seed, len)?;
+    let blocks = l.div_ceil(hash.size() * 8);
+    let old_counter = p_counter;
+    let top = BigUint::one() << (l - 1);
+
+    let x = hash_chain(hash, &p_seed, 0, blocks, len) % &top + &top;
+    p_seed += blocks;
+    let step = (&q.prime * &p0) << 1;
+    let mut t = Integer::div_ceil(&x, &step);
+
+    loop {
+        if &t * &step + 1u8 > BigUint::one() << l {
+            t = Integer::div_ceil(&top, &step);
+        }
+        let p = &t * &step + 1u8;
+        p_counter += 1;
+
+        let a = hash_chain(hash, &p_seed, 0, blocks, len);
+        p_seed += blocks;
+        let a = a % (&p - 3u8) + 2u8;
+        let z = a.modpow(&((&t * &q.prime) << 1), &p);
+
+        if pocklington(&z, &p, &p0) {
+            let seed = Seed::Provable {
+                first_seed: first_seed.to_vec(),
+                p_seed: seed_bytes(&p_seed, len),
+                q_seed: seed_bytes(&q.seed, len),
+                p_counter,
+                q_counter: q.counter,
+            };
+            return Some((p, q.prime, seed));
+        }
+        if p_counter > 4 * l as u32 + old_counter {
+            return None;
+        }
+        t += 1u8;
+    }
+}
+
+// A.2.1: h^((p-1)/q) mod p for the first h from 2 that isn't 1
+fn unverifiable_generator(p: &BigUint, q: &BigUint) -> BigUint {
+    let e = (p - 1u8) / q;
+    let mut h = BigUint::from(2u8);
+
+    loop {
+        let g = h.modpow(&e, p);
+        if !g.is_one() {
+            return g;
+        }
+        h += 1u8;
+    }
+}
+
+// A.2.3: Hash(domain_parameter_seed || "ggen" || index || count)^((p-1)/q)
+// mod p for the first count that gives g >= 2
+fn verifiable_generator(
+    p: &BigUint,
+    q: &BigUint,
+    domain_parameter_seed: &[u8],
+    index: u8,
+    hash: HashAlgorithm,
+) -> Result<BigUint, Error> {
+    let e = (p - 1u8) / q;
+
+    for count in 1..=u16::MAX {
+        let u = [
+            domain_parameter_seed,
+            b"ggen",
+            &[index],
+            &count.to_be_bytes(),
+        ]
+        .concat();
+        let g = BigUint::from_bytes_be(&hash.digest(&u)).modpow(&e, p);
+
+        if g >= BigUint::from(2u8) {
+            return Ok(g);
+        }
+    }
+
+    Err(Error::InvalidParameters("no generator for this index"))
+}
+
+/// New parameters of L bits for p and N for q, with a seed of N bits. g is
+/// derived from the seed when an `index` is given, so it can be validated.
+pub fn generate(
+    l: usize,
+    n: usize,
+    hash: HashAlgorithm,
+    method: Method,
+    index: Option<u8>,
+) -> Result<(DomainParameters, Seed), Error> {
+    check_sizes(l, n, hash)?;
+
+    let (p, q, seed) = loop {
+        let mut seed = random_bytes(n / 8)?;
+
+        match method {
+            Method::Probable => {
+                if let Some((p, q, counter)) = probable_primes(l, n, hash, &seed)? {
+                    break (p, q, Seed::Probable { seed, counter });
+                }
+            }
+            Method::Provable => {
+                // A.1.2.1.1, firstseed is at least 2^(N-1)
+                seed[0] |= 0x80;
+                if let Some(found) = provable_primes(l, n, hash, &seed) {
+                    break found;
+                }
+            }
+        }
+    };
+
+    let g = match index {
+        Some(index) => verifiable_generator(&p, &q, &seed.domain_parameter_seed(), index, hash)?,
+        None => unverifiable_generator(&p, &q),
+    };
+
+    Ok((DomainParameters { p, q, g }, seed))
+}
+
+/// Validates `params` made with `hash`: p and q are generated again from
+/// `seed` (A.1.1.3 or A.1.2.2) and g from the seed and `index` (A.2.4).
+/// Without a seed p and q are only tested for primality, and without an
+/// index g is only checked to have order q (A.2.2).
+pub fn validate(
+    params: &DomainParameters,
+    hash: HashAlgorithm,
+    seed: Option<&Seed>,
+    index: Option<u8>,
+) -> Result<Validation, Error> {
+    let (l, n) = params.sizes();
+    check_sizes(l, n, hash)?;
+    let DomainParameters { p, q, g } = params;
+
+    let primes = match seed {
+        Some(seed) => {
+            if seed.len() * 8 < n {
+                return Err(Error::InvalidParameters("seed is shorter than N"));
+            }
+
+            let regenerated = match seed {
+                Seed::Probable {
+                    seed: bytes,
+                    counter,
+                } => {
+                    if *counter >= 4 * l as u32 {
+                        return Err(Error::InvalidParameters("counter out of range"));
+                    }
+                    probable_primes(l, n, hash, bytes)?.map(|(p, q, counter)| {
+                        let seed = Seed::Probable {
+                            seed: bytes.clone(),
+                            counter,
+                        };
+                        (p, q, seed)
+                    })
+                }
+                Seed::Provable { first_seed, .. } => {
+                    if BigUint::from_bytes_be(first_seed) < BigUint::one() << (n - 1) {
+                        return Err(Error::InvalidParameters("first seed is below 2^(N-1)"));
+                    }
+                    provable_primes(l, n, hash, first_seed)
+                }
+            };
+
+            match regenerated {
+                Some(found) if found == (p.clone(), q.clone(), seed.clone()) => Assurance::Verified,
+                _ => {
+                    return Err(Error::InvalidParameters(
+                        "p and q don't follow from the seed",
+                    ))
+                }
+            }
+        }
+        None => {
+            if !is_probable_prime(q, q_rounds(n))? {
+                return Err(Error::InvalidParameters("q isn't prime"));
+            }
+            if !is_probable_prime(p, p_rounds(l))? {
+                return Err(Error::InvalidParameters("p isn't prime"));
+            }
+            if !((p - 1u8) % q).is_zero() {
+                return Err(Error::InvalidParameters("q doesn't divide p - 1"));
+            }
+            Assurance::Partial
+        }
+    };
+
+    if !g.modpow(q, p).is_one() {
+        return Err(Error::InvalidParameters("g doesn't have order q"));
+    }
+    let generator = match (seed, index) {
+        (Some(seed), Some(index)) => {
+            if verifiable_generator(p, q, &seed.domain_parameter_seed(), index, hash)? != *g {
+                return Err(Error::InvalidParameters(
+                    "g doesn't follow from the seed and index",
+                ));
+            }
+            Assurance::Verified
+        }
+        _ => Assurance::Partial,
+    };
+
+    Ok(Validation { primes, generator })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::{DsaPublicKey, Signature};
+    use std::collections::HashMap;
+
+    // PQGVer style records for A.1.1.2 probable primes and A.2.3 generators,
+    // made with OpenSSL's FIPS 186-4 generator (genpkey -genparam with
+    // type:fips186_4 and gindex:1). NSS's PQG_VerifyParams gives every Result.
+    const PROBABLE: &str = "
+[mod = L=1024, N=160, SHA-1]
+
+P = f4f0b61ee7cb855c2cf0087e920a3dcdc9ca5727ee3e5e7ba176410d1fa457f236d703a73dcad0a5533cbf2e2f80f9097947b295bdfeebbd2229a60d26312423c2302cf6b0bc454c11487b0c4015b325f4ed2807e95aebb6a83991112c1c1531cd3fb5204f6ece3a6aac8bd6f6f5d43e814bbfd381e6b888b6c07e849e0db953
+Q = e4cf00ab1b2361ac1862d51428e509c62164f101
+G = 592b1a966c1d8eaf86bb9ba86e881b020201b30ad92439d86087159594a7731943b6e3a969f36f75f768d5c940e2d0ee8e9a644416f5203fdb08d2f69a97872b3d3d53392fc69fec01d506b1d30c3456927f055d1e64820d0b1a26303c9bfa4e4c3cc1fcaaf65c2bceb5cbb8804757dd97742e91d2dbc62ee177e7cff4db8e58
+domain_parameter_seed = 74e28e48a8ddafc738a3134b5fd19650dd0e9c5e
+counter = 94
+index = 01
+Result = P
+
+P = f4f0b61ee7cb855c2cf0087e920a3dcdc9ca5727ee3e5e7ba176410d1fa457f236d703a73dcad0a5533cbf2e2f80f9097947b295bdfeebbd2229a60d26312423c2302cf6b0bc454c11487b0c4015b325f4ed2807e95aebb6a83991112c1c1531cd3fb5204f6ece3a6aac8bd6f6f5d43e814bbfd381e6b888b6c07e849e0db953
+Q = e4cf00ab1b2361ac1862d51428e509c62164f101
+G = 592b1a966c1d8eaf86bb9ba86e881b020201b30ad92439d86087159594a7731943b6e3a969f36f75f768d5c940e2d0ee8e9a644416f5203fdb08d2f69a97872b3d3d53392fc69fec01d506b1d30c3456927f055d1e64820d0b1a26303c9bfa4e4c3cc1fcaaf65c2bceb5cbb8804757dd97742e91d2dbc62ee177e7cff4db8e58
+domain_parameter_seed = 74e28e48a8ddafc738a3134b5fd19650dd0e9c5e
+counter = 95
+index = 01
+Result = F (counter changed)
+
+[mod = L=2048, N=224, SHA-224]
+
+P = d09cd50f99f760e7b6033b4962251c8c31f0ba290d26f3ffc744e026ec48802a36e0385be46701bc3aae10257c33d57ac5f6996b25774a57f4dcc63576a20eb8078d45b318018ff25eb7ef21d9738670e7a20a9440bd6319690a4986a08e638ae0fe71e14328973cd2e29ad0cb30bb3dfc1fedc3385f47c9f9e6618d56bb79c3f9adfb5001119e4a23b4c896a12ebfc3fee9ed711a5793539ceedebe9c77d980c3d72c5e6099d5d9d0aaf4fa29a27fc7fdc483a5a944a44aec6c3fd12fb70ade0bec258bfd0c11b0134f7691cb6b5ed939c771dbc4f05f587d4172870f6625b05c205152ba84135c3416a586c0b5c127300eed892fe1ca9495f97b3ae2299a39
+Q = 9bb93c6157aa31d588d92a23b8f4c781bb71f013151c39864de3fca1
+G = 8bab7076eb4d37289d89aa9610fb1bc98fc1537cd22c04fa43ede4f3e7624cd28d96de70b2665a431bfdfe2f0e2556dd8295072bd9d46141366e3fc3734ec1352badedb7f8929463d55fd1d0785fd183638ab0a5733ca6f87b83495be33bf2e822e11df2c0363980807cfcdb84d13f0109ca85a4aee77b8aa850897f47e46112e7a74f850724ffd1e30cdb56a575beb4fff647f0f9a89828f738a0fa6abdead124b01f0a43f955adbc75e335ead012d67f2b67d78e168176f6ebce81cfadd7729fd6b7ea239e901b7a5d0545140a600fa6f3cce702acbc497d9b938127e7b07939b817f498b37b06cc314066b147386ae3943e14e8d99b8ebbdfbbdcf4b3805f
+domain_parameter_seed = 5c55d21e2fcae124c046ee8236510fdc82b40c51acb6290a77fbc8da
+counter = 223
+index = 01
+Result = P
+
+P = d09cd50f99f760e7b6033b4962251c8c31f0ba290d26f3ffc744e026ec48802a36e0385be46701bc3aae10257c33d57ac5f6996b25774a57f4dcc63576a20eb8078d45b318018ff25eb7ef21d9738670e7a20a9440bd6319690a4986a08e638ae0fe71e14328973cd2e29ad0cb30bb3dfc1fedc3385f47c9f9e6618d56bb79c3f9adfb5001119e4a23b4c896a12ebfc3fee9ed711a5793539ceedebe9c77d980c3d72c5e6099d5d9d0aaf4fa29a27fc7fdc483a5a944a44aec6c3fd12fb70ade0bec258bfd0c11b0134f7691cb6b5ed939c771dbc4f05f587d4172870f6625b05c205152ba84135c3416a586c0b5c127300eed892fe1ca9495f97b3ae2299a39
+Q = 9bb93c6157aa31d588d92a23b8f4c781bb71f013151c39864de3fca1
+G = 8bab7076eb4d37289d89aa9610fb1bc98fc1537cd22c04fa43ede4f3e7624cd28d96de70b2665a431bfdfe2f0e2556dd8295072bd9d46141366e3fc3734ec1352badedb7f8929463d55fd1d0785fd183638ab0a5733ca6f87b83495be33bf2e822e11df2c0363980807cfcdb84d13f0109ca85a4aee77b8aa850897f47e46112e7a74f850724ffd1e30cdb56a575beb4fff647f0f9a89828f738a0fa6abdead124b01f0a43f955adbc75e335ead012d67f2b67d78e168176f6ebce81cfadd7729fd6b7ea239e901b7a5d0545140a600fa6f3cce702acbc497d9b938127e7b07939b817f498b37b06cc314066b147386ae3943e14e8d99b8ebbdfbbdcf4b3805f
+domain_parameter_seed = 5c55d21e2fcae124c046ee8236510fdc82b40c51acb6290a77fbc8db
+counter = 223
+index = 01
+Result = F (domain_parameter_seed changed)
+
+[mod = L=2048, N=256, SHA-256]
+
+P = 9b8148421d365e0d5ba4381f07ea74f8b7d98a46c015bb441d029a1f87a814b2b2a10504a1716d8b962f49259b1cabcf8ff15356dbc24800fd6cced4a3dc8cd60f5eeea79c0b7d7f147d3e399368919059ed049d09361c03c22af9c443b7b5d1e7a2071414a2e7bba6119cf13077dd219026c57f935c788927e7b059c5e900a16fa972a8e95924353f13b17bd55e4e863f76edd2b935bc07b4c2b00a8fa77cdc92329c3184e12ac2b218203a4a213875955d71d20febea747bce392cd6b635ea6383480b40d820fa51dbfd1c133f1a4775439d05aa7c2afa608bc4248ca5470232825c256f99491019770302dbc8d356002d6119c3eef7df051cfbb42dd3b1b1
+Q = ebb856053a0087b2d2593c18b6fd1298ff6b8b87ead391d46189b46b6ef96425
+G = 866f5832bdc9f8d0529085ea156bbf33ceb24a9a7e8ee23c2a3e440458e894a0996deb241ebc61e1ebb01e2da5534dea4c73066ae2a3e26454d8c0da91ed0a364a6dce7661431c6dabd2af51b037ff5f095f717f272249c8196e9778d927d3cea5a493e9e6ab64e5de1ae40e53c6a94c5349aa279ec679d9e09c7470c7e69ad595de06f924fd85df95ce072eed6fba2ff744d4410901a8884f5da0792cd39870485e2129b2d2702af9a568870bb1a0929be1ebc6fe2f31d56ada2c286e6c28326063e60965283340dff893329fa7a0e02431499648c844b3b916301abed792cba1fcc4fe539ecfb4fd5d20f10e4e4322488b85895d13f6138b1c2b06c6006772
+domain_parameter_seed = 9f2ef71ddc5fd78c71894d4a4ab4a02d4aae730540306f7ad2546a26671cb25f
+counter = 75
+index = 01
+Result = P
+
+P = 9b8148421d365e0d5ba4381f07ea74f8b7d98a46c015bb441d029a1f87a814b2b2a10504a1716d8b962f49259b1cabcf8ff15356dbc24800fd6cced4a3dc8cd60f5eeea79c0b7d7f147d3e399368919059ed049d09361c03c22af9c443b7b5d1e7a2071414a2e7bba6119cf13077dd219026c57f935c788927e7b059c5e900a16fa972a8e95924353f13b17bd55e4e863f76edd2b935bc07b4c2b00a8fa77cdc92329c3184e12ac2b218203a4a213875955d71d20febea747bce392cd6b635ea6383480b40d820fa51dbfd1c133f1a4775439d05aa7c2afa608bc4248ca5470232825c256f99491019770302dbc8d356002d6119c3eef7df051cfbb42dd3b1b1
+Q = ebb856053a0087b2d2593c18b6fd1298ff6b8b87ead391d46189b46b6ef96425
+G = 866f5832bdc9f8d0529085ea156bbf33ceb24a9a7e8ee23c2a3e440458e894a0996deb241ebc61e1ebb01e2da5534dea4c73066ae2a3e26454d8c0da91ed0a364a6dce7661431c6dabd2af51b037ff5f095f717f272249c8196e9778d927d3cea5a493e9e6ab64e5de1ae40e53c6a94c5349aa279ec679d9e09c7470c7e69ad595de06f924fd85df95ce072eed6fba2ff744d4410901a8884f5da0792cd39870485e2129b2d2702af9a568870bb1a0929be1ebc6fe2f31d56ada2c286e6c28326063e60965283340dff893329fa7a0e02431499648c844b3b916301abed792cba1fcc4fe539ecfb4fd5d20f10e4e4322488b85895d13f6138b1c2b06c6006772
+domain_parameter_seed = 9f2ef71ddc5fd78c71894d4a4ab4a02d4aae730540306f7ad2546a26671cb25f
+counter = 75
+index = 02
+Result = F (index changed)
+";
+
+    // PQGVer style records for A.1.2.1.2 provable primes and A.2.3 generators,
+    // made with NSS's PQG_ParamGenV2 and judged by its PQG_VerifyParams
+    const PROVABLE: &str = "
+[mod = L=1024, N=160, SHA-1]
+
+P = a8af1ed934e1adb5d0f63b4d575a38f697d142a2127b6cfbf6b6992ed240a3371ac73f113363e9ba907e6d5fbea3f7cbe3f3bc9b6b43b2bf1fef219f2be771b2e61236585314adf5659819f5cebde251bcd8f7ec1be91bb48e178fa4f947cbe06b332087f25773b4475006a9279683be852d08a017cb45c13597946bb9bda28f
+Q = e2f4599f647d2b225f8a0ca20b3ae1543623fdf9
+G = 10021356fbeb85255e5fe55b4f4da978ef5768177df967b9a326d3e95e03bf99cff43e674d16371791b4ba0bb3471e178542f3176ab476c75f7950814ff45b208c0cfb3df850647121e07b846fa451c86e46c29ad11391d257998d2de4573e05a0f110f0ea0bd27bc8fc388301e4ce1e96fd9b4fd0954a7bf4d873ddde08926c
+firstseed = a7e02f640340957a215e801b83edeef803eb1c98
+pseed = a7e02f640340957a215e801b83edeef803eb1e76
+qseed = a7e02f640340957a215e801b83edeef803eb1d33
+pgen_counter = 154
+qgen_counter = 137
+index = 01
+Result = P
+
+P = a8af1ed934e1adb5d0f63b4d575a38f697d142a2127b6cfbf6b6992ed240a3371ac73f113363e9ba907e6d5fbea3f7cbe3f3bc9b6b43b2bf1fef219f2be771b2e61236585314adf5659819f5cebde251bcd8f7ec1be91bb48e178fa4f947cbe06b332087f25773b4475006a9279683be852d08a017cb45c13597946bb9bda28f
+Q = e2f4599f647d2b225f8a0ca20b3ae1543623fdf9
+G = 10021356fbeb85255e5fe55b4f4da978ef5768177df967b9a326d3e95e03bf99cff43e674d16371791b4ba0bb3471e178542f3176ab476c75f7950814ff45b208c0cfb3df850647121e07b846fa451c86e46c29ad11391d257998d2de4573e05a0f110f0ea0bd27bc8fc388301e4ce1e96fd9b4fd0954a7bf4d873ddde08926c
+firstseed = a7e02f640340957a215e801b83edeef803eb1c98
+pseed = a7e02f640340957a215e801b83edeef803eb1e76
+qseed = a7e02f640340957a215e801b83edeef803eb1d33
+pgen_counter = 155
+qgen_counter = 137
+index = 01
+Result = F (pgen_counter changed)
+
+[mod = L=2048, N=224, SHA-224]
+
+P = f8d722112f99723998bfe6e171b9891c0fe54a7281e9cf8ffb7b2e5a7fe41d9d56825c0f75442fee7259e640187098fec90ad874fc80776d11662cfaffb47d4887e38b47bea424953a73de18309f96f631ab6c281c9813877c8d5153d2bd4df9536c93abd071e272981f6259ce3bd96e915818f7196e562983a6105f9837588d939c0c3cb5c97231cd4a438c401548c12dd57e5628eb71802ff4f119dc64884e066a885058b1c1bb7724aa08f46ad1f8d8dbfc33ec2fc711ae8556c4b36b85fa3a57781ee76ffd780a5ce58ff06bc3e69a614a264e2270b88327ff9e5fd9ccdb005a37027ad9ae42a44cc8e6f7d2600048a3ac2018460bbc9893a7ba2d1e0165
+Q = a44eef6e108133df856270af7b06421bce3f2ed9f8f24b3ba0172d17
+G = edf49fadf6fb78ebea69557eaff75ed8c89477a6c0d96265d453c47ef2f15a83e2c40f9aa887032c316f683f3b7fe179768538e7f5023e8bee6bb581cbe16d77d699662311d5cefc4d9ce8f8ed9b6e40b11bee79238ab36b7cf46bf82c2e38142464c458ed79f1bbb8060502ddeac0b35d0353ad2c9f1c37c158d36a8e1570963dd6c9ba2638c9c3e0ba4a010b84b230f60223b0f0b96901f5021229ce8dbb70bccbce4da5c85399834a14607cb084c4f887ef2a90618472977dee77aef16199a1f9bef3f303e575273228d707b2f34833110744b7b75bfe542164676a43b211fbc8c58347cc24bcd627b94ebd936392675f3592d8c181110aba711f4d280be7
+firstseed = f75cf5c5a46b77ec872fe7ed88779003df04fcb8bd6c3adfd5e710bd
+pseed = f75cf5c5a46b77ec872fe7ed88779003df04fcb8bd6c3adfd5e76317
+qseed = f75cf5c5a46b77ec872fe7ed88779003df04fcb8bd6c3adfd5e7116e
+pgen_counter = 2926
+qgen_counter = 173
+index = 01
+Result = P
+
+P = f8d722112f99723998bfe6e171b9891c0fe54a7281e9cf8ffb7b2e5a7fe41d9d56825c0f75442fee7259e640187098fec90ad874fc80776d11662cfaffb47d4887e38b47bea424953a73de18309f96f631ab6c281c9813877c8d5153d2bd4df9536c93abd071e272981f6259ce3bd96e915818f7196e562983a6105f9837588d939c0c3cb5c97231cd4a438c401548c12dd57e5628eb71802ff4f119dc64884e066a885058b1c1bb7724aa08f46ad1f8d8dbfc33ec2fc711ae8556c4b36b85fa3a57781ee76ffd780a5ce58ff06bc3e69a614a264e2270b88327ff9e5fd9ccdb005a37027ad9ae42a44cc8e6f7d2600048a3ac2018460bbc9893a7ba2d1e0165
+Q = a44eef6e108133df856270af7b06421bce3f2ed9f8f24b3ba0172d17
+G = edf49fadf6fb78ebea69557eaff75ed8c89477a6c0d96265d453c47ef2f15a83e2c40f9aa887032c316f683f3b7fe179768538e7f5023e8bee6bb581cbe16d77d699662311d5cefc4d9ce8f8ed9b6e40b11bee79238ab36b7cf46bf82c2e38142464c458ed79f1bbb8060502ddeac0b35d0353ad2c9f1c37c158d36a8e1570963dd6c9ba2638c9c3e0ba4a010b84b230f60223b0f0b96901f5021229ce8dbb70bccbce4da5c85399834a14607cb084c4f887ef2a90618472977dee77aef16199a1f9bef3f303e575273228d707b2f34833110744b7b75bfe542164676a43b211fbc8c58347cc24bcd627b94ebd936392675f3592d8c181110aba711f4d280be7
+firstseed = f75cf5c5a46b77ec872fe7ed88779003df04fcb8bd6c3adfd5e710bd
+pseed = f75cf5c5a46b77ec872fe7ed88779003df04fcb8bd6c3adfd5e76317
+qseed = f75cf5c5a46b77ec872fe7ed88779003df04fcb8bd6c3adfd5e7116f
+pgen_counter = 2926
+qgen_counter = 173
+index = 01
+Result = F (qseed changed)
+
+[mod = L=2048, N=256, SHA-256]
+
+P = ebc2f08d4489bd549c0cdde926e0e421e79ed18179cbfe071f099f04e88de552783613a7f24d9fb113b53c453403f28657c5bbfb1715076a32acc34ec70e74dccdba2774a68f32e5a3556bb6961371f66a60b643728bb327a727e36bf310eb66717f95cbdcc4a869308924db7de67544637c8d0f927c2d0272c55b1561f9ecf1cd88c6e1c727afdfd2a00b636ca38860bd2abc70675d8be751655f04dae572d506c04e8437daed449492614ff0bcbdbaf51e758a1e34d8708f82c302c1cfc560a81e4979306d40cd97b4e502c45c482544027bbffd2c1f46a5cd5aa1a37c47841466472166225ac92d052dc721466b16f1eedb68e6227c786df6dd5785328ceb
+Q = bf9f55f35668141627878fcb271dd9d65fe9b69c657cd94cdf11845ad7fba217
+G = 6cfa04791d9aa2c9ef4b8cec02975ae2fc0cfb4bb4678a86e73834d1dfa175343100e7ef0ddee06f20c6ff5a3ca42c766b723cbc7b148b94fa7b5d9d9a3e649f2b3a8f188fd8fabb7bf3a30eccdbfa7d535befe90cc3d4005337745e1b994d59ec202aac1bff20274e01037f8fb9156a77d14f27819dbcea5b59b7e2cbaac051626d8ff7d50ce4a8f2fdbb6d83c4fd886a88ba97178392235539fe90dd7579fc8d0161468620d697931105063071d5ee0ea2c2a27a449df716d069cc5540d49c5116e5ccabf04ddc2f2499ccd1d07cfa6c8de61787a1d58038827ee8a7fd89a9aa0334f0fcf056bae9ac79ce25ea91ad529b036ebcc2aed2ddbf080f1a891a11
+firstseed = c8ef3957e5901acac5d384526586a2580080e25f987777b1e6cdc3b61b5ef32c
+pseed = c8ef3957e5901acac5d384526586a2580080e25f987777b1e6cdc3b61b5ef7e9
+qseed = c8ef3957e5901acac5d384526586a2580080e25f987777b1e6cdc3b61b5ef3cd
+pgen_counter = 238
+qgen_counter = 151
+index = 01
+Result = P
+
+P = ebc2f08d4489bd549c0cdde926e0e421e79ed18179cbfe071f099f04e88de552783613a7f24d9fb113b53c453403f28657c5bbfb1715076a32acc34ec70e74dccdba2774a68f32e5a3556bb6961371f66a60b643728bb327a727e36bf310eb66717f95cbdcc4a869308924db7de67544637c8d0f927c2d0272c55b1561f9ecf1cd88c6e1c727afdfd2a00b636ca38860bd2abc70675d8be751655f04dae572d506c04e8437daed449492614ff0bcbdbaf51e758a1e34d8708f82c302c1cfc560a81e4979306d40cd97b4e502c45c482544027bbffd2c1f46a5cd5aa1a37c47841466472166225ac92d052dc721466b16f1eedb68e6227c786df6dd5785328ceb
+Q = bf9f55f35668141627878fcb271dd9d65fe9b69c657cd94cdf11845ad7fba217
+G = 0d9796b75f9c2fc265d6667f40475135a045c4749956d85cceaa99649b779d70546cfdc488b0d81dbf53c2e75d58d7a664962ca144ad27c9774d65b93892b490e3165dd79c92eb2fca973f18a13fcf55139b6f0ce2bf75360e7345158291307cde68adc72415a2d5b49a60446cab00fcfe727e44e0d97ccadc78b8da845fc7961f23fd14eba214ee61be86ac2e503f561e1ca13a0dcd22be5de152a9b9e5dbdb1d8de5b18debcbd82ed03325127501ca5131d794c6f5a0d0c3f7dc369e0ae5a4de15552a8d59163f7db485bf3291a84c2780a77175574b264df9fe5d4f2d8a7026181f8a5bde86259425dca86770782d8a0885e76624b3329b293f574fd41138
+firstseed = c8ef3957e5901acac5d384526586a2580080e25f987777b1e6cdc3b61b5ef32c
+pseed = c8ef3957e5901acac5d384526586a2580080e25f987777b1e6cdc3b61b5ef7e9
+qseed = c8ef3957e5901acac5d384526586a2580080e25f987777b1e6cdc3b61b5ef3cd
+pgen_counter = 238
+qgen_counter = 151
+index = 01
+Result = F (G changed)
+";
+
+    // FIPS 186-3 SigVer records from NIST CAVP (CAVS 11.0), passing and failing
+    const SIG_VER: &str = "
+[mod = L=1024, N=160, SHA-1]
+
+P = dc5bf3a88b2d99e4c95cdd7a0501cc38630d425cf5c390af3429cff1f35147b795caea923f0d3577158f8a0c89dabd1962c2c453306b5d70cacfb01430aceb54e5a5fa6f9340d3bd2da612fceeb76b0ec1ebfae635a56ab141b108e00dc76eefe2edd0c514c21c457457c39065dba9d0ecb7569c247172d8438ad2827b60435b
+Q = e956602b83d195dbe945b3ac702fc61f81571f1d
+G = d7eb9ca20a3c7a079606bafc4c9261ccaba303a5dc9fe9953f197dfe548c234895baa77f441ee6a2d97b909cbbd26ff7b869d24cae51b5c6edb127a4b5d75cd8b46608bfa148249dffdb59807c5d7dde3fe3080ca3a2d28312142becb1fa8e24003e21c7287108174b95d5bc711e1c8d9b1076784f5dc37a964a5e51390da713
+
+Msg = 0fe1bfee500bdb76026099b1d37553f6bdfe48c82094ef98cb309dd777330bedfaa2f94c823ef74ef4074b50d8706041ac0e371c7c22dcf70263b8d60e17a86c7c379cfda8f22469e0df9d49d59439fc99891873628fff25dda5fac5ac794e948babdde968143ba05f1128f34fdad5875edc4cd71c6c24ba2060ffbd439ce2b3
+X = 1d93010c29ecfc432188942f46f19f44f0e1bb5d
+Y = 6240ea0647117c38fe705106d56db578f3e10130928452d4f3587881b8a2bc6873a8befc3237f20914e2a91c7f07a928ee22adeed23d74ab7f82ea11f70497e578f7a9b4cbd6f10226222b0b4da2ea1e49813d6bb9882fbf675c0846bb80cc891857b89b0ef1beb6cce3378a9aab5d66ad4cb9277cf447dfe1e64434749432fb
+R = b5af307867fb8b54390013cc67020ddf1f2c0b81
+S = 620d3b22ab5031440c3e35eab6f481298f9e9f08
+Result = P
+
+Msg = 97d50898025d2f9ba633866e968ca75e969d394edba6517204cb3dd537c2ba38778a2dc9dbc685a915e5676fcd43bc3726bc59ce3d7a9fae35565082a069c139fa37c90d922b126933db3fa6c5ef6b1edf00d174a51887bb76909c6a94fe994ecc7b7fc8f26113b17f30f9d01693df99a125b4f17e184331c6b6e8ca00f54f3a
+X = 350e13534692a7e0c4b7d58836046c436fbb2322
+Y = 69974de550fe6bd3099150faea1623ad3fb6d9bf23a07215093f319725ad0877accffd291b6da18eb0cbe51676ceb0977504eb97c27c0b191883f72fb2710a9fbd8bcf13be0bf854410b32f42b33ec89d3cc1cf892bcd536c4195ca9ada302ad600c3408739935d77dc247529ca47f844cc86f5016a2fe962c6e20ca7c4d4e8f
+R = b5d05faa7005764e8dae0327c5bf1972ff7681b9
+S = 18ea15bd9f00475b25204cbc23f8c23e01588015
+Result = F (3 - R changed )
+
+[mod = L=2048, N=256, SHA-384]
+
+P = e7c1c86125db9ef417da1ced7ea0861bdad629216a3f3c745df42a46b989e59f4d98425ee3c932fa3c2b6f637bdb6545bec526faa037e11f5578a4363b9fca5eba60d6a9cbaa2befd04141d989c7356285132c2eaf74f2d868521cdc0a17ae9a2546ef863027d3f8cc7949631fd0e2971417a912c8b8c5c989730db6ea6e8baee0e667850429038093c851ccb6fb173bb081e0efe0bd7450e0946888f89f75e443ab93ef2da293a01622cf43c6dd79625d41ba8f9ef7e3086ab39134283d8e96c89249488120fd061e4a87d34af41069c0b4fd3934c31b589cbe85b68b912718d5dab859fda7082511fad1d152044905005546e19b14aa96585a55269bf2b831
+Q = 8e056ec9d4b7acb580087a6ed9ba3478711bb025d5b8d9c731ef9b38bd43db2f
+G = dc2bfb9776786ad310c8b0cdcbba3062402613c67e6959a8d8d1b05aab636528b7b1fe9cd33765f853d6dbe13d09f2681f8c7b1ed7886aaed70c7bd76dbe858ffb8bd86235ddf759244678f428c6519af593dc94eeadbd9852ba2b3d61664e8d58c29d2039af3c3d6d16f90988f6a8c824569f3d48050e30896a9e17cd0232ef01ab8790008f6973b84c763a72f4ae8b485abfb7e8efeb86808fa2b281d3e5d65d28f5992a34c077c5aa8026cb2fbc34a45f7e9bd216b10e6f12ecb172e9a6eb8f2e91316905b6add1fd22e83bc2f089f1d5e6a6e6707c18ff55ddcb7954e8bceaf0efc4e8314910c03b0e51175f344faafee476a373ac95743cec712b72cf2e
+
+Msg = 6cd6ccfd66bcd832189c5f0c77994210e3bf2c43416f0fe77c4e92f31c5369538dc2c003f146c5ac79df43194ccf3c44d470d9f1083bd15b99b5bcf88c32d8a9021f09ea2288d7b3bf345a12aef3949c1e121b9fb371a67c2d1377364206ac839dd78483561426bda0303f285aa12e9c45d3cdfc6beae3549703b187deeb3296
+X = 56c897b5938ad5b3d437d7e4826da586a6b3be15e893fa1aaa946f20a028b6b3
+Y = 38ad44489e1a5778b9689f4dcf40e2acf23840fb954e987d6e8cb629106328ac64e1f3c3eba48b21176ad4afe3b733bead382ee1597e1b83e4b43424f2daaba04e5bd79e1436693ac2bddb79a298f026e57e200a252efd1e848a4a2e90be6e78f5242b468b9c0c6d2615047a5a40b9ae7e57a519114db55bf3bed65e580f894b094630ca9c217f6accd091e72d2f22da620044ff372d7273f9445017fad492959e59600b7494dbe766a03e40125d4e6747c76f68a5b0cdc0e7d7cee12d08c6fb7d0fb049e420a33405075ed4463296345ca695fb7feab7c1b5333ae519fcd4bb6a043f4555378969114743d4face96cad31c0e0089da4e3f61b6d7dabc088ab7
+R = 3b85b17be240ed658beb3652c9d93e8e9eea160d35ee2459614305802963374e
+S = 726800a5174a53b56dce86064109c0273cd11fcfa3c92c5cd6aa910260c0e3c7
+Result = F (1 - Message changed)
+
+Msg = 3ad6b0884f358dea09c31a9abc40c45a6000611fc2b907b30eac00413fd2819de7015488a411609d46c499b8f7afa1b78b352ac7f8535bd805b8ff2a5eae557098c668f7ccd73af886d6823a6d456c29931ee864ed46d767382785728c2a83fcff5271007d2a67d06fa205fd7b9d1a42ea5d6dc76e5e18a9eb148cd1e8b262ae
+X = 2faf566a9f057960f1b50c69508f483d9966d6e35743591f3a677a9dc40e1555
+Y = 926425d617babe87c442b03903e32ba5bbf0cd9d602b59c4df791a4d64a6d4333ca0c0d370552539197d327dcd1bbf8c454f24b03fc7805f862db34c7b066ddfddbb11dbd010b27123062d028fe041cb56a2e77488348ae0ab6705d87aac4d4e9e6600e9e706326d9979982cffa839beb9eacc3963bcca455a507e80c1c37ad4e765b2c9c0477a075e9bc584feacdf3a35a9391d4711f14e197c54022282bfed9a191213d64127f17a9c5affec26e0c71f15d3a5b16098fec118c45bf8bb2f3b1560df0949254c1c0aeb0a16d5a95a40fab8521fbe8ea77c51169b587cc3360e5733e6a23b9fded8c40724ea1f9e93614b3a6c9b4f8dbbe915b794497227ba62
+R = 343ea0a9e66277380f604d5880fca686bffab69ca97bfba015a102a7e23dce0e
+S = 6258488c770e0f5ad7b9da8bade5023fc0d17c6ec517bd08d53e6dc01ac5c2b3
+Result = P
+";
+
+    fn hex(value: &str) -> Vec<u8> {
+        (0..value.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&value[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn number(value: &str) -> BigUint {
+        BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+    }
+
+    // records of a CAVP .rsp file under their "[mod = L=.., N=.., SHA-..]" headers
+    fn read_rsp(text: &str) -> Vec<(HashAlgorithm, HashMap<&str, &str>)> {
+        let mut records = Vec::new();
+        let mut hash = None;
+        let mut record = HashMap::new();
+
+        for line in text.lines().map(str::trim).chain([""]) {
+            if let Some(header) = line.strip_prefix("[mod = ") {
+                hash = Some(match header.rsplit(", ").next().unwrap() {
+                    "SHA-1]" => HashAlgorithm::Sha1,
+                    "SHA-224]" => HashAlgorithm::Sha224,
+                    "SHA-256]" => HashAlgorithm::Sha256,
+                    "SHA-384]" => HashAlgorithm::Sha384,
+                    _ => HashAlgorithm::Sha512,
+                });
+            } else if let Some((name, value)) = line.split_once(" = ") {
+                record.insert(name, value);
+            } else if line.is_empty() && !record.is_empty() {
+                records.push((hash.unwrap(), std::mem::take(&mut record)));
+            }
+        }
+
+        records
+    }
+
+    fn parameters(record: &HashMap<&str, &str>) -> DomainParameters {
+        DomainParameters::new(
+            number(record["P"]),
+            number(record["Q"]),
+            number(record["G"]),
+        )
+        .unwrap()
+    }
+
+    // validates each record with its seed and index, the outcome has to match Result
+    fn check_records(text: &str, seed: impl Fn(&HashMap<&str, &str>) -> Seed) {
+        let mut results = Vec::new();
+
+        for (hash, record) in read_rsp(text) {
+            let params = parameters(&record);
+            let index = u8::from_str_radix(record["index"], 16).unwrap();
+            let validation = validate(&params, hash, Some(&seed(&record)), Some(index));
+
+            assert_eq!(
+                validation.is_ok(),
+                record["Result"].starts_with('P'),
+                "{}",
+                record["Result"]
+            );
+            if let Ok(validation) = validation {
+                assert_eq!(validation.primes, Assurance::Verified);
+                assert_eq!(validation.generator, Assurance::Verified);
+            }
+            results.push(validation.is_ok());
+        }
+
+        assert_eq!(results, [true, false, true, false, true, false]);
+    }
+
+    #[test]
+    fn test_known_parameters_validate() {
+        check_records(PROBABLE, |record| Seed::Probable {
+            seed: hex(record["domain_parameter_seed"]),
+            counter: record["counter"].parse().unwrap(),
+        });
+
+        for (hash, record) in read_rsp(PROBABLE) {
+            if !record["Result"].starts_with('P') {
+                continue;
+            }
+            let params = parameters(&record);
+            let seed = Seed::Probable {
+                seed: hex(record["domain_parameter_seed"]),
+                counter: record["counter"].parse().unwrap(),
+            };
+            let index = u8::from_str_radix(record["index"], 16).unwrap();
+
+            // without the seed only the partial checks are left
+            let validation = validate(&params, hash, None, None).unwrap();
+            assert_eq!(validation.primes, Assurance::Partial);
+            assert_eq!(validation.generator, Assurance::Partial);
+
+            // another element of order q passes only without the index
+            let g2 = params.g().modpow(&BigUint::from(2u8), params.p());
+            let other = DomainParameters::new(params.p().clone(), params.q().clone(), g2).unwrap();
+            assert!(validate(&other, hash, Some(&seed), None).is_ok());
+            assert!(validate(&other, hash, Some(&seed), Some(index)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_provable_parameters_validate() {
+        check_records(PROVABLE, |record| Seed::Provable {
+            first_seed: hex(record["firstseed"]),
+            p_seed: hex(record["pseed"]),
+            q_seed: hex(record["qseed"]),
+            p_counter: record["pgen_counter"].parse().unwrap(),
+            q_counter: record["qgen_counter"].parse().unwrap(),
+        });
+    }
+
+    #[test]
+    fn test_cavp_signatures_verify() {
+        let mut params = None;
+        let mut results = Vec::new();
+
+        for (hash, record) in read_rsp(SIG_VER) {
+            if record.contains_key("P") {
+                let domain = parameters(&record);
+                let validation = validate(&domain, hash, None, None).unwrap();
+                assert_eq!(validation.primes, Assurance::Partial);
+                params = Some(domain);
+                continue;
+            }
+
+            let key = DsaPublicKey::new(params.clone().unwrap(), number(record["Y"])).unwrap();
+            let signature = Signature {
+                r: number(record["R"]),
+                s: number(record["S"]),
+            };
+            let verified = key.verify(hash, &hex(record["Msg"]), &signature);
+            assert_eq!(
+                verified.is_ok(),
+                record["Result"].starts_with('P'),
+                "{}",
+                record["Result"]
+            );
+            results.push(verified.is_ok());
+        }
+
+        assert_eq!(results, [true, false, false, true]);
+    }
+
+    #[test]
+    fn test_generated_parameters_validate() {
+        for method in [Method::Probable, Method::Provable] {
+            let (params, seed) = generate(1024, 160, HashAlgorithm::Sha1, method, Some(1)).unwrap();
+            assert_eq!(params.sizes(), (1024, 160));
+            assert!(((params.p() - 1u8) % params.q()).is_zero());
+
+            let validation = validate(&params, HashAlgorithm::Sha1, Some(&seed), Some(1)).unwrap();
+            assert_eq!(validation.primes, Assurance::Verified);
+            assert_eq!(validation.generator, Assurance::Verified);
+            assert!(validate(&params, HashAlgorithm::Sha256, Some(&seed), Some(1)).is_err());
+        }
+
+        let (params, seed) =
+            generate(1024, 160, HashAlgorithm::Sha256, Method::Provable, None).unwrap();
+        let Seed::Provable { p_counter, .. } = &seed else {
+            unreachable!()
+        };
+        let mut wrong = seed.clone();
+        if let Seed::Provable {
+            p_counter: counter, ..
+        } = &mut wrong
+        {
+            *counter = p_counter + 1;
+        }
+        assert!(validate(&params, HashAlgorithm::Sha256, Some(&wrong), None).is_err());
+        assert_eq!(
+            validate(&params, HashAlgorithm::Sha256, Some(&seed), None).unwrap(),
+            Validation {
+                primes: Assurance::Verified,
+                generator: Assurance::Partial,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unapproved_sizes_are_refused() {
+        assert_eq!(
+            generate(1024, 256, HashAlgorithm::Sha256, Method::Probable, None),
+            Err(Error::ParameterSizes(1024, 256))
+        );
+        assert!(matches!(
+            generate(2048, 224, HashAlgorithm::Sha1, Method::Probable, None),
+            Err(Error::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+// Deterministic nonces after RFC 6979 section 3.2: k comes out of an HMAC-DRBG
+// seeded with the private key and the message hash, so the same message always
+// gets the same signature and signing needs no random generator. Only q is
+// involved, the nonces serve ECDSA over any group order as well as DSA.
+use crate::key::bits_to_int;
+use num_bigint::BigUint;
+use num_traits::Zero;
+use rsa_core::HashAlgorithm;
+
+/// Candidates for k in [1, q - 1], in the order the RFC tries them.
+pub struct Nonces<'a> {
+    hash: HashAlgorithm,
+    q: &'a BigUint,
+    k: Vec<u8>,
+    v: Vec<u8>,
+    started: bool,
+}
+
+// int2octets: `value` < q big endian over as many bytes as q takes
+fn int_to_octets(value: &BigUint, q: &BigUint) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut out = vec![0; (q.bits() as usize).div_ceil(8) - bytes.len()];
+    out.extend_from_slice(&bytes);
+    out
+}
+
+impl<'a> Nonces<'a> {
+    /// Steps a to g: the DRBG state for private key `x` and message `digest`.
+    pub fn new(hash: HashAlgorithm, q: &'a BigUint, x: &BigUint, digest: &[u8]) -> Self {
+        let private = int_to_octets(x, q);
+        // bits2octets reduces the digest below q first
+        let message = int_to_octets(&(bits_to_int(digest, q) % q), q);
+
+        let mut k = vec![0; hash.size()];
+        let mut v = vec![1; hash.size()];
+        for separator in [0u8, 1] {
+            k = hash.hmac(&k, &[&v, &[separator], &private, &message]);
+            v = hash.hmac(&k, &[&v]);
+        }
+
+        Nonces {
+            hash,
+            q,
+            k,
+            v,
+            started: false,
+        }
+    }
+
+    // step h.3, moving on after a candidate was refused
+    fn reseed(&mut self) {
+        self.k = self.hash.hmac(&self.k, &[&self.v, &[0]]);
+        self.v = self.hash.hmac(&self.k, &[&self.v]);
+    }
+}
+
+impl Iterator for Nonces<'_> {
+    type Item = BigUint;
+
+    /// Step h, never runs dry.
+    fn next(&mut self) -> Option<BigUint> {
+        if self.started {
+            self.reseed();
+        }
+        self.started = true;
+
+        loop {
+            let mut t = Vec::new();
+            while t.len() * 8 < self.q.bits() as usize {
+                self.v = self.hash.hmac(&self.k, &[&self.v]);
+                t.extend_from_slice(&self.v);
+            }
+
+            let k = bits_to_int(&t, self.q);
+            if !k.is_zero() && k < *self.q {
+                return Some(k);
+            }
+            self.reseed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(value: &str) -> BigUint {
+        BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+    }
+
+    #[test]
+    fn test_p256_nonce() {
+        // RFC 6979 appendix A.2.5, k for SHA-256 and message "sample"
+        let q = number("FFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551");
+        let x = number("C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721");
+        let digest = HashAlgorithm::Sha256.digest(b"sample");
+
+        let mut nonces = Nonces::new(HashAlgorithm::Sha256, &q, &x, &digest);
+        let k = nonces.next().unwrap();
+        assert_eq!(
+            k,
+            number("A6E3C57DD01ABE90086538398355DD4C3B17AA873382B0F24D6129493D8AAD60")
+        );
+
+        let next = nonces.next().unwrap();
+        assert!(next != k && next < q);
+        assert_eq!(
+            Nonces::new(HashAlgorithm::Sha256, &q, &x, &digest).next(),
+            Some(k)
+        );
+    }
+}
@@ -0,0 +1,4 @@
+// Key passphrases and X.509 handling shared by the rsa and dsa tools
+pub mod passphrase;
+#[cfg(feature = "openssl")]
+pub mod x509;
@@ -0,0 +1,586 @@
+// PKCS#10 certificate requests, X.509 v3 certificates and chain verification
+// for the keys the rsa and dsa tools generate
+use clap::{Args, ValueEnum};
+use openssl::asn1::{Asn1Integer, Asn1Time};
+use openssl::bn::{BigNum, MsbOption};
+use openssl::error::ErrorStack;
+use openssl::hash::MessageDigest;
+use openssl::pkey::{HasPublic, Id, PKeyRef, Private};
+use openssl::stack::Stack;
+use openssl::x509::extension::{
+    AuthorityKeyIdentifier, BasicConstraints, SubjectAlternativeName, SubjectKeyIdentifier,
+};
+use openssl::x509::store::X509StoreBuilder;
+use openssl::x509::{
+    X509Extension, X509Name, X509NameBuilder, X509Ref, X509Req, X509ReqBuilder, X509StoreContext,
+    X509v3Context, X509,
+};
+use std::fmt;
+use std::net::IpAddr;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum KeyUsage {
+    DigitalSignature,
+    NonRepudiation,
+    KeyEncipherment,
+    DataEncipherment,
+    KeyAgreement,
+    KeyCertSign,
+    CrlSign,
+}
+
+/// Subject and extensions of a request or certificate.
+#[derive(Args, Debug, Clone)]
+pub struct Profile {
+    /// Subject as comma separated attributes, e.g. "CN=api.internal,O=Example,C=UA"
+    #[arg(long)]
+    pub subject: Option<String>,
+
+    /// Subject alternative name, repeatable: dns:NAME, ip:ADDRESS, email:ADDRESS or uri:URI.
+    /// Without a prefix IP addresses are taken as ip and anything else as dns
+    #[arg(long)]
+    pub san: Vec<String>,
+
+    /// Key usage, repeatable
+    #[arg(long, value_enum)]
+    pub key_usage: Vec<KeyUsage>,
+
+    /// Mark as a certificate authority in basic constraints
+    #[arg(long)]
+    pub ca: bool,
+
+    /// Most intermediate CAs allowed below this CA
+    #[arg(long, requires = "ca")]
+    pub path_len: Option<u32>,
+}
+
+/// Validity period and serial number of a certificate.
+#[derive(Args, Debug, Clone)]
+pub struct Validity {
+    /// Days the certificate is valid for from its start
+    #[arg(long, default_value_t = 365)]
+    pub days: u32,
+
+    /// Start of the validity period as YYYYMMDDHHMMSSZ, now by default
+    #[arg(long)]
+    pub not_before: Option<String>,
+
+    /// End of the validity period as YYYYMMDDHHMMSSZ, overrides --days
+    #[arg(long)]
+    pub not_after: Option<String>,
+
+    /// Serial number, decimal or 0x prefixed hex. Random 159 bits by default
+    #[arg(long)]
+    pub serial: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidInput(String),
+    /// Chain didn't verify, `depth` 0 is the leaf
+    Verification {
+        depth: u32,
+        reason: String,
+    },
+    OpenSsl(ErrorStack),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+            Error::Verification { depth, reason } => {
+                write!(f, "Certificate at depth {}: {}", depth, reason)
+            }
+            Error::OpenSsl(e) => write!(f, "OpenSSL error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ErrorStack> for Error {
+    fn from(e: ErrorStack) -> Self {
+        Error::OpenSsl(e)
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> Error {
+    Error::InvalidInput(reason.into())
+}
+
+/// Parses "CN=name,O=organization", a backslash escapes a comma in a value.
+pub fn parse_subject(subject: &str) -> Result<X509Name, Error> {
+    let mut attributes = vec![String::new()];
+    let mut chars = subject.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => attributes.last_mut().unwrap().extend(chars.next()),
+            ',' => attributes.push(String::new()),
+            c => attributes.last_mut().unwrap().push(c),
+        }
+    }
+
+    let mut name = X509NameBuilder::new()?;
+    for attribute in &attributes {
+        let (field, value) = attribute
+            .split_once('=')
+            .ok_or_else(|| invalid(format!("subject attribute without '=': {}", attribute)))?;
+
+        name.append_entry_by_text(field.trim(), value.trim())
+            .map_err(|_| invalid(format!("unknown subject attribute {}", field.trim())))?;
+    }
+
+    Ok(name.build())
+}
+
+impl Profile {
+    fn extensions(&self, context: &X509v3Context) -> Result<Vec<X509Extension>, Error> {
+        let mut extensions = Vec::new();
+
+        let mut constraints = BasicConstraints::new();
+        constraints.critical();
+        if self.ca {
+            constraints.ca();
+        }
+        if let Some(path_len) = self.path_len {
+            constraints.pathlen(path_len);
+        }
+        extensions.push(constraints.build()?);
+
+        if !self.key_usage.is_empty() {
+            let mut usage = openssl::x509::extension::KeyUsage::new();
+            usage.critical();
+            for key_usage in &self.key_usage {
+                match key_usage {
+                    KeyUsage::DigitalSignature => usage.digital_signature(),
+                    KeyUsage::NonRepudiation => usage.non_repudiation(),
+                    KeyUsage::KeyEncipherment => usage.key_encipherment(),
+                    KeyUsage::DataEncipherment => usage.data_encipherment(),
+                    KeyUsage::KeyAgreement => usage.key_agreement(),
+                    KeyUsage::KeyCertSign => usage.key_cert_sign(),
+                    KeyUsage::CrlSign => usage.crl_sign(),
+                };
+            }
+            extensions.push(usage.build()?);
+        }
+
+        if !self.san.is_empty() {
+            let mut names = SubjectAlternativeName::new();
+            for san in &self.san {
+                match san.split_once(':') {
+                    Some(("dns", name)) => names.dns(name),
+                    Some(("ip", address)) => names.ip(address),
+                    Some(("email", address)) => names.email(address),
+                    Some(("uri", uri)) => names.uri(uri),
+                    _ if san.parse::<IpAddr>().is_ok() => names.ip(san),
+                    _ => names.dns(san),
+                };
+            }
+            extensions.push(
+                names
+                    .build(context)
+                    .map_err(|_| invalid("malformed subject alternative name"))?,
+            );
+        }
+
+        Ok(extensions)
+    }
+
+    fn subject(&self) -> Result<X509Name, Error> {
+        parse_subject(
+            self.subject
+                .as_deref()
+                .ok_or_else(|| invalid("--subject is required"))?,
+        )
+    }
+}
+
+impl Validity {
+    fn serial(&self) -> Result<Asn1Integer, Error> {
+        let serial = match self.serial.as_deref() {
+            Some(serial) => match serial.strip_prefix("0x") {
+                Some(hex) => BigNum::from_hex_str(hex),
+                None => BigNum::from_dec_str(serial),
+            }
+            .map_err(|_| invalid(format!("malformed serial number {}", serial)))?,
+            None => {
+                let mut serial = BigNum::new()?;
+                serial.rand(159, MsbOption::MAYBE_ZERO, false)?;
+                serial
+            }
+        };
+
+        // RFC 5280 section 4.1.2.2
+        if serial.is_negative() || serial.num_bits() == 0 || serial.num_bytes() > 20 {
+            return Err(invalid(
+                "serial number must be positive and at most 20 bytes",
+            ));
+        }
+
+        Ok(serial.to_asn1_integer()?)
+    }
+
+    fn period(&self) -> Result<(Asn1Time, Asn1Time), Error> {
+        let time = |value: &str| {
+            Asn1Time::from_str(value).map_err(|_| invalid(format!("malformed time {}", value)))
+        };
+
+        let not_before = match self.not_before.as_deref() {
+            Some(value) => time(value)?,
+            None => Asn1Time::days_from_now(0)?,
+        };
+        let not_after = match self.not_after.as_deref() {
+            Some(value) => time(value)?,
+            None => {
+                let start = Asn1Time::from_unix(0)?.diff(&not_before)?;
+                let seconds = start.days as i64 * 86400 + start.secs as i64;
+
+                Asn1Time::from_unix(seconds + self.days as i64 * 86400)?
+            }
+        };
+
+        if not_after <= not_before {
+            return Err(invalid("validity period ends before it starts"));
+        }
+
+        Ok((not_before, not_after))
+    }
+}
+
+// SHA-256 for every key the tools make, except EdDSA which hashes itself
+fn digest<T>(key: &PKeyRef<T>) -> MessageDigest {
+    match key.id() {
+        Id::ED25519 | Id::ED448 => MessageDigest::null(),
+        _ => MessageDigest::sha256(),
+    }
+}
+
+/// PKCS#10 request for `key` with the subject and extensions of `profile`.
+pub fn build_request(key: &PKeyRef<Private>, profile: &Profile) -> Result<X509Req, Error> {
+    let mut builder = X509ReqBuilder::new()?;
+    builder.set_version(0)?;
+    let subject = profile.subject()?;
+    builder.set_subject_name(&subject)?;
+    builder.set_pubkey(key)?;
+
+    let mut extensions = Stack::new()?;
+    for extension in profile.extensions(&builder.x509v3_context(None))? {
+        extensions.push(extension)?;
+    }
+    builder.add_extensions(&extensions)?;
+
+    builder.sign(key, digest(key))?;
+    Ok(builder.build())
+}
+
+/// Reads a PEM request and checks it's signed by the key it carries.
+pub fn load_request(pem: &[u8]) -> Result<X509Req, Error> {
+    let request = X509Req::from_pem(pem)?;
+
+    let key = request.public_key()?;
+    if !request.verify(&key)? {
+        return Err(invalid("request signature doesn't verify"));
+    }
+
+    Ok(request)
+}
+
+/// Certificate authority signing the certificate, the self-signed case when absent.
+pub struct Issuer<'a> {
+    pub certificate: &'a X509Ref,
+    pub key: &'a PKeyRef<Private>,
+}
+
+/// X.509 v3 certificate for `subject_key`. The subject comes from `profile`
+/// and the extensions always do, whatever a request asked for: the issuer decides them.
+pub fn build_certificate<T: HasPublic>(
+    subject_key: &PKeyRef<T>,
+    subject: &X509Name,
+    profile: &Profile,
+    validity: &Validity,
+    issuer: Issuer,
+) -> Result<X509, Error> {
+    let self_signed = issuer.certificate.subject_name().to_der()? == subject.to_der()?
+        && issuer.key.public_eq(subject_key);
+    if !self_signed && !issuer.certificate.public_key()?.public_eq(issuer.key) {
+        return Err(invalid("issuer key doesn't match the issuer certificate"));
+    }
+
+    let (not_before, not_after) = validity.period()?;
+    let mut builder = X509::builder()?;
+    builder.set_version(2)?;
+    let serial = validity.serial()?;
+    builder.set_serial_number(&serial)?;
+    builder.set_subject_name(subject)?;
+    builder.set_issuer_name(issuer.certificate.subject_name())?;
+    builder.set_pubkey(subject_key)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+
+    let context_issuer = (!self_signed).then_some(issuer.certificate);
+    for extension in profile.extensions(&builder.x509v3_context(context_issuer, None))? {
+        builder.append_extension(extension)?;
+    }
+    let key_id =
+        SubjectKeyIdentifier::new().build(&builder.x509v3_context(context_issuer, None))?;
+    builder.append_extension(key_id)?;
+    let authority_key_id = AuthorityKeyIdentifier::new()
+        .keyid(true)
+        .build(&builder.x509v3_context(context_issuer, None))?;
+    builder.append_extension(authority_key_id)?;
+
+    builder.sign(issuer.key, digest(issuer.key))?;
+    Ok(builder.build())
+}
+
+/// Self-signed certificate for `key` with the subject and extensions of `profile`.
+pub fn self_signed(
+    key: &PKeyRef<Private>,
+    profile: &Profile,
+    validity: &Validity,
+) -> Result<X509, Error> {
+    let subject = profile.subject()?;
+
+    // a stand-in issuer, only its subject is used
+    let mut placeholder = X509::builder()?;
+    placeholder.set_subject_name(&subject)?;
+    placeholder.set_pubkey(key)?;
+    let placeholder = placeholder.build();
+
+    build_certificate(
+        key,
+        &subject,
+        profile,
+        validity,
+        Issuer {
+            certificate: &placeholder,
+            key,
+        },
+    )
+}
+
+/// Certificate for the key and subject in `request`, signed by `issuer`.
+/// `profile.subject` overrides the requested subject.
+pub fn sign_request(
+    request: &X509Req,
+    profile: &Profile,
+    validity: &Validity,
+    issuer: Issuer,
+) -> Result<X509, Error> {
+    let subject = match profile.subject {
+        Some(_) => profile.subject()?,
+        None => request.subject_name().to_owned()?,
+    };
+
+    let key = request.public_key()?;
+    build_certificate(&key, &subject, profile, validity, issuer)
+}
+
+/// Verifies `chain`, leaf first, up to one of the trust `anchors` and
+/// returns the path it was verified along, leaf to anchor.
+pub fn verify_chain(chain: &[X509], anchors: &[X509]) -> Result<Vec<X509>, Error> {
+    let (leaf, intermediates) = chain
+        .split_first()
+        .ok_or_else(|| invalid("no certificates in the chain"))?;
+    if anchors.is_empty() {
+        return Err(invalid("no trust anchors"));
+    }
+
+    let mut store = X509StoreBuilder::new()?;
+    for anchor in anchors {
+        store.add_cert(anchor.clone())?;
+    }
+    let store = store.build();
+
+    let mut untrusted = Stack::new()?;
+    for certificate in intermediates {
+        untrusted.push(certificate.clone())?;
+    }
+
+    let mut context = X509StoreContext::new()?;
+    context.init(&store, leaf, &untrusted, |context| {
+        if context.verify_cert()? {
+            let path = context
+                .chain()
+                .map(|chain| chain.iter().map(|cert| cert.to_owned()).collect())
+                .unwrap_or_default();
+            Ok(Ok(path))
+        } else {
+            Ok(Err(Error::Verification {
+                depth: context.error_depth(),
+                reason: context.error().error_string().to_string(),
+            }))
+        }
+    })?
+}
+
+/// Subject of `certificate` in the comma separated form `parse_subject` reads.
+pub fn subject_line(certificate: &X509Ref) -> String {
+    certificate
+        .subject_name()
+        .entries()
+        .map(|entry| {
+            let field = entry.object().nid().short_name().unwrap_or("?");
+            let value = entry.data().to_string().unwrap_or_default();
+
+            format!("{}={}", field, value.replace(',', "\\,"))
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::dsa::Dsa;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    fn key() -> PKey<Private> {
+        PKey::from_dsa(Dsa::generate(2048).unwrap()).unwrap()
+    }
+
+    fn profile(subject: &str) -> Profile {
+        Profile {
+            subject: Some(subject.to_string()),
+            san: Vec::new(),
+            key_usage: Vec::new(),
+            ca: false,
+            path_len: None,
+        }
+    }
+
+    fn validity() -> Validity {
+        Validity {
+            days: 30,
+            not_before: None,
+            not_after: None,
+            serial: None,
+        }
+    }
+
+    #[test]
+    fn test_subject_parsing() {
+        let name = parse_subject("CN=api.internal, O=Example\\, Inc.,C=UA").unwrap();
+        let mut builder = X509::builder().unwrap();
+        builder.set_subject_name(&name).unwrap();
+        let certificate = builder.build();
+
+        assert_eq!(
+            subject_line(&certificate),
+            "CN=api.internal,O=Example\\, Inc.,C=UA"
+        );
+        assert!(parse_subject("CN").is_err());
+        assert!(parse_subject("XX=value").is_err());
+    }
+
+    #[test]
+    fn test_chain_through_intermediate() {
+        let (root_key, intermediate_key, leaf_key) = (key(), key(), key());
+
+        let mut root_profile = profile("CN=Root CA,O=Example");
+        root_profile.ca = true;
+        root_profile.key_usage = vec![KeyUsage::KeyCertSign, KeyUsage::CrlSign];
+        let root = self_signed(&root_key, &root_profile, &validity()).unwrap();
+
+        let mut intermediate_profile = profile("CN=Intermediate CA");
+        intermediate_profile.ca = true;
+        intermediate_profile.path_len = Some(0);
+        intermediate_profile.key_usage = vec![KeyUsage::KeyCertSign];
+        let request = build_request(&intermediate_key, &intermediate_profile).unwrap();
+        let request = load_request(&request.to_pem().unwrap()).unwrap();
+        let root_issuer = || Issuer {
+            certificate: &root,
+            key: &root_key,
+        };
+        let intermediate = sign_request(
+            &request,
+            &Profile {
+                subject: None,
+                ..intermediate_profile
+            },
+            &validity(),
+            root_issuer(),
+        )
+        .unwrap();
+        assert_eq!(subject_line(&intermediate), "CN=Intermediate CA");
+
+        let mut leaf_profile = profile("CN=api.internal");
+        leaf_profile.san = vec!["api.internal".into(), "ip:10.0.0.1".into()];
+        leaf_profile.key_usage = vec![KeyUsage::DigitalSignature];
+        let leaf_validity = Validity {
+            serial: Some("0x1f".into()),
+            ..validity()
+        };
+        let request = build_request(&leaf_key, &leaf_profile).unwrap();
+        let leaf = sign_request(
+            &request,
+            &leaf_profile,
+            &leaf_validity,
+            Issuer {
+                certificate: &intermediate,
+                key: &intermediate_key,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            leaf.serial_number().to_bn().unwrap(),
+            BigNum::from_u32(0x1f).unwrap()
+        );
+        assert_eq!(leaf.subject_alt_names().unwrap().len(), 2);
+
+        let anchors = [root.clone()];
+        let path = verify_chain(&[leaf.clone(), intermediate.clone()], &anchors).unwrap();
+        assert_eq!(path.len(), 3);
+
+        // missing intermediate, or an untrusted root
+        assert!(matches!(
+            verify_chain(std::slice::from_ref(&leaf), &anchors),
+            Err(Error::Verification { depth: 0, .. })
+        ));
+        let other_root = self_signed(&key(), &root_profile, &validity()).unwrap();
+        assert!(verify_chain(&[leaf.clone(), intermediate.clone()], &[other_root]).is_err());
+
+        // a leaf can't issue, it isn't a CA
+        let below_leaf = sign_request(
+            &build_request(&key(), &profile("CN=rogue")).unwrap(),
+            &profile("CN=rogue"),
+            &validity(),
+            Issuer {
+                certificate: &leaf,
+                key: &leaf_key,
+            },
+        )
+        .unwrap();
+        assert!(verify_chain(&[below_leaf, leaf, intermediate], &anchors).is_err());
+
+        // the issuer key has to match the issuer certificate
+        assert!(sign_request(
+            &request,
+            &leaf_profile,
+            &validity(),
+            Issuer {
+                certificate: &root,
+                key: &intermediate_key,
+            },
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_rsa_and_eddsa_keys() {
+        let mut ca_profile = profile("CN=Root CA");
+        ca_profile.ca = true;
+
+        for key in [
+            PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap(),
+            PKey::generate_ed25519().unwrap(),
+        ] {
+            let root = self_signed(&key, &ca_profile, &validity()).unwrap();
+            let anchors = [root];
+            let path = verify_chain(&anchors, &anchors).unwrap();
+            assert_eq!(path.len(), 1);
+        }
+    }
+}
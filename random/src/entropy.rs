@@ -0,0 +1,800 @@
+// NIST SP 800-90B non-IID min-entropy estimators (section 6.3)
+use std::collections::HashMap;
+use std::fmt;
+
+// z value for the 99% upper confidence bound used by every estimator
+const Z_ALPHA: f64 = 2.576;
+
+/// Input shorter than an estimator needs for a defined result.
+#[derive(Debug, PartialEq)]
+pub struct TooFewSamples {
+    pub estimator: &'static str,
+    pub needed: usize,
+    pub got: usize,
+}
+
+impl fmt::Display for TooFewSamples {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} needs at least {} samples, got {}", self.estimator, self.needed, self.got)
+    }
+}
+
+impl std::error::Error for TooFewSamples {}
+
+fn require(estimator: &'static str, samples: &[u8], needed: usize) -> Result<(), TooFewSamples> {
+    if samples.len() < needed {
+        return Err(TooFewSamples { estimator, needed, got: samples.len() });
+    }
+
+    Ok(())
+}
+
+pub struct Estimate {
+    pub name: &'static str,
+    /// Min-entropy per sample, `None` when the estimator doesn't apply
+    pub min_entropy: Option<f64>,
+}
+
+pub struct Report {
+    pub samples: usize,
+    pub bits_per_sample: u32,
+    pub original: Vec<Estimate>,
+    /// Estimates over the bitstring, empty for binary samples
+    pub bitstring: Vec<Estimate>,
+    pub min_entropy: f64,
+}
+
+/// Runs all estimators over `samples` holding `bits_per_sample` significant bits each.
+///
+/// Non-binary samples are additionally expanded into a bitstring, and the result
+/// is `min(H_original, bits_per_sample * H_bitstring)` as section 3.1.3 requires.
+/// Fails when `samples` is too short for any of the estimators.
+pub fn assess(samples: &[u8], bits_per_sample: u32) -> Result<Report, TooFewSamples> {
+    assert!(
+        (1..=8).contains(&bits_per_sample),
+        "Bits per sample should be in range 1..=8. Current value is: {}",
+        bits_per_sample
+    );
+
+    let k = 1usize << bits_per_sample;
+    let mut original = vec![
+        Estimate {
+            name: "most-common-value",
+            min_entropy: Some(most_common_value(samples)?),
+        },
+        Estimate {
+            name: "t-tuple",
+            min_entropy: t_tuple(samples),
+        },
+        Estimate {
+            name: "longest-repeated-substring",
+            min_entropy: lrs(samples),
+        },
+        Estimate {
+            name: "multi-mcw-prediction",
+            min_entropy: Some(multi_mcw(samples, k)?),
+        },
+        Estimate {
+            name: "lag-prediction",
+            min_entropy: Some(lag(samples, k)?),
+        },
+        Estimate {
+            name: "multi-mmc-prediction",
+            min_entropy: Some(multi_mmc(samples, k)?),
+        },
+        Estimate {
+            name: "lz78y-prediction",
+            min_entropy: Some(lz78y(samples, k)?),
+        },
+    ];
+    let mut bitstring = Vec::new();
+
+    if bits_per_sample == 1 {
+        original.extend(binary_estimates(samples)?);
+    } else {
+        let bits = to_bitstring(samples, bits_per_sample);
+
+        bitstring = assess(&bits, 1)?.original;
+    }
+
+    let minimum = |estimates: &[Estimate]| {
+        estimates
+            .iter()
+            .filter_map(|e| e.min_entropy)
+            .fold(bits_per_sample as f64, f64::min)
+    };
+    let min_entropy = if bitstring.is_empty() {
+        minimum(&original)
+    } else {
+        minimum(&original).min(bits_per_sample as f64 * minimum(&bitstring))
+    };
+
+    Ok(Report {
+        samples: samples.len(),
+        bits_per_sample,
+        original,
+        bitstring,
+        min_entropy,
+    })
+}
+
+fn binary_estimates(bits: &[u8]) -> Result<Vec<Estimate>, TooFewSamples> {
+    Ok(vec![
+        Estimate {
+            name: "collision",
+            min_entropy: Some(collision(bits)?),
+        },
+        Estimate {
+            name: "markov",
+            min_entropy: Some(markov(bits)?),
+        },
+        Estimate {
+            name: "compression",
+            min_entropy: compression(bits),
+        },
+    ])
+}
+
+/// Expands every sample into its `bits_per_sample` bits, most significant first.
+pub fn to_bitstring(samples: &[u8], bits_per_sample: u32) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|&s| (0..bits_per_sample).rev().map(move |b| (s >> b) & 1))
+        .collect()
+}
+
+fn upper_bound(p: f64, n: usize) -> f64 {
+    (p + Z_ALPHA * (p * (1.0 - p) / (n as f64 - 1.0)).sqrt()).min(1.0)
+}
+
+/// Most common value estimate (6.3.1).
+pub fn most_common_value(samples: &[u8]) -> Result<f64, TooFewSamples> {
+    require("most-common-value", samples, 2)?;
+
+    let mut counts = [0usize; 256];
+
+    for &s in samples {
+        counts[s as usize] += 1;
+    }
+
+    let p = *counts.iter().max().unwrap() as f64 / samples.len() as f64;
+
+    Ok(-upper_bound(p, samples.len()).log2())
+}
+
+/// Collision estimate (6.3.2), binary samples only.
+pub fn collision(bits: &[u8]) -> Result<f64, TooFewSamples> {
+    // two collisions at worst take three samples each
+    require("collision", bits, 6)?;
+
+    let mut times = Vec::new();
+    let mut index = 0;
+
+    // with two symbols a repeat shows up after two or three samples
+    while index + 1 < bits.len() {
+        if bits[index] == bits[index + 1] {
+            times.push(2.0);
+            index += 2;
+        } else if index + 2 < bits.len() {
+            times.push(3.0);
+            index += 3;
+        } else {
+            break;
+        }
+    }
+
+    let v = times.len() as f64;
+    let mean = times.iter().sum::<f64>() / v;
+    let sigma = (times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (v - 1.0)).sqrt();
+    let mean_lower = mean - Z_ALPHA * sigma / v.sqrt();
+
+    // for binary data the expected collision time of the spec reduces to 2 + 2pq
+    if mean_lower >= 2.5 {
+        return Ok(1.0);
+    }
+
+    let pq = ((mean_lower - 2.0) / 2.0).max(0.0);
+    let p = (1.0 + (1.0 - 4.0 * pq).sqrt()) / 2.0;
+
+    Ok(-p.log2())
+}
+
+/// Markov estimate (6.3.3), binary samples only.
+pub fn markov(bits: &[u8]) -> Result<f64, TooFewSamples> {
+    require("markov", bits, 2)?;
+
+    let n = bits.len();
+    let p0 = bits.iter().filter(|&&b| b == 0).count() as f64 / n as f64;
+    let p1 = 1.0 - p0;
+    let mut transitions = [[0f64; 2]; 2];
+
+    for w in bits.windows(2) {
+        transitions[w[0] as usize][w[1] as usize] += 1.0;
+    }
+
+    let row = |from: usize| {
+        let total = transitions[from][0] + transitions[from][1];
+
+        if total == 0.0 {
+            [0.0, 0.0]
+        } else {
+            [transitions[from][0] / total, transitions[from][1] / total]
+        }
+    };
+    let [p00, p01] = row(0);
+    let [p10, p11] = row(1);
+
+    // probabilities of the most likely 128-bit sequences
+    let candidates = [
+        p0 * p00.powi(127),
+        p0 * p01.powi(64) * p10.powi(63),
+        p0 * p01 * p11.powi(126),
+        p1 * p10 * p00.powi(126),
+        p1 * p10.powi(64) * p01.powi(63),
+        p1 * p11.powi(127),
+    ];
+    let p_max = candidates.into_iter().fold(0.0, f64::max);
+
+    Ok((-p_max.log2() / 128.0).min(1.0))
+}
+
+/// Compression estimate (6.3.4), binary samples only.
+pub fn compression(bits: &[u8]) -> Option<f64> {
+    const B: usize = 6;
+    const D: usize = 1000;
+
+    let blocks: Vec<usize> = bits
+        .chunks_exact(B)
+        .map(|c| c.iter().fold(0, |acc, &b| (acc << 1) | b as usize))
+        .collect();
+
+    if blocks.len() <= D + 1 {
+        return None;
+    }
+
+    let nu = blocks.len() - D;
+    let mut dict = [0usize; 1 << B];
+
+    for (i, &block) in blocks[..D].iter().enumerate() {
+        dict[block] = i + 1;
+    }
+
+    let mut log_distances = Vec::with_capacity(nu);
+
+    for (i, &block) in blocks.iter().enumerate().skip(D) {
+        let i = i + 1;
+        let distance = if dict[block] != 0 { i - dict[block] } else { i };
+
+        dict[block] = i;
+        log_distances.push((distance as f64).log2());
+    }
+
+    let mean = log_distances.iter().sum::<f64>() / nu as f64;
+    let square_mean = log_distances.iter().map(|d| d * d).sum::<f64>() / (nu - 1) as f64;
+    let sigma = 0.5907 * (square_mean - mean * mean).max(0.0).sqrt();
+    let mean_lower = mean - Z_ALPHA * sigma / (nu as f64).sqrt();
+
+    let symbols = (1 << B) as f64;
+    let expected = |p: f64| {
+        let q = (1.0 - p) / (symbols - 1.0);
+
+        maurer_g(p, D, blocks.len()) + (symbols - 1.0) * maurer_g(q, D, blocks.len())
+    };
+
+    let p = solve_decreasing(expected, mean_lower, 1.0 / symbols, 1.0).unwrap_or(1.0 / symbols);
+
+    Some(-p.log2() / B as f64)
+}
+
+// G(z) of the compression estimate, summed in O(n) with a running prefix over u
+fn maurer_g(z: f64, d: usize, total: usize) -> f64 {
+    let mut prefix = 0.0;
+    let mut power = 1.0;
+    let mut sum = 0.0;
+
+    for t in 1..=total {
+        let log_t = (t as f64).log2();
+
+        if t > d {
+            sum += prefix + z * power * log_t;
+        }
+
+        // power is (1 - z)^(t - 1)
+        prefix += z * z * power * log_t;
+        power *= 1.0 - z;
+    }
+
+    sum / (total - d) as f64
+}
+
+// finds x in [lo, hi] where the decreasing function f crosses target
+fn solve_decreasing(f: impl Fn(f64) -> f64, target: f64, lo: f64, hi: f64) -> Option<f64> {
+    if f(lo) < target {
+        return None;
+    }
+    if f(hi) > target {
+        return Some(hi);
+    }
+
+    let (mut lo, mut hi) = (lo, hi);
+
+    for _ in 0..64 {
+        let mid = (lo + hi) / 2.0;
+
+        if f(mid) > target {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    Some((lo + hi) / 2.0)
+}
+
+// suffix array with the longest common prefix of neighbouring suffixes
+struct Suffixes {
+    lcp: Vec<usize>,
+}
+
+impl Suffixes {
+    fn new(samples: &[u8]) -> Self {
+        let n = samples.len();
+        let mut sa: Vec<usize> = (0..n).collect();
+        let mut rank: Vec<usize> = samples.iter().map(|&s| s as usize).collect();
+        let mut next = vec![0; n];
+        let mut k = 1;
+
+        // prefix doubling
+        while k < n {
+            let key = |i: usize, rank: &[usize]| (rank[i], if i + k < n { rank[i + k] + 1 } else { 0 });
+
+            sa.sort_unstable_by_key(|&i| key(i, &rank));
+            next[sa[0]] = 0;
+            for w in 1..n {
+                next[sa[w]] = next[sa[w - 1]] + (key(sa[w - 1], &rank) != key(sa[w], &rank)) as usize;
+            }
+            std::mem::swap(&mut rank, &mut next);
+
+            if rank[sa[n - 1]] == n - 1 {
+                break;
+            }
+            k *= 2;
+        }
+
+        // Kasai, lcp[i] is shared by suffixes sa[i - 1] and sa[i]
+        let mut lcp = vec![0; n];
+        let mut inverse = vec![0; n];
+        let mut h = 0;
+
+        for (i, &s) in sa.iter().enumerate() {
+            inverse[s] = i;
+        }
+        for i in 0..n {
+            if inverse[i] > 0 {
+                let j = sa[inverse[i] - 1];
+
+                while i + h < n && j + h < n && samples[i + h] == samples[j + h] {
+                    h += 1;
+                }
+                lcp[inverse[i]] = h;
+                h = h.saturating_sub(1);
+            } else {
+                h = 0;
+            }
+        }
+
+        Suffixes { lcp }
+    }
+
+    /// Occurrence counts of every repeated `w`-tuple.
+    fn repeats(&self, w: usize) -> Vec<usize> {
+        let mut counts = Vec::new();
+        let mut run = 1;
+
+        for &l in self.lcp.iter().skip(1) {
+            if l >= w {
+                run += 1;
+            } else {
+                if run > 1 {
+                    counts.push(run);
+                }
+                run = 1;
+            }
+        }
+        if run > 1 {
+            counts.push(run);
+        }
+
+        counts
+    }
+
+    fn max_count(&self, w: usize) -> usize {
+        self.repeats(w).into_iter().max().unwrap_or(1)
+    }
+
+    fn longest_repeat(&self) -> usize {
+        self.lcp.iter().copied().max().unwrap_or(0)
+    }
+}
+
+/// t-Tuple estimate (6.3.5).
+pub fn t_tuple(samples: &[u8]) -> Option<f64> {
+    let suffixes = Suffixes::new(samples);
+    let n = samples.len();
+    let mut p_max = None::<f64>;
+    let mut t = 1;
+
+    loop {
+        let q = suffixes.max_count(t);
+
+        if q < 35 {
+            break;
+        }
+
+        let p = (q as f64 / (n - t + 1) as f64).powf(1.0 / t as f64);
+        p_max = Some(p_max.map_or(p, |m| m.max(p)));
+        t += 1;
+    }
+
+    p_max.map(|p| -upper_bound(p, n).log2())
+}
+
+/// Longest repeated substring estimate (6.3.6).
+pub fn lrs(samples: &[u8]) -> Option<f64> {
+    let suffixes = Suffixes::new(samples);
+    let n = samples.len();
+    let u = (1..).find(|&t| suffixes.max_count(t) < 35).unwrap();
+    let v = suffixes.longest_repeat();
+
+    if v < u {
+        return None;
+    }
+
+    let p_max = (u..=v)
+        .map(|w| {
+            let pairs: f64 = suffixes
+                .repeats(w)
+                .into_iter()
+                .map(|c| c as f64 * (c as f64 - 1.0) / 2.0)
+                .sum();
+            let tuples = (n - w + 1) as f64;
+
+            (pairs / (tuples * (tuples - 1.0) / 2.0)).powf(1.0 / w as f64)
+        })
+        .fold(0.0, f64::max);
+
+    Some(-upper_bound(p_max, n).log2())
+}
+
+/// Min-entropy from the outcome of a predictor over `correct` (6.3.7 steps 4-7),
+/// which needs at least two predictions for the confidence bound.
+fn predictor_entropy(correct: &[bool], k: usize) -> f64 {
+    let n = correct.len();
+    let hits = correct.iter().filter(|&&c| c).count();
+    let p_global = hits as f64 / n as f64;
+    let p_global_upper = if hits == 0 {
+        1.0 - 0.01f64.powf(1.0 / n as f64)
+    } else {
+        upper_bound(p_global, n)
+    };
+
+    let mut longest = 0;
+    let mut run = 0;
+    for &c in correct {
+        run = if c { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let r = (longest + 1) as f64;
+
+    // probability of seeing no run of r correct predictions, in log space
+    let no_run = |p: f64| {
+        let q = 1.0 - p;
+        let mut x: f64 = 1.0;
+
+        for _ in 0..10 {
+            x = 1.0 + q * p.powf(r) * x.powf(r + 1.0);
+        }
+
+        (1.0 - p * x).ln() - ((r + 1.0 - r * x) * q).ln() - (n as f64 + 1.0) * x.ln()
+    };
+    let p_local = solve_decreasing(no_run, 0.99f64.ln(), 1e-12, 1.0 - 1e-12).unwrap_or(0.0);
+
+    -p_global_upper.max(p_local).max(1.0 / k as f64).log2()
+}
+
+// picks the predictor that has been right most often, later predictors win ties
+struct Scoreboard {
+    scores: Vec<usize>,
+    winner: usize,
+}
+
+impl Scoreboard {
+    fn new(size: usize) -> Self {
+        Scoreboard {
+            scores: vec![0; size],
+            winner: 0,
+        }
+    }
+
+    fn update(&mut self, predictions: &[Option<u8>], actual: u8) {
+        for (j, prediction) in predictions.iter().enumerate() {
+            if *prediction == Some(actual) {
+                self.scores[j] += 1;
+
+                if self.scores[j] >= self.scores[self.winner] {
+                    self.winner = j;
+                }
+            }
+        }
+    }
+}
+
+/// Multi most common in window prediction estimate (6.3.7).
+pub fn multi_mcw(samples: &[u8], k: usize) -> Result<f64, TooFewSamples> {
+    const WINDOWS: [usize; 4] = [63, 255, 1023, 4095];
+
+    require("multi-mcw-prediction", samples, WINDOWS[0] + 2)?;
+
+    let mut counts = [[0usize; 256]; 4];
+    let mut last_seen = [0usize; 256];
+    let mut scoreboard = Scoreboard::new(WINDOWS.len());
+    let mut correct = Vec::with_capacity(samples.len().saturating_sub(WINDOWS[0]));
+    let symbols: Vec<usize> = {
+        let mut present: Vec<usize> = samples.iter().map(|&s| s as usize).collect();
+        present.sort_unstable();
+        present.dedup();
+        present
+    };
+
+    for (i, &s) in samples.iter().enumerate() {
+        if i >= WINDOWS[0] {
+            // most frequent value in each window, the most recent one on ties
+            let predictions: Vec<Option<u8>> = WINDOWS
+                .iter()
+                .enumerate()
+                .map(|(j, &w)| {
+                    if i < w {
+                        return None;
+                    }
+
+                    symbols
+                        .iter()
+                        .max_by_key(|&&v| (counts[j][v], last_seen[v]))
+                        .map(|&v| v as u8)
+                })
+                .collect();
+
+            correct.push(predictions[scoreboard.winner] == Some(s));
+            scoreboard.update(&predictions, s);
+        }
+
+        for (j, &w) in WINDOWS.iter().enumerate() {
+            counts[j][s as usize] += 1;
+
+            if i >= w {
+                counts[j][samples[i - w] as usize] -= 1;
+            }
+        }
+        last_seen[s as usize] = i + 1;
+    }
+
+    Ok(predictor_entropy(&correct, k))
+}
+
+/// Lag prediction estimate (6.3.8).
+pub fn lag(samples: &[u8], k: usize) -> Result<f64, TooFewSamples> {
+    const D: usize = 128;
+
+    require("lag-prediction", samples, 3)?;
+
+    let mut scoreboard = Scoreboard::new(D);
+    let mut correct = Vec::with_capacity(samples.len().saturating_sub(1));
+
+    for i in 1..samples.len() {
+        let predictions: Vec<Option<u8>> = (1..=D)
+            .map(|d| if d <= i { Some(samples[i - d]) } else { None })
+            .collect();
+
+        correct.push(predictions[scoreboard.winner] == Some(samples[i]));
+        scoreboard.update(&predictions, samples[i]);
+    }
+
+    Ok(predictor_entropy(&correct, k))
+}
+
+// most frequent follower, the greatest value on ties
+fn most_frequent(followers: &HashMap<u8, usize>) -> Option<(u8, usize)> {
+    followers
+        .iter()
+        .map(|(&y, &count)| (y, count))
+        .max_by_key(|&(y, count)| (count, y))
+}
+
+/// Multi Markov model with counting prediction estimate (6.3.9).
+pub fn multi_mmc(samples: &[u8], k: usize) -> Result<f64, TooFewSamples> {
+    const D: usize = 16;
+    const MAX_ENTRIES: usize = 100_000;
+
+    require("multi-mmc-prediction", samples, 4)?;
+
+    let mut models: Vec<HashMap<&[u8], HashMap<u8, usize>>> = vec![HashMap::new(); D];
+    let mut scoreboard = Scoreboard::new(D);
+    let mut correct = Vec::with_capacity(samples.len().saturating_sub(2));
+
+    for i in 2..samples.len() {
+        for (d, model) in models.iter_mut().enumerate() {
+            let d = d + 1;
+
+            if d < i {
+                let context = &samples[i - d - 1..i - 1];
+
+                if let Some(followers) = model.get_mut(context) {
+                    *followers.entry(samples[i - 1]).or_insert(0) += 1;
+                } else if model.len() < MAX_ENTRIES {
+                    model.insert(context, HashMap::from([(samples[i - 1], 1)]));
+                }
+            }
+        }
+
+        let predictions: Vec<Option<u8>> = models
+            .iter()
+            .enumerate()
+            .map(|(d, model)| {
+                let d = d + 1;
+
+                if d > i {
+                    return None;
+                }
+
+                model
+                    .get(&samples[i - d..i])
+                    .and_then(most_frequent)
+                    .map(|(y, _)| y)
+            })
+            .collect();
+
+        correct.push(predictions[scoreboard.winner] == Some(samples[i]));
+        scoreboard.update(&predictions, samples[i]);
+    }
+
+    Ok(predictor_entropy(&correct, k))
+}
+
+/// LZ78Y prediction estimate (6.3.10).
+pub fn lz78y(samples: &[u8], k: usize) -> Result<f64, TooFewSamples> {
+    const B: usize = 16;
+    const MAX_DICTIONARY: usize = 65536;
+
+    require("lz78y-prediction", samples, B + 3)?;
+
+    let mut dictionary: HashMap<&[u8], HashMap<u8, usize>> = HashMap::new();
+    let mut correct = Vec::with_capacity(samples.len().saturating_sub(B + 1));
+
+    for i in B + 1..samples.len() {
+        for j in (1..=B).rev() {
+            let context = &samples[i - j - 1..i - 1];
+
+            if let Some(followers) = dictionary.get_mut(context) {
+                *followers.entry(samples[i - 1]).or_insert(0) += 1;
+            } else if dictionary.len() < MAX_DICTIONARY {
+                dictionary.insert(context, HashMap::from([(samples[i - 1], 1)]));
+            }
+        }
+
+        let mut prediction = None;
+        let mut max_count = 0;
+
+        for j in (1..=B).rev() {
+            if let Some((y, count)) = dictionary.get(&samples[i - j..i]).and_then(most_frequent) {
+                if count > max_count {
+                    prediction = Some(y);
+                    max_count = count;
+                }
+            }
+        }
+
+        correct.push(prediction == Some(samples[i]));
+    }
+
+    Ok(predictor_entropy(&correct, k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LCGRandom;
+
+    fn uniform(n: usize, k: u32) -> Vec<u8> {
+        let mut random = LCGRandom::preset("minstd").unwrap();
+
+        (0..n).map(|_| random.gen_range(0..k) as u8).collect()
+    }
+
+    fn biased_bits(n: usize, p_one: f64) -> Vec<u8> {
+        let mut random = LCGRandom::preset("minstd").unwrap();
+
+        (0..n).map(|_| (random.gen_f64() < p_one) as u8).collect()
+    }
+
+    #[test]
+    fn test_constant_source_has_no_entropy() {
+        let samples = vec![7u8; 5000];
+
+        assert_eq!(most_common_value(&samples).unwrap(), 0.0);
+        assert!(assess(&samples, 3).unwrap().min_entropy < 0.01);
+    }
+
+    #[test]
+    fn test_biased_bits() {
+        // min-entropy of a 3:1 biased coin is -log2(0.75) = 0.415
+        let bits = biased_bits(100_000, 0.75);
+
+        for h in [most_common_value(&bits).unwrap(), collision(&bits).unwrap(), markov(&bits).unwrap()] {
+            assert!((0.36..0.43).contains(&h), "estimate {}", h);
+        }
+        assert!(compression(&bits).unwrap() < 0.6);
+    }
+
+    #[test]
+    fn test_uniform_bytes() {
+        let samples = uniform(20_000, 256);
+
+        // confidence bounds keep estimates of small datasets below 8 bits
+        assert!(most_common_value(&samples).unwrap() > 7.0);
+        assert!(multi_mcw(&samples, 256).unwrap() > 7.0);
+        assert!(lag(&samples, 256).unwrap() > 6.5);
+        assert!(lz78y(&samples, 256).unwrap() > 6.5);
+    }
+
+    #[test]
+    fn test_predictors_catch_patterns() {
+        let periodic: Vec<u8> = (0..20_000).map(|i| [3, 1, 4, 0, 5, 9, 2, 6][i % 8]).collect();
+        let alternating: Vec<u8> = (0..20_000).map(|i| (i % 2) as u8).collect();
+
+        assert!(lag(&periodic, 16).unwrap() < 0.01);
+        assert!(multi_mmc(&periodic, 16).unwrap() < 0.01);
+        assert!(lz78y(&periodic, 16).unwrap() < 0.01);
+        assert!(markov(&alternating).unwrap() < 0.01);
+        // alternating bits look perfectly balanced to the most common value estimate
+        assert!(most_common_value(&alternating).unwrap() > 0.95);
+        assert!(lrs(&periodic).unwrap() < 0.1);
+    }
+
+    #[test]
+    fn test_t_tuple_and_lrs_on_uniform_bits() {
+        let bits = uniform(20_000, 2);
+
+        assert!(t_tuple(&bits).unwrap() > 0.8);
+        assert!(lrs(&bits).unwrap() > 0.8);
+    }
+
+    #[test]
+    fn test_too_few_samples() {
+        assert_eq!(
+            most_common_value(&[]),
+            Err(TooFewSamples { estimator: "most-common-value", needed: 2, got: 0 })
+        );
+        for estimate in [
+            collision(&[0, 1]),
+            markov(&[1]),
+            lag(&[5, 5], 256),
+            lz78y(&[5; 18], 256),
+        ] {
+            assert!(estimate.is_err());
+        }
+        assert!(t_tuple(&[]).is_none());
+        assert!(lrs(&[]).is_none());
+        assert!(compression(&[]).is_none());
+
+        // multi-mcw-prediction needs the most with 65 samples
+        let samples = uniform(65, 2);
+
+        assert!(matches!(assess(&[], 8), Err(TooFewSamples { got: 0, .. })));
+        assert!(matches!(
+            assess(&samples[..64], 1),
+            Err(TooFewSamples { estimator: "multi-mcw-prediction", needed: 65, .. })
+        ));
+        assert!(assess(&samples, 1).unwrap().min_entropy.is_finite());
+        assert!(assess(&samples, 4).unwrap().min_entropy.is_finite());
+    }
+}
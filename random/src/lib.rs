@@ -1,50 +1,515 @@
+use std::fmt;
+use std::ops::Range;
+
+pub mod entropy;
+pub mod presets;
+pub mod stats;
+
+/// Largest modulus `LCGRandom::period` accepts, finding the cycle takes up to
+/// three times as many steps as the modulus.
+pub const MAX_PERIOD_MODULUS: u64 = 1 << 32;
+
+#[derive(Debug, PartialEq)]
+pub struct ModulusTooLarge(pub u64);
+
+impl fmt::Display for ModulusTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "modulus {} is above the limit of {}", self.0, MAX_PERIOD_MODULUS)
+    }
+}
+
+impl std::error::Error for ModulusTooLarge {}
+
 // linear congruential generator
 pub struct LCGRandom {
     a: u128,
     c: u128,
     m: u128,
     s: u128,
+    shift: u32,
+    mask: u128,
 }
 
 impl LCGRandom {
-    pub fn new(a: u32, c: u32, m: u32, s: u32) -> Self {
+    pub fn new(a: u64, c: u64, m: u64, s: u64) -> Self {
+        if m == 0 {
+            panic!("Modulus should be positive. Current value is: {}", m);
+        }
+
         LCGRandom {
             a: a as u128,
             c: c as u128,
             m: m as u128,
             s: s as u128,
+            shift: 0,
+            mask: u32::MAX as u128,
         }
     }
 
+    /// Generator with parameters from the `presets` catalogue.
+    pub fn preset(name: &str) -> Option<Self> {
+        presets::find(name).map(|preset| preset.build())
+    }
+
+    /// Output `(state >> shift) & mask` instead of the whole state.
+    /// States wider than 32 bits are truncated to their low bits otherwise.
+    pub fn with_output(mut self, shift: u32, mask: u64) -> Self {
+        if mask == 0 || mask > u32::MAX as u64 {
+            panic!("Mask should fit into 32 bits. Current value is: {:#x}", mask);
+        }
+
+        self.shift = shift;
+        self.mask = mask as u128;
+        self
+    }
+
+    /// Number of distinct values `generate` can return.
+    pub fn output_range(&self) -> u64 {
+        (((self.m - 1) >> self.shift).min(self.mask) + 1) as u64
+    }
+
+    fn step(&mut self) {
+        self.s = (self.a * self.s + self.c) % self.m;
+    }
+
+    fn output(&self) -> u32 {
+        ((self.s >> self.shift) & self.mask) as u32
+    }
+
     pub fn generate(&mut self) -> u32 {
-        let seed = (self.a * self.s + self.c) % self.m;
-        self.s = seed;
+        self.step();
 
-        seed as u32
+        self.output()
     }
 
     pub fn generate_n(&mut self, n: u32) -> u32 {
         for _ in 0..n {
-            self.generate();
+            self.step();
         }
 
-        self.s as u32
+        self.output()
     }
 
-    pub fn period(a: u32, c: u32, m: u32, s: u32) -> u32 {
+    /// Cycle length the sequence from `s` ends up in, for moduli up to
+    /// `MAX_PERIOD_MODULUS` only.
+    pub fn period(a: u64, c: u64, m: u64, s: u64) -> Result<u64, ModulusTooLarge> {
+        if m > MAX_PERIOD_MODULUS {
+            return Err(ModulusTooLarge(m));
+        }
+
         let mut slow: LCGRandom = LCGRandom::new(a, c, m, s);
         let mut fast: LCGRandom = LCGRandom::new(a, c, m, s);
         let mut i = 0;
 
-        while slow.generate() != fast.generate_n(2) {}
+        loop {
+            slow.step();
+            fast.step();
+            fast.step();
+
+            if slow.s == fast.s {
+                break;
+            }
+        }
 
         loop {
-            fast.generate();
+            fast.step();
             i += 1;
 
             if slow.s == fast.s {
-                return i;
+                return Ok(i);
+            }
+        }
+    }
+
+    /// Number of uniformly distributed bits a single draw can provide.
+    pub fn bits_per_draw(&self) -> u32 {
+        63 - self.output_range().leading_zeros()
+    }
+
+    /// Draws until the output fits into `bits_per_draw` bits, so every returned
+    /// bit pattern is equally likely even when the range isn't a power of two.
+    fn next_bits(&mut self) -> u32 {
+        let bits = self.bits_per_draw();
+        assert!(bits > 0, "Output range {} can't produce random bits", self.output_range());
+
+        loop {
+            let value = self.generate() as u128;
+
+            if value < 1 << bits {
+                return value as u32;
             }
         }
     }
+
+    /// Uniform integer in `range` using rejection sampling over the generator output range.
+    /// Ranges wider than the output range take several draws side by side.
+    pub fn gen_range(&mut self, range: Range<u32>) -> u32 {
+        assert!(range.start < range.end, "Empty range {:?}", range);
+
+        let n = (range.end - range.start) as u64;
+        let output_range = self.output_range();
+
+        if n > output_range {
+            // as many bits as n - 1 takes, at most half of the values are rejected
+            let bits = 64 - (n - 1).leading_zeros();
+
+            loop {
+                let value = self.next_wide(bits);
+
+                if value < n {
+                    return range.start + value as u32;
+                }
+            }
+        }
+
+        // largest multiple of n that fits into the output range, values above it are biased
+        let zone = output_range - output_range % n;
+
+        loop {
+            let value = self.generate() as u64;
+
+            if value < zone {
+                return range.start + (value % n) as u32;
+            }
+        }
+    }
+
+    /// `bits` uniform bits, concatenated from as many draws as it takes.
+    /// A draw wider than what's still missing gives its high bits only.
+    fn next_wide(&mut self, bits: u32) -> u64 {
+        let per_draw = self.bits_per_draw();
+        let mut value = 0u64;
+        let mut collected = 0;
+
+        while collected < bits {
+            let take = per_draw.min(bits - collected);
+            value = (value << take) | (self.next_bits() >> (per_draw - take)) as u64;
+            collected += take;
+        }
+
+        value
+    }
+
+    /// Uniform float in [0, 1) built from 53 random bits.
+    pub fn gen_f64(&mut self) -> f64 {
+        self.next_wide(53) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Normal distribution sample (Box-Muller transform).
+    pub fn gen_normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        let u1 = 1.0 - self.gen_f64();
+        let u2 = self.gen_f64();
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+
+        mean + std_dev * z
+    }
+
+    /// Exponential distribution sample with rate `lambda` (inverse transform).
+    pub fn gen_exponential(&mut self, lambda: f64) -> f64 {
+        assert!(lambda > 0.0, "Rate should be positive. Current value is: {}", lambda);
+
+        -(1.0 - self.gen_f64()).ln() / lambda
+    }
+
+    /// Poisson distribution sample with mean `lambda`.
+    ///
+    /// Knuth's multiplication method for small means, Hörmann's PTRS
+    /// transformed rejection for large ones.
+    pub fn gen_poisson(&mut self, lambda: f64) -> u64 {
+        assert!(lambda > 0.0, "Mean should be positive. Current value is: {}", lambda);
+
+        if lambda < 30.0 {
+            let limit = (-lambda).exp();
+            let mut k = 0;
+            let mut p = self.gen_f64();
+
+            while p > limit {
+                k += 1;
+                p *= self.gen_f64();
+            }
+
+            return k;
+        }
+
+        let slam = lambda.sqrt();
+        let loglam = lambda.ln();
+        let b = 0.931 + 2.53 * slam;
+        let a = -0.059 + 0.02483 * b;
+        let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+        let v_r = 0.9277 - 3.6224 / (b - 2.0);
+
+        loop {
+            let u = self.gen_f64() - 0.5;
+            let v = self.gen_f64();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+
+            if us >= 0.07 && v <= v_r {
+                return k as u64;
+            }
+
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+
+            let log_v = (v * inv_alpha / (a / (us * us) + b)).ln();
+            if log_v <= -lambda + k * loglam - ln_factorial(k) {
+                return k as u64;
+            }
+        }
+    }
+
+    /// Fisher-Yates shuffle of `items` in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.gen_range(0..i as u32 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    /// Picks `k` items uniformly without replacement (reservoir sampling).
+    pub fn sample<T, I: IntoIterator<Item = T>>(&mut self, items: I, k: usize) -> Vec<T> {
+        let mut reservoir = Vec::with_capacity(k);
+
+        for (i, item) in items.into_iter().enumerate() {
+            if i < k {
+                reservoir.push(item);
+            } else {
+                let j = self.gen_range(0..i as u32 + 1) as usize;
+
+                if j < k {
+                    reservoir[j] = item;
+                }
+            }
+        }
+
+        reservoir
+    }
+}
+
+/// Continuous stream of 32-bit words out of generators with narrower output.
+/// `bits_per_draw` bits of every draw follow each other, most significant
+/// first, so no bit of a word is stuck at zero.
+#[derive(Default)]
+pub struct Words {
+    buffer: u64,
+    buffered: u32,
+}
+
+impl Words {
+    pub fn word(&mut self, random: &mut LCGRandom) -> u32 {
+        let bits = random.bits_per_draw();
+
+        while self.buffered < 32 {
+            self.buffer = (self.buffer << bits) | random.next_bits() as u64;
+            self.buffered += bits;
+        }
+
+        self.buffered -= 32;
+        let word = (self.buffer >> self.buffered) as u32;
+        self.buffer &= (1 << self.buffered) - 1;
+
+        word
+    }
+}
+
+fn ln_factorial(k: f64) -> f64 {
+    if k < 10.0 {
+        return (2..=k as u64).map(|i| (i as f64).ln()).sum();
+    }
+
+    // Stirling series
+    let k1 = k + 1.0;
+    (k1 - 0.5) * k1.ln() - k1 + 0.5 * (2.0 * std::f64::consts::PI).ln() + 1.0 / (12.0 * k1)
+        - 1.0 / (360.0 * k1.powi(3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // statistic is compared against critical values for p = 0.001
+    fn chi_square(counts: &[u64], expected: &[f64]) -> f64 {
+        counts
+            .iter()
+            .zip(expected)
+            .map(|(&o, &e)| (o as f64 - e).powi(2) / e)
+            .sum()
+    }
+
+    fn minstd() -> LCGRandom {
+        LCGRandom::new(48271, 0, 2147483647, 1)
+    }
+
+    #[test]
+    fn test_period() {
+        assert_eq!(LCGRandom::period(5, 3, 16, 0), Ok(16));
+        // multiplicative generators never leave 0, and 1 cycles through the powers of 5 mod 16
+        assert_eq!(LCGRandom::period(5, 0, 16, 0), Ok(1));
+        assert_eq!(LCGRandom::period(5, 0, 16, 1), Ok(4));
+        assert_eq!(
+            LCGRandom::period(25214903917, 11, 1 << 48, 0),
+            Err(ModulusTooLarge(1 << 48))
+        );
+    }
+
+    #[test]
+    fn test_gen_range_removes_modulo_bias() {
+        // full period generator over 16 values: `% 6` hits 0..4 three times, 4..6 twice
+        let mut modulo = LCGRandom::new(5, 3, 16, 0);
+        let mut ranged = LCGRandom::new(5, 3, 16, 0);
+        let mut modulo_counts = [0u64; 6];
+        let mut ranged_counts = [0u64; 6];
+
+        for _ in 0..16 * 100 {
+            modulo_counts[(modulo.generate() % 6) as usize] += 1;
+        }
+        for _ in 0..6 * 200 {
+            ranged_counts[ranged.gen_range(0..6) as usize] += 1;
+        }
+
+        assert_ne!(modulo_counts[0], modulo_counts[5]);
+        assert!(ranged_counts.iter().all(|&c| c == 200));
+    }
+
+    #[test]
+    fn test_gen_range_chi_square() {
+        let mut random = minstd();
+        let mut counts = [0u64; 10];
+
+        for _ in 0..100_000 {
+            let value = random.gen_range(10..20);
+            assert!((10..20).contains(&value));
+            counts[(value - 10) as usize] += 1;
+        }
+
+        // 9 degrees of freedom
+        assert!(chi_square(&counts, &[10_000.0; 10]) < 27.877);
+    }
+
+    #[test]
+    fn test_gen_range_wider_than_output() {
+        // msvc draws 15 bits, 0..100_000 needs 17 of them
+        let mut random = LCGRandom::preset("msvc").unwrap();
+        let mut counts = [0u64; 10];
+
+        for _ in 0..100_000 {
+            let value = random.gen_range(0..100_000);
+            assert!(value < 100_000);
+            counts[(value / 10_000) as usize] += 1;
+        }
+
+        assert!(chi_square(&counts, &[10_000.0; 10]) < 27.877);
+
+        let mut items: Vec<u32> = (0..40_000).collect();
+        random.shuffle(&mut items);
+        assert_ne!(items[..10], [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(random.sample(0..40_000, 5).len(), 5);
+    }
+
+    #[test]
+    fn test_gen_f64_chi_square() {
+        let mut random = minstd();
+        let mut counts = [0u64; 20];
+
+        for _ in 0..100_000 {
+            let value = random.gen_f64();
+            assert!((0.0..1.0).contains(&value));
+            counts[(value * 20.0) as usize] += 1;
+        }
+
+        // 19 degrees of freedom
+        assert!(chi_square(&counts, &[5_000.0; 20]) < 43.820);
+    }
+
+    #[test]
+    fn test_gen_f64_narrow_output() {
+        // 24 bits a draw, 53 don't split into whole draws
+        let mut random = LCGRandom::preset("vb6").unwrap();
+        let n = 100_000;
+        let values: Vec<f64> = (0..n).map(|_| random.gen_f64()).collect();
+
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let max = values.iter().cloned().fold(0.0, f64::max);
+        assert!((mean - 0.5).abs() < 0.01);
+        assert!(max > 0.999 && max < 1.0);
+    }
+
+    #[test]
+    fn test_words_pack_narrow_output() {
+        // msvc draws 15 bits, a word takes two draws and the top 2 bits of a third
+        let mut random = LCGRandom::preset("msvc").unwrap();
+        let mut draws = LCGRandom::preset("msvc").unwrap();
+        let mut words = Words::default();
+        let draw = (0..3).map(|_| draws.generate()).collect::<Vec<_>>();
+        assert_eq!(
+            words.word(&mut random),
+            draw[0] << 17 | draw[1] << 2 | draw[2] >> 13
+        );
+
+        let mut seen = 0;
+        for _ in 0..1000 {
+            seen |= words.word(&mut random);
+        }
+        assert_eq!(seen, u32::MAX);
+
+        // full 32-bit output passes through as is
+        let mut random = LCGRandom::preset("nr").unwrap();
+        let mut words = Words::default();
+        let first: Vec<u32> = (0..3).map(|_| words.word(&mut random)).collect();
+        assert_eq!(first, [1013904223, 1196435762, 3519870697]);
+    }
+
+    #[test]
+    fn test_distribution_moments() {
+        let mut random = minstd();
+        let n = 50_000;
+
+        let normal: Vec<f64> = (0..n).map(|_| random.gen_normal(3.0, 2.0)).collect();
+        let mean = normal.iter().sum::<f64>() / n as f64;
+        let var = normal.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n as f64;
+        assert!((mean - 3.0).abs() < 0.05);
+        assert!((var - 4.0).abs() < 0.15);
+
+        let exp_mean = (0..n).map(|_| random.gen_exponential(2.0)).sum::<f64>() / n as f64;
+        assert!((exp_mean - 0.5).abs() < 0.02);
+
+        for lambda in [4.0, 100.0] {
+            let poisson_mean =
+                (0..n).map(|_| random.gen_poisson(lambda) as f64).sum::<f64>() / n as f64;
+            assert!((poisson_mean - lambda).abs() < lambda.sqrt() * 0.05);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_chi_square() {
+        let mut random = minstd();
+        let mut counts = [0u64; 4];
+
+        for _ in 0..20_000 {
+            let mut items = [0, 1, 2, 3];
+            random.shuffle(&mut items);
+            counts[items.iter().position(|&x| x == 0).unwrap()] += 1;
+        }
+
+        // 3 degrees of freedom
+        assert!(chi_square(&counts, &[5_000.0; 4]) < 16.266);
+    }
+
+    #[test]
+    fn test_sample_chi_square() {
+        let mut random = minstd();
+        let mut counts = [0u64; 10];
+
+        for _ in 0..20_000 {
+            let picked = random.sample(0..10usize, 3);
+            assert_eq!(picked.len(), 3);
+
+            for i in picked {
+                counts[i] += 1;
+            }
+        }
+
+        assert!(chi_square(&counts, &[6_000.0; 10]) < 27.877);
+    }
 }
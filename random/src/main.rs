@@ -1,60 +1,659 @@
-use clap::Parser;
-use random::LCGRandom;
-use std::fs::OpenOptions;
-use std::io::Write;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use random::{entropy, presets, stats, LCGRandom, Words};
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::ops::Range;
+use std::process;
+use std::time::Instant;
 
-/// Simple program to greet a person
+/// Linear congruential generator toolkit
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-struct Args {
+#[command(propagate_version = true)]
+struct Cli {
+    #[command(subcommand)]
+    operation: Operation,
+
+    /// Print results as JSON
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum Operation {
+    /// Generate random values
+    Generate(GenerateArgs),
+    /// Find period of the generator, for moduli up to 2^32
+    Period(GeneratorArgs),
+    /// Describe generator output statistically
+    Analyze(AnalyzeArgs),
+    /// Run statistical tests against generator output
+    Test(TestArgs),
+    /// Measure generation speed
+    Bench(BenchArgs),
+    /// List known parameter presets
+    Presets,
+    /// Estimate min-entropy of a sample file (NIST SP 800-90B non-IID track)
+    Entropy(EntropyArgs),
+}
+
+#[derive(Args, Debug)]
+struct GeneratorArgs {
+    /// Named parameter set with its output transformation, see `presets`
+    #[arg(long, conflicts_with_all = ["a", "c", "modulus"])]
+    preset: Option<String>,
+
     /// Multiplier value
-    #[arg(short)]
-    a: u32,    
+    #[arg(short, required_unless_present = "preset")]
+    a: Option<u64>,
 
-    /// Seed or start value
-    #[arg(short, long)]
-    seed: u32,
+    /// Increment value
+    #[arg(short, required_unless_present = "preset")]
+    c: Option<u64>,
 
     /// Modulus value
+    #[arg(short, long, required_unless_present = "preset")]
+    modulus: Option<u64>,
+
+    /// Seed or start state, preset seed by default
     #[arg(short, long)]
-    modulus: u32, 
+    seed: Option<u64>,
+}
 
-    /// Increment value
-    #[arg(short)]
-    c: u32,
+#[derive(Args, Debug)]
+struct GenerateArgs {
+    #[command(flatten)]
+    generator: GeneratorArgs,
 
-    /// File name
-    #[arg(short, long, default_value_t = 10)]
-    iteration: u32,     
+    /// Number of generated values
+    #[arg(short = 'n', long, default_value_t = 10)]
+    count: u64,
 
     /// File name
     #[arg(short, long, default_value = "random.txt")]
-    file_name: String,      
+    file_name: String,
+
+    /// Output uniform integers in range LOW..HIGH instead of raw values
+    #[arg(short, long, value_parser = parse_range, conflicts_with = "float")]
+    range: Option<Range<u32>>,
 
-    /// Find period of algorithm
-    #[arg(short, long, default_value = "false")]
-    period: String,  
+    /// Output uniform floats in [0, 1) instead of raw values
+    #[arg(long)]
+    float: bool,
+
+    /// Output format of generated values
+    #[arg(long, value_enum, default_value_t = Format::Decimal)]
+    format: Format,
+
+    /// Write to stdout until the pipe closes instead of a fixed number of values into the file
+    #[arg(long, conflicts_with = "json")]
+    stream: bool,
+
+    /// Stop after writing this many bytes
+    #[arg(long)]
+    count_bytes: Option<u64>,
 }
 
-fn main() {
-    let args = Args::parse();
-    let mut lcg_random = random::LCGRandom::new(args.a, args.c, args.modulus, args.seed);
-    let mut file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(args.file_name)
-        .expect("Failed to open file.");
+#[derive(Args, Debug)]
+struct AnalyzeArgs {
+    #[command(flatten)]
+    generator: GeneratorArgs,
+
+    /// Number of analyzed values
+    #[arg(short = 'n', long, default_value_t = 100_000)]
+    count: usize,
+
+    /// Number of chi-square buckets, at least 2
+    #[arg(short, long, default_value_t = 64, value_parser = clap::value_parser!(u64).range(2..))]
+    buckets: u64,
+}
+
+#[derive(Args, Debug)]
+struct TestArgs {
+    #[command(flatten)]
+    generator: GeneratorArgs,
+
+    /// Number of tested values
+    #[arg(short = 'n', long, default_value_t = 100_000)]
+    count: usize,
+
+    /// Number of chi-square buckets, at least 2
+    #[arg(short, long, default_value_t = 64, value_parser = clap::value_parser!(u64).range(2..))]
+    buckets: u64,
+
+    /// Significance level, tests with lower p-value fail
+    #[arg(long, default_value_t = 0.01)]
+    alpha: f64,
+}
+
+#[derive(Args, Debug)]
+struct BenchArgs {
+    #[command(flatten)]
+    generator: GeneratorArgs,
+
+    /// Number of values per measurement
+    #[arg(short = 'n', long, default_value_t = 10_000_000)]
+    count: u64,
+}
+
+#[derive(Args, Debug)]
+struct EntropyArgs {
+    /// File with one sample per byte
+    #[arg(short, long)]
+    data_path: String,
+
+    /// Significant bits in every sample
+    #[arg(short, long, default_value_t = 8)]
+    bits_per_sample: u32,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// Decimal value per line
+    Decimal,
+    /// Hexadecimal value per line
+    Hex,
+    /// 32-bit little-endian words, narrower output packed into a continuous bit stream
+    RawLe32,
+    /// 32-bit big-endian words, narrower output packed into a continuous bit stream
+    RawBe32,
+    /// ASCII '0'/'1' stream, 32 digits per word packed like the raw formats
+    Bits,
+}
+
+enum Value {
+    Integer(u32),
+    Float(f64),
+}
+
+#[derive(Serialize, Debug)]
+struct Generator {
+    preset: Option<String>,
+    a: u64,
+    c: u64,
+    m: u64,
+    seed: u64,
+    shift: u32,
+    mask: u64,
+}
+
+#[derive(Serialize)]
+struct PresetEntry {
+    name: &'static str,
+    description: &'static str,
+    a: u64,
+    c: u64,
+    m: u64,
+    seed: u64,
+    shift: u32,
+    mask: u64,
+}
+
+#[derive(Serialize)]
+struct EntropyEstimate {
+    name: &'static str,
+    min_entropy: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct EntropyReport {
+    samples: usize,
+    bits_per_sample: u32,
+    original: Vec<EntropyEstimate>,
+    bitstring: Vec<EntropyEstimate>,
+    min_entropy: f64,
+}
+
+#[derive(Serialize)]
+struct GenerateReport {
+    generator: Generator,
+    values: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct PeriodReport {
+    generator: Generator,
+    period: u64,
+}
+
+#[derive(Serialize)]
+struct AnalyzeReport {
+    generator: Generator,
+    count: usize,
+    mean: f64,
+    expected_mean: f64,
+    variance: f64,
+    expected_variance: f64,
+    min: u32,
+    max: u32,
+    chi_square: f64,
+    degrees_of_freedom: u64,
+    serial_correlation: f64,
+}
+
+#[derive(Serialize)]
+struct TestResult {
+    name: &'static str,
+    p_value: f64,
+    passed: bool,
+}
+
+#[derive(Serialize)]
+struct TestReport {
+    generator: Generator,
+    count: usize,
+    alpha: f64,
+    tests: Vec<TestResult>,
+    passed: bool,
+}
+
+#[derive(Serialize)]
+struct BenchResult {
+    name: &'static str,
+    nanos_per_value: f64,
+    values_per_second: f64,
+}
+
+#[derive(Serialize)]
+struct BenchReport {
+    generator: Generator,
+    count: u64,
+    results: Vec<BenchResult>,
+}
+
+impl GeneratorArgs {
+    fn params(&self) -> Generator {
+        match &self.preset {
+            Some(name) => {
+                let preset = presets::find(name).unwrap_or_else(|| {
+                    let names: Vec<&str> = presets::PRESETS.iter().map(|p| p.name).collect();
+                    panic!("Unknown preset: {}. Available: {}", name, names.join(", "))
+                });
+
+                Generator {
+                    preset: Some(preset.name.to_string()),
+                    a: preset.a,
+                    c: preset.c,
+                    m: preset.m,
+                    seed: self.seed.unwrap_or(preset.seed),
+                    shift: preset.shift,
+                    mask: preset.mask,
+                }
+            }
+            None => Generator {
+                preset: None,
+                a: self.a.unwrap(),
+                c: self.c.unwrap(),
+                m: self.modulus.unwrap(),
+                seed: self.seed.unwrap_or(0),
+                shift: 0,
+                mask: u32::MAX as u64,
+            },
+        }
+    }
+}
+
+impl Generator {
+    fn build(&self) -> LCGRandom {
+        LCGRandom::new(self.a, self.c, self.m, self.seed).with_output(self.shift, self.mask)
+    }
+
+    fn take(&self, count: usize) -> Vec<u32> {
+        let mut lcg_random = self.build();
+
+        (0..count).map(|_| lcg_random.generate()).collect()
+    }
+}
 
-    for _ in 0..args.iteration {
-        let seed = lcg_random.generate();
+impl Format {
+    fn is_text(self) -> bool {
+        !matches!(self, Format::RawLe32 | Format::RawBe32)
+    }
+
+    /// Whether the output is a bit stream for test suites rather than values.
+    fn is_stream(self) -> bool {
+        matches!(self, Format::RawLe32 | Format::RawBe32 | Format::Bits)
+    }
+
+    fn encode(self, value: &Value, buf: &mut Vec<u8>) {
+        let value = match *value {
+            Value::Integer(value) => value,
+            Value::Float(value) => {
+                writeln!(buf, "{}", value).unwrap();
+                return;
+            }
+        };
+
+        match self {
+            Format::Decimal => writeln!(buf, "{}", value).unwrap(),
+            Format::Hex => writeln!(buf, "{:08x}", value).unwrap(),
+            Format::RawLe32 => buf.extend_from_slice(&value.to_le_bytes()),
+            Format::RawBe32 => buf.extend_from_slice(&value.to_be_bytes()),
+            Format::Bits => write!(buf, "{:032b}", value).unwrap(),
+        }
+    }
+}
+
+fn parse_range(value: &str) -> Result<Range<u32>, String> {
+    let (low, high) = value
+        .split_once("..")
+        .ok_or(format!("Range should look like LOW..HIGH. Current value is: {}", value))?;
+    let low: u32 = low.parse().map_err(|e| format!("Invalid range start: {}", e))?;
+    let high: u32 = high.parse().map_err(|e| format!("Invalid range end: {}", e))?;
+
+    if low >= high {
+        return Err(format!("Range {}..{} is empty", low, high));
+    }
+
+    Ok(low..high)
+}
+
+fn print_json<T: Serialize>(report: &T) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(report).expect("Failed to serialize report.")
+    );
+}
+
+fn generate(args: GenerateArgs, json: bool) {
+    if args.float && args.format != Format::Decimal {
+        panic!("Float output supports only decimal format. Current value is: {:?}", args.format);
+    }
+
+    let generator = args.generator.params();
+    let mut lcg_random = generator.build();
+    let output: Box<dyn Write> = if args.stream {
+        Box::new(io::stdout().lock())
+    } else {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&args.file_name)
+            .expect("Failed to open file.");
 
-        println!("{}", seed);
-        file.write_all(format!("{}\n", seed).as_bytes()).expect("Failed to write into file.");
+        Box::new(file)
+    };
+    let mut writer = BufWriter::new(output);
+    let mut buf = Vec::new();
+    let mut values = Vec::new();
+    let mut words = Words::default();
+    let mut written = 0u64;
+    let mut generated = 0u64;
+
+    while args.stream || generated < args.count {
+        let value = if args.float {
+            Value::Float(lcg_random.gen_f64())
+        } else if let Some(range) = &args.range {
+            Value::Integer(lcg_random.gen_range(range.clone()))
+        } else if args.format.is_stream() {
+            Value::Integer(words.word(&mut lcg_random))
+        } else {
+            Value::Integer(lcg_random.generate())
+        };
+        generated = generated.wrapping_add(1);
+
+        buf.clear();
+        args.format.encode(&value, &mut buf);
+
+        if let Some(limit) = args.count_bytes {
+            let left = limit - written;
+
+            if left == 0 {
+                break;
+            }
+
+            buf.truncate(left.min(buf.len() as u64) as usize);
+        }
+
+        if json {
+            values.push(match value {
+                Value::Integer(value) => value.into(),
+                Value::Float(value) => value.into(),
+            });
+        } else if !args.stream && args.format.is_text() {
+            print!("{}", String::from_utf8_lossy(&buf));
+        }
+
+        match writer.write_all(&buf) {
+            // consumer like dieharder closed the pipe, nothing left to do
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => return,
+            result => result.expect("Failed to write output."),
+        }
+        written += buf.len() as u64;
+    }
+
+    match writer.flush() {
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => return,
+        result => result.expect("Failed to write output."),
     }
 
-    if args.period.eq_ignore_ascii_case("true") {
-        let period = LCGRandom::period(args.a, args.c, args.modulus, args.seed);
+    if json {
+        print_json(&GenerateReport { generator, values });
+    }
+}
+
+fn period(args: GeneratorArgs, json: bool) {
+    let generator = args.params();
+    let period = LCGRandom::period(generator.a, generator.c, generator.m, generator.seed)
+        .unwrap_or_else(|e| panic!("Unable to find the period: {}", e));
+
+    if json {
+        print_json(&PeriodReport { generator, period });
+    } else {
         println!("Period: {}", period);
     }
-}
\ No newline at end of file
+}
+
+fn analyze(args: AnalyzeArgs, json: bool) {
+    let generator = args.generator.params();
+    let values = generator.take(args.count);
+    let summary = stats::summary(&values);
+    let range = generator.build().output_range();
+    let (chi_square, degrees_of_freedom) = stats::chi_square_uniform(&values, range, args.buckets);
+    let (serial_correlation, _) = stats::serial_correlation(&values);
+    let m = range as f64;
+    let report = AnalyzeReport {
+        count: summary.count,
+        mean: summary.mean,
+        expected_mean: (m - 1.0) / 2.0,
+        variance: summary.variance,
+        expected_variance: (m * m - 1.0) / 12.0,
+        min: summary.min,
+        max: summary.max,
+        chi_square,
+        degrees_of_freedom,
+        serial_correlation,
+        generator,
+    };
+
+    if json {
+        print_json(&report);
+    } else {
+        println!("Values: {}", report.count);
+        println!("Mean: {} (expected {})", report.mean, report.expected_mean);
+        println!("Variance: {} (expected {})", report.variance, report.expected_variance);
+        println!("Min: {}", report.min);
+        println!("Max: {}", report.max);
+        println!("Chi-square: {} ({} degrees of freedom)", report.chi_square, report.degrees_of_freedom);
+        println!("Serial correlation: {}", report.serial_correlation);
+    }
+}
+
+fn test(args: TestArgs, json: bool) {
+    let generator = args.generator.params();
+    let values = generator.take(args.count);
+    let range = generator.build().output_range();
+    let (chi_square, dof) = stats::chi_square_uniform(&values, range, args.buckets);
+    let p_values = [
+        ("chi-square", stats::chi_square_p_value(chi_square, dof)),
+        ("serial-correlation", stats::serial_correlation(&values).1),
+        ("low-bit-runs", stats::low_bit_runs(&values)),
+    ];
+    let tests: Vec<TestResult> = p_values
+        .into_iter()
+        .map(|(name, p_value)| TestResult {
+            name,
+            p_value,
+            passed: p_value >= args.alpha,
+        })
+        .collect();
+    let passed = tests.iter().all(|t| t.passed);
+
+    if json {
+        print_json(&TestReport {
+            generator,
+            count: args.count,
+            alpha: args.alpha,
+            tests,
+            passed,
+        });
+    } else {
+        for test in &tests {
+            let status = if test.passed { "PASS" } else { "FAIL" };
+            println!("{:<20} p = {:<12.6} {}", test.name, test.p_value, status);
+        }
+    }
+
+    if !passed {
+        process::exit(1);
+    }
+}
+
+fn bench(args: BenchArgs, json: bool) {
+    let generator = args.generator.params();
+    let mut lcg_random = generator.build();
+    let mut measure = |name: &'static str, f: &mut dyn FnMut(&mut LCGRandom) -> f64| {
+        let start = Instant::now();
+        let mut sink = 0.0;
+
+        for _ in 0..args.count {
+            sink += f(&mut lcg_random);
+        }
+
+        let nanos = Instant::now().duration_since(start).as_nanos() as f64;
+        std::hint::black_box(sink);
+
+        BenchResult {
+            name,
+            nanos_per_value: nanos / args.count as f64,
+            values_per_second: args.count as f64 / nanos * 1e9,
+        }
+    };
+    let results = vec![
+        measure("generate", &mut |r| r.generate() as f64),
+        measure("gen_range", &mut |r| r.gen_range(0..6) as f64),
+        measure("gen_f64", &mut |r| r.gen_f64()),
+    ];
+
+    if json {
+        print_json(&BenchReport {
+            generator,
+            count: args.count,
+            results,
+        });
+    } else {
+        for result in &results {
+            println!(
+                "{:<10} {:>8.2} ns/value {:>14.0} values/s",
+                result.name, result.nanos_per_value, result.values_per_second
+            );
+        }
+    }
+}
+
+fn list_presets(json: bool) {
+    if json {
+        let entries: Vec<PresetEntry> = presets::PRESETS
+            .iter()
+            .map(|p| PresetEntry {
+                name: p.name,
+                description: p.description,
+                a: p.a,
+                c: p.c,
+                m: p.m,
+                seed: p.seed,
+                shift: p.shift,
+                mask: p.mask,
+            })
+            .collect();
+        print_json(&entries);
+    } else {
+        for p in presets::PRESETS {
+            println!(
+                "{:<14} a = {}, c = {}, m = {}, output = (state >> {}) & {:#x}\n{:<14} {}",
+                p.name, p.a, p.c, p.m, p.shift, p.mask, "", p.description
+            );
+        }
+    }
+}
+
+fn assess_entropy(args: EntropyArgs, json: bool) {
+    let samples = fs::read(&args.data_path).expect("Unable to read samples from the file!");
+
+    if args.bits_per_sample < 8 {
+        if let Some(s) = samples.iter().find(|&&s| s >> args.bits_per_sample != 0) {
+            panic!(
+                "Sample {} doesn't fit into {} bits per sample.",
+                s, args.bits_per_sample
+            );
+        }
+    }
+
+    let report = entropy::assess(&samples, args.bits_per_sample)
+        .unwrap_or_else(|e| panic!("Unable to estimate entropy: {}", e));
+    let estimates = |estimates: Vec<entropy::Estimate>| -> Vec<EntropyEstimate> {
+        estimates
+            .into_iter()
+            .map(|e| EntropyEstimate {
+                name: e.name,
+                min_entropy: e.min_entropy,
+            })
+            .collect()
+    };
+    let report = EntropyReport {
+        samples: report.samples,
+        bits_per_sample: report.bits_per_sample,
+        original: estimates(report.original),
+        bitstring: estimates(report.bitstring),
+        min_entropy: report.min_entropy,
+    };
+
+    if json {
+        print_json(&report);
+        return;
+    }
+
+    println!("Samples: {} ({} bits each)", report.samples, report.bits_per_sample);
+
+    for (title, estimates) in [("Original", &report.original), ("Bitstring", &report.bitstring)] {
+        if estimates.is_empty() {
+            continue;
+        }
+
+        println!("{} estimates:", title);
+        for estimate in estimates {
+            match estimate.min_entropy {
+                Some(h) => println!("  {:<28} {:.6}", estimate.name, h),
+                None => println!("  {:<28} n/a", estimate.name),
+            }
+        }
+    }
+
+    println!("Min-entropy per sample: {:.6}", report.min_entropy);
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match cli.operation {
+        Operation::Generate(args) => generate(args, cli.json),
+        Operation::Period(args) => period(args, cli.json),
+        Operation::Analyze(args) => analyze(args, cli.json),
+        Operation::Test(args) => test(args, cli.json),
+        Operation::Bench(args) => bench(args, cli.json),
+        Operation::Presets => list_presets(cli.json),
+        Operation::Entropy(args) => assess_entropy(args, cli.json),
+    }
+}
@@ -0,0 +1,227 @@
+use crate::LCGRandom;
+
+// historical LCG parameters together with the way each platform extracts output bits
+pub struct Preset {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub a: u64,
+    pub c: u64,
+    pub m: u64,
+    pub seed: u64,
+    /// Output is `(state >> shift) & mask`
+    pub shift: u32,
+    pub mask: u64,
+}
+
+impl Preset {
+    pub fn build(&self) -> LCGRandom {
+        self.build_with_seed(self.seed)
+    }
+
+    pub fn build_with_seed(&self, seed: u64) -> LCGRandom {
+        LCGRandom::new(self.a, self.c, self.m, seed).with_output(self.shift, self.mask)
+    }
+}
+
+pub const PRESETS: &[Preset] = &[
+    Preset {
+        name: "minstd",
+        description: "Park-Miller 1993 minimal standard, C++11 std::minstd_rand",
+        a: 48271,
+        c: 0,
+        m: (1 << 31) - 1,
+        seed: 1,
+        shift: 0,
+        mask: 0x7fffffff,
+    },
+    Preset {
+        name: "minstd0",
+        description: "Park-Miller 1988 minimal standard, C++11 std::minstd_rand0, Apple CarbonLib",
+        a: 16807,
+        c: 0,
+        m: (1 << 31) - 1,
+        seed: 1,
+        shift: 0,
+        mask: 0x7fffffff,
+    },
+    Preset {
+        name: "glibc",
+        description: "glibc random() with TYPE_0 state, srandom(1)",
+        a: 1103515245,
+        c: 12345,
+        m: 1 << 31,
+        seed: 1,
+        shift: 0,
+        mask: 0x7fffffff,
+    },
+    Preset {
+        name: "posix",
+        description: "POSIX.1 sample rand() implementation, srand(1)",
+        a: 1103515245,
+        c: 12345,
+        m: 1 << 32,
+        seed: 1,
+        shift: 16,
+        mask: 0x7fff,
+    },
+    Preset {
+        name: "msvc",
+        description: "Microsoft Visual C++ rand(), srand(1)",
+        a: 214013,
+        c: 2531011,
+        m: 1 << 32,
+        seed: 1,
+        shift: 16,
+        mask: 0x7fff,
+    },
+    Preset {
+        name: "borland",
+        description: "Borland C/C++ rand(), srand(1)",
+        a: 22695477,
+        c: 1,
+        m: 1 << 32,
+        seed: 1,
+        shift: 16,
+        mask: 0x7fff,
+    },
+    Preset {
+        name: "java",
+        description: "java.util.Random nextInt(), new Random(0)",
+        a: 0x5DEECE66D,
+        c: 11,
+        m: 1 << 48,
+        // seeds are scrambled with the multiplier: 0 ^ 0x5DEECE66D
+        seed: 0x5DEECE66D,
+        shift: 16,
+        mask: 0xffffffff,
+    },
+    Preset {
+        name: "drand48",
+        description: "POSIX lrand48(), srand48(1)",
+        a: 0x5DEECE66D,
+        c: 11,
+        m: 1 << 48,
+        // srand48 puts the seed into the high 32 bits and 0x330E below
+        seed: (1 << 16) | 0x330E,
+        shift: 17,
+        mask: 0x7fffffff,
+    },
+    Preset {
+        name: "randu",
+        description: "IBM System/360 RANDU",
+        a: 65539,
+        c: 0,
+        m: 1 << 31,
+        seed: 1,
+        shift: 0,
+        mask: 0x7fffffff,
+    },
+    Preset {
+        name: "nr",
+        description: "Numerical Recipes ranqd1",
+        a: 1664525,
+        c: 1013904223,
+        m: 1 << 32,
+        seed: 0,
+        shift: 0,
+        mask: 0xffffffff,
+    },
+    Preset {
+        name: "vb6",
+        description: "Microsoft Visual Basic 6 Rnd() state",
+        a: 1140671485,
+        c: 12820163,
+        m: 1 << 24,
+        seed: 0x50000,
+        shift: 0,
+        mask: 0xffffff,
+    },
+    Preset {
+        name: "zx81",
+        description: "Sinclair ZX81 RND",
+        a: 75,
+        c: 74,
+        m: (1 << 16) + 1,
+        seed: 0,
+        shift: 0,
+        mask: 0xffffffff,
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS
+        .iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first(name: &str, n: usize) -> Vec<u32> {
+        let mut random = LCGRandom::preset(name).unwrap();
+
+        (0..n).map(|_| random.generate()).collect()
+    }
+
+    // outputs captured from glibc 2.x, libstdc++ and OpenJDK
+    #[test]
+    fn test_captured_outputs() {
+        assert_eq!(
+            first("glibc", 5),
+            [1103527590, 377401575, 662824084, 1147902781, 2035015474]
+        );
+        assert_eq!(
+            first("drand48", 5),
+            [89400484, 976015093, 1792756325, 721524505, 1214379247]
+        );
+        assert_eq!(
+            first("minstd", 5),
+            [48271, 182605794, 1291394886, 1914720637, 2078669041]
+        );
+        assert_eq!(
+            first("minstd0", 5),
+            [16807, 282475249, 1622650073, 984943658, 1144108930]
+        );
+        assert_eq!(
+            first("java", 5),
+            [3139482720, 3571011896, 1033096058, 2604232894, 2737687030]
+        );
+
+        // C++11 requires the 10000th value of a default-constructed engine
+        assert_eq!(LCGRandom::preset("minstd").unwrap().generate_n(10000), 399268537);
+        assert_eq!(LCGRandom::preset("minstd0").unwrap().generate_n(10000), 1043618065);
+    }
+
+    // outputs published in the respective documentation
+    #[test]
+    fn test_documented_outputs() {
+        assert_eq!(first("msvc", 5), [41, 18467, 6334, 26500, 19169]);
+        assert_eq!(first("posix", 4), [16838, 5758, 10113, 17515]);
+        assert_eq!(first("nr", 3), [1013904223, 1196435762, 3519870697]);
+        assert_eq!(first("randu", 3), [65539, 393225, 1769499]);
+
+        // Borland C++ rand() without srand, the runtime seed 0x015A4E36 is one step past srand(1)
+        assert_eq!(first("borland", 6), [346, 130, 10982, 1090, 11656, 7117]);
+        let mut borland = find("borland").unwrap().build_with_seed(0x015A4E36);
+        let outputs: Vec<u32> = (0..5).map(|_| borland.generate()).collect();
+        assert_eq!(outputs, [130, 10982, 1090, 11656, 7117]);
+
+        // VB6 Rnd() without Randomize prints state / 2^24 as a Single
+        let rnd = [0.7055475, 0.533424, 0.5795186, 0.2895625, 0.301948];
+        for (state, expected) in first("vb6", 5).into_iter().zip(rnd) {
+            assert!((state as f64 / (1 << 24) as f64 - expected).abs() < 5e-8);
+        }
+
+        // PRINT RND after power-on prints seed / 65536, 0.0011291504 and 0.08581543
+        assert_eq!(first("zx81", 2), [74, 5624]);
+    }
+
+    #[test]
+    fn test_presets_are_named_uniquely() {
+        for (i, preset) in PRESETS.iter().enumerate() {
+            assert!(PRESETS[i + 1..].iter().all(|p| p.name != preset.name));
+            assert!(preset.build().output_range() <= 1 << 32);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// statistical checks of generator output
+
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    pub variance: f64,
+    pub min: u32,
+    pub max: u32,
+}
+
+pub fn summary(values: &[u32]) -> Summary {
+    let count = values.len();
+    let mean = values.iter().map(|&v| v as f64).sum::<f64>() / count as f64;
+    let variance = values
+        .iter()
+        .map(|&v| (v as f64 - mean).powi(2))
+        .sum::<f64>()
+        / count as f64;
+
+    Summary {
+        count,
+        mean,
+        variance,
+        min: values.iter().copied().min().unwrap_or(0),
+        max: values.iter().copied().max().unwrap_or(0),
+    }
+}
+
+/// Chi-square statistic of `values` spread over `buckets` equal slices of [0, m).
+/// Returns the statistic and its degrees of freedom.
+pub fn chi_square_uniform(values: &[u32], m: u64, buckets: u64) -> (f64, u64) {
+    let buckets = buckets.min(m);
+    let mut counts = vec![0u64; buckets as usize];
+
+    for &value in values {
+        counts[(value as u128 * buckets as u128 / m as u128) as usize] += 1;
+    }
+
+    // bucket b holds values in [ceil(b * m / k), ceil((b + 1) * m / k))
+    let bound = |b: u64| (b as u128 * m as u128).div_ceil(buckets as u128);
+    let statistic = counts
+        .iter()
+        .enumerate()
+        .map(|(b, &observed)| {
+            let width = bound(b as u64 + 1) - bound(b as u64);
+            let expected = values.len() as f64 * width as f64 / m as f64;
+
+            (observed as f64 - expected).powi(2) / expected
+        })
+        .sum();
+
+    (statistic, buckets - 1)
+}
+
+/// Upper tail probability of the chi-square distribution (Wilson-Hilferty approximation).
+pub fn chi_square_p_value(statistic: f64, dof: u64) -> f64 {
+    let k = dof as f64;
+    let z = ((statistic / k).cbrt() - (1.0 - 2.0 / (9.0 * k))) / (2.0 / (9.0 * k)).sqrt();
+
+    0.5 * erfc(z / std::f64::consts::SQRT_2)
+}
+
+/// Lag-1 serial correlation coefficient with its two-sided p-value.
+pub fn serial_correlation(values: &[u32]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let x: Vec<f64> = values.iter().map(|&v| v as f64).collect();
+    let mean = x.iter().sum::<f64>() / n;
+    let variance: f64 = x.iter().map(|v| (v - mean).powi(2)).sum();
+    let covariance: f64 = x
+        .iter()
+        .zip(x.iter().cycle().skip(1))
+        .map(|(a, b)| (a - mean) * (b - mean))
+        .sum();
+    let coefficient = if variance == 0.0 {
+        1.0
+    } else {
+        covariance / variance
+    };
+    // under independence the coefficient is close to N(-1 / (n - 1), 1 / n)
+    let z = (coefficient + 1.0 / (n - 1.0)) * n.sqrt();
+
+    (coefficient, erfc(z.abs() / std::f64::consts::SQRT_2))
+}
+
+/// NIST SP 800-22 runs test over the least significant bit of every value.
+pub fn low_bit_runs(values: &[u32]) -> f64 {
+    let n = values.len() as f64;
+    let pi = values.iter().filter(|&&v| v & 1 == 1).count() as f64 / n;
+
+    if (pi - 0.5).abs() >= 2.0 / n.sqrt() {
+        return 0.0;
+    }
+
+    let runs = 1 + values.windows(2).filter(|w| (w[0] ^ w[1]) & 1 == 1).count();
+    let expected = 2.0 * n * pi * (1.0 - pi);
+
+    erfc((runs as f64 - expected).abs() / (2.0 * (2.0 * n).sqrt() * pi * (1.0 - pi)))
+}
+
+/// Complementary error function with fractional error below 1.2e-7.
+pub fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let result = t * poly.exp();
+
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p_values() {
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+        assert!((erfc(1.0) - 0.157299207).abs() < 1e-7);
+        // 99th percentile of chi-square with 10 degrees of freedom is 23.209
+        assert!((chi_square_p_value(23.209, 10) - 0.01).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_low_bit_runs_detects_alternation() {
+        let alternating: Vec<u32> = (0..1000).collect();
+        let mut random = crate::LCGRandom::new(48271, 0, (1 << 31) - 1, 1);
+        let values: Vec<u32> = (0..1000).map(|_| random.generate()).collect();
+
+        assert!(low_bit_runs(&alternating) < 0.01);
+        assert!(low_bit_runs(&values) > 0.01);
+    }
+}
@@ -0,0 +1,128 @@
+// RSABSSA blind signatures (RFC 9474) over key files, on the rsa_core backend
+// whichever backend is selected: OpenSSL has no blind signing API.
+use crate::native::{private_key, public_key};
+use clap::ValueEnum;
+use rsa_core::blind::{self, Blinded};
+use rsa_core::Error;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+    /// RSABSSA-SHA384-PSS-Randomized, the recommended variant
+    PssRandomized,
+    /// RSABSSA-SHA384-PSSZERO-Randomized
+    PsszeroRandomized,
+    /// RSABSSA-SHA384-PSS-Deterministic
+    PssDeterministic,
+    /// RSABSSA-SHA384-PSSZERO-Deterministic
+    PsszeroDeterministic,
+}
+
+impl Variant {
+    fn variant(self) -> blind::Variant {
+        match self {
+            Variant::PssRandomized => blind::Variant::PssRandomized,
+            Variant::PsszeroRandomized => blind::Variant::PssZeroRandomized,
+            Variant::PssDeterministic => blind::Variant::PssDeterministic,
+            Variant::PsszeroDeterministic => blind::Variant::PssZeroDeterministic,
+        }
+    }
+}
+
+/// Client side, prepares `data` and blinds it for the public key in `pem`.
+/// Returns the prepared message, which finalize and verify need, and the blinding.
+pub fn blind(pem: &[u8], variant: Variant, data: &[u8]) -> Result<(Vec<u8>, Blinded), Error> {
+    let key = public_key(pem)?;
+    let prepared = blind::prepare(variant.variant(), data)?;
+    let blinded = blind::blind(&key, variant.variant(), &prepared)?;
+
+    Ok((prepared, blinded))
+}
+
+/// Server side, signs a blinded message with the private key in `pem`.
+pub fn blind_sign(
+    pem: &[u8],
+    passphrase: Option<&str>,
+    blinded_msg: &[u8],
+) -> Result<Vec<u8>, Error> {
+    blind::blind_sign(&private_key(pem, passphrase)?, blinded_msg)
+}
+
+/// Client side, unblinds the server's answer into a signature of the prepared message.
+pub fn finalize(
+    pem: &[u8],
+    variant: Variant,
+    prepared: &[u8],
+    blind_sig: &[u8],
+    inv: &[u8],
+) -> Result<Vec<u8>, Error> {
+    blind::finalize(
+        &public_key(pem)?,
+        variant.variant(),
+        prepared,
+        blind_sig,
+        inv,
+    )
+}
+
+pub fn verify(
+    pem: &[u8],
+    variant: Variant,
+    prepared: &[u8],
+    signature: &[u8],
+) -> Result<bool, Error> {
+    match blind::verify(&public_key(pem)?, variant.variant(), prepared, signature) {
+        Ok(()) => Ok(true),
+        Err(Error::Verification) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use crate::signature::{self, SaltLength, SignatureOptions, SignaturePadding};
+    use crate::Digest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+    use openssl::symm::Cipher;
+
+    #[test]
+    fn test_blind_signature_with_pem_keys() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let private_pem = rsa
+            .private_key_to_pem_passphrase(Cipher::aes_256_cbc(), b"secret")
+            .unwrap();
+        let public_pem = rsa.public_key_to_pem().unwrap();
+        let key = PKey::from_rsa(rsa).unwrap();
+
+        let (prepared, blinded) = blind(&public_pem, Variant::PssRandomized, b"token").unwrap();
+        assert!(prepared.ends_with(b"token"));
+
+        let blind_sig = blind_sign(&private_pem, Some("secret"), &blinded.blinded_msg).unwrap();
+        let signature = finalize(
+            &public_pem,
+            Variant::PssRandomized,
+            &prepared,
+            &blind_sig,
+            &blinded.inv,
+        )
+        .unwrap();
+        assert!(verify(&public_pem, Variant::PssRandomized, &prepared, &signature).unwrap());
+        assert!(!verify(&public_pem, Variant::PssRandomized, b"token", &signature).unwrap());
+        assert!(!verify(
+            &public_pem,
+            Variant::PsszeroRandomized,
+            &prepared,
+            &signature
+        )
+        .unwrap());
+
+        // a plain PSS signature to anyone who doesn't know it was blinded
+        let options = SignatureOptions {
+            padding: SignaturePadding::Pss,
+            digest: Digest::Sha384,
+            salt_length: SaltLength::Digest,
+        };
+        assert!(signature::verify(&key, &prepared[..], &signature, &options).unwrap());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::Digest;
+use clap::ValueEnum;
+#[cfg(feature = "openssl")]
+use openssl::encrypt::{Decrypter, Encrypter};
+#[cfg(feature = "openssl")]
+use openssl::error::ErrorStack;
+#[cfg(feature = "openssl")]
+use openssl::pkey::{HasPrivate, HasPublic, PKey, PKeyRef};
+#[cfg(feature = "openssl")]
+use openssl::rsa::{Padding, RsaRef};
+use std::fmt;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum PaddingMode {
+    /// PKCS#1 v1.5, kept for compatibility
+    Pkcs1,
+    /// PKCS#1 v2 OAEP
+    Oaep,
+    /// Raw RSA, data must be exactly the modulus size
+    None,
+}
+
+#[derive(Clone, Debug)]
+pub struct PaddingOptions {
+    pub mode: PaddingMode,
+    /// OAEP label hash
+    pub oaep_digest: Digest,
+    /// Mask generation function hash
+    pub mgf1_digest: Digest,
+    pub label: Option<Vec<u8>>,
+}
+
+impl Default for PaddingOptions {
+    fn default() -> Self {
+        PaddingOptions {
+            mode: PaddingMode::Pkcs1,
+            oaep_digest: Digest::Sha256,
+            mgf1_digest: Digest::Sha256,
+            label: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    #[cfg(feature = "openssl")]
+    OpenSsl(ErrorStack),
+    Native(rsa_core::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "openssl")]
+            Error::OpenSsl(e) => write!(f, "OpenSSL error: {}", e),
+            Error::Native(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(feature = "openssl")]
+impl From<ErrorStack> for Error {
+    fn from(e: ErrorStack) -> Self {
+        Error::OpenSsl(e)
+    }
+}
+
+impl From<rsa_core::Error> for Error {
+    fn from(e: rsa_core::Error) -> Self {
+        Error::Native(e)
+    }
+}
+
+/// Public key of either backend, as the block stream and hybrid containers use it.
+pub trait PublicKey {
+    /// Modulus size in bytes
+    fn size(&self) -> usize;
+
+    /// Modulus, big-endian
+    fn modulus(&self) -> Vec<u8>;
+
+    fn public_encrypt(&self, data: &[u8], options: &PaddingOptions) -> Result<Vec<u8>, Error>;
+}
+
+/// Private key of either backend, as the block stream and hybrid containers use it.
+pub trait PrivateKey: PublicKey {
+    fn private_decrypt(&self, cypher: &[u8], options: &PaddingOptions) -> Result<Vec<u8>, Error>;
+}
+
+#[cfg(feature = "openssl")]
+impl<T: HasPublic> PublicKey for PKey<T> {
+    fn size(&self) -> usize {
+        PKeyRef::size(self)
+    }
+
+    fn modulus(&self) -> Vec<u8> {
+        self.rsa().expect("Not an RSA key!").n().to_vec()
+    }
+
+    fn public_encrypt(&self, data: &[u8], options: &PaddingOptions) -> Result<Vec<u8>, Error> {
+        Ok(public_encrypt(self, data, options)?)
+    }
+}
+
+#[cfg(feature = "openssl")]
+impl<T: HasPrivate + HasPublic> PrivateKey for PKey<T> {
+    fn private_decrypt(&self, cypher: &[u8], options: &PaddingOptions) -> Result<Vec<u8>, Error> {
+        Ok(private_decrypt(self, cypher, options)?)
+    }
+}
+
+#[cfg(feature = "openssl")]
+impl PaddingMode {
+    pub fn padding(self) -> Padding {
+        match self {
+            PaddingMode::Pkcs1 => Padding::PKCS1,
+            PaddingMode::Oaep => Padding::PKCS1_OAEP,
+            PaddingMode::None => Padding::NONE,
+        }
+    }
+}
+
+impl PaddingOptions {
+    /// Largest message a single block of a `key_size`-byte modulus can carry.
+    pub fn max_data_len(&self, key_size: usize) -> usize {
+        match self.mode {
+            PaddingMode::Pkcs1 => key_size.saturating_sub(11),
+            PaddingMode::Oaep => {
+                key_size.saturating_sub(2 * self.oaep_digest.hash_algorithm().size() + 2)
+            }
+            PaddingMode::None => key_size,
+        }
+    }
+}
+
+#[cfg(feature = "openssl")]
+pub fn public_encrypt<T: HasPublic>(
+    key: &PKeyRef<T>,
+    data: &[u8],
+    options: &PaddingOptions,
+) -> Result<Vec<u8>, ErrorStack> {
+    let mut encrypter = Encrypter::new(key)?;
+    encrypter.set_rsa_padding(options.mode.padding())?;
+
+    if options.mode == PaddingMode::Oaep {
+        encrypter.set_rsa_oaep_md(options.oaep_digest.message_digest())?;
+        encrypter.set_rsa_mgf1_md(options.mgf1_digest.message_digest())?;
+
+        if let Some(label) = &options.label {
+            encrypter.set_rsa_oaep_label(label)?;
+        }
+    }
+
+    let mut cypher = vec![0; encrypter.encrypt_len(data)?];
+    let cypher_len = encrypter.encrypt(data, &mut cypher)?;
+    cypher.truncate(cypher_len);
+
+    Ok(cypher)
+}
+
+#[cfg(feature = "openssl")]
+pub fn private_decrypt<T: HasPrivate>(
+    key: &PKeyRef<T>,
+    cypher: &[u8],
+    options: &PaddingOptions,
+) -> Result<Vec<u8>, ErrorStack> {
+    let mut decrypter = Decrypter::new(key)?;
+    decrypter.set_rsa_padding(options.mode.padding())?;
+
+    if options.mode == PaddingMode::Oaep {
+        decrypter.set_rsa_oaep_md(options.oaep_digest.message_digest())?;
+        decrypter.set_rsa_mgf1_md(options.mgf1_digest.message_digest())?;
+
+        if let Some(label) = &options.label {
+            decrypter.set_rsa_oaep_label(label)?;
+        }
+    }
+
+    let mut decrypted = vec![0; decrypter.decrypt_len(cypher)?];
+    let decrypted_len = decrypter.decrypt(cypher, &mut decrypted)?;
+    decrypted.truncate(decrypted_len);
+
+    Ok(decrypted)
+}
+
+#[cfg(feature = "openssl")]
+/// Raw private key operation, only PKCS#1 v1.5 and no padding make sense here.
+pub fn private_encrypt<T: HasPrivate>(
+    key: &RsaRef<T>,
+    data: &[u8],
+    mode: PaddingMode,
+) -> Result<Vec<u8>, ErrorStack> {
+    let mut cypher = vec![0; key.size() as usize];
+    let cypher_len = key.private_encrypt(data, &mut cypher, mode.padding())?;
+    cypher.truncate(cypher_len);
+
+    Ok(cypher)
+}
+
+#[cfg(feature = "openssl")]
+pub fn public_decrypt<T: HasPublic>(
+    key: &RsaRef<T>,
+    cypher: &[u8],
+    mode: PaddingMode,
+) -> Result<Vec<u8>, ErrorStack> {
+    let mut decrypted = vec![0; key.size() as usize];
+    let decrypted_len = key.public_decrypt(cypher, &mut decrypted, mode.padding())?;
+    decrypted.truncate(decrypted_len);
+
+    Ok(decrypted)
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    const DIGESTS: [Digest; 5] = [
+        Digest::Sha1,
+        Digest::Sha224,
+        Digest::Sha256,
+        Digest::Sha384,
+        Digest::Sha512,
+    ];
+
+    #[test]
+    fn test_round_trip() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let key = PKey::from_rsa(rsa.clone()).unwrap();
+        let public_key = PKey::public_key_from_pem(&key.public_key_to_pem().unwrap()).unwrap();
+        let data = b"Some secret message".to_vec();
+        let mut cases = vec![PaddingOptions::default()];
+
+        for oaep_digest in DIGESTS {
+            for mgf1_digest in DIGESTS {
+                for label in [None, Some(b"label".to_vec())] {
+                    cases.push(PaddingOptions {
+                        mode: PaddingMode::Oaep,
+                        oaep_digest,
+                        mgf1_digest,
+                        label,
+                    });
+                }
+            }
+        }
+
+        for options in &cases {
+            let cypher = public_encrypt(&public_key, &data, options).unwrap();
+
+            assert_eq!(cypher.len(), 256);
+            assert_eq!(private_decrypt(&key, &cypher, options).unwrap(), data, "{:?}", options);
+        }
+
+        let raw = PaddingOptions {
+            mode: PaddingMode::None,
+            ..Default::default()
+        };
+        let mut block = vec![0u8; 256];
+        block[255] = 42;
+        let cypher = public_encrypt(&public_key, &block, &raw).unwrap();
+        assert_eq!(private_decrypt(&key, &cypher, &raw).unwrap(), block);
+
+        for mode in [PaddingMode::Pkcs1, PaddingMode::None] {
+            let input = if mode == PaddingMode::None { &block } else { &data };
+            let cypher = private_encrypt(&rsa, input, mode).unwrap();
+
+            assert_eq!(&public_decrypt(&rsa, &cypher, mode).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_oaep_label_mismatch_fails() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let options = PaddingOptions {
+            mode: PaddingMode::Oaep,
+            label: Some(b"one".to_vec()),
+            ..Default::default()
+        };
+        let other = PaddingOptions {
+            label: Some(b"two".to_vec()),
+            ..options.clone()
+        };
+        let cypher = public_encrypt(&key, b"data", &options).unwrap();
+
+        assert!(private_decrypt(&key, &cypher, &other).is_err());
+    }
+}
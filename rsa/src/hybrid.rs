@@ -0,0 +1,332 @@
+use crate::cipher::{self, PaddingMode, PaddingOptions, PrivateKey, PublicKey};
+use crate::Digest;
+use aes_gcm::aead::{self, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::ChaCha20Poly1305;
+use clap::ValueEnum;
+use num_bigint::BigUint;
+use rsa_core::prime::{random_bytes, random_range};
+use rsa_core::HashAlgorithm;
+use std::fmt;
+
+// Container layout, every number is big-endian:
+//
+//   magic "RSAH" | version u8 | key wrap u8 | aead u8 | reserved u8
+//   | wrapped key length u16 | nonce [12] | wrapped key | ciphertext | tag [16]
+//
+// Everything before the ciphertext is authenticated as associated data.
+const MAGIC: &[u8; 4] = b"RSAH";
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const FIXED_HEADER_LEN: usize = 10 + NONCE_LEN;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum KeyWrap {
+    /// Content key encrypted with RSA-OAEP (SHA-256)
+    Oaep,
+    /// Content key derived from a random RSA-KEM secret (KDF2, SHA-256)
+    Kem,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Aead {
+    Aes256Gcm,
+    Chacha20Poly1305,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidContainer(&'static str),
+    UnsupportedVersion(u8),
+    /// Authentication tag or wrapped key didn't verify
+    Integrity,
+    Rsa(cipher::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidContainer(reason) => write!(f, "Invalid hybrid container: {}", reason),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "Unsupported hybrid container version: {}", version)
+            }
+            Error::Integrity => write!(f, "Hybrid container failed integrity check"),
+            Error::Rsa(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<cipher::Error> for Error {
+    fn from(e: cipher::Error) -> Self {
+        Error::Rsa(e)
+    }
+}
+
+impl From<rsa_core::Error> for Error {
+    fn from(e: rsa_core::Error) -> Self {
+        Error::Rsa(cipher::Error::Native(e))
+    }
+}
+
+impl KeyWrap {
+    fn id(self) -> u8 {
+        match self {
+            KeyWrap::Oaep => 1,
+            KeyWrap::Kem => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, Error> {
+        match id {
+            1 => Ok(KeyWrap::Oaep),
+            2 => Ok(KeyWrap::Kem),
+            _ => Err(Error::InvalidContainer("unknown key wrap")),
+        }
+    }
+}
+
+impl Aead {
+    fn id(self) -> u8 {
+        match self {
+            Aead::Aes256Gcm => 1,
+            Aead::Chacha20Poly1305 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Self, Error> {
+        match id {
+            1 => Ok(Aead::Aes256Gcm),
+            2 => Ok(Aead::Chacha20Poly1305),
+            _ => Err(Error::InvalidContainer("unknown aead")),
+        }
+    }
+
+    fn encrypt(self, key: &[u8], nonce: &[u8], payload: Payload) -> Result<Vec<u8>, aead::Error> {
+        match self {
+            Aead::Aes256Gcm => aead::Aead::encrypt(
+                &Aes256Gcm::new_from_slice(key).map_err(|_| aead::Error)?,
+                nonce.into(),
+                payload,
+            ),
+            Aead::Chacha20Poly1305 => aead::Aead::encrypt(
+                &ChaCha20Poly1305::new_from_slice(key).map_err(|_| aead::Error)?,
+                nonce.into(),
+                payload,
+            ),
+        }
+    }
+
+    fn decrypt(self, key: &[u8], nonce: &[u8], payload: Payload) -> Result<Vec<u8>, aead::Error> {
+        match self {
+            Aead::Aes256Gcm => aead::Aead::decrypt(
+                &Aes256Gcm::new_from_slice(key).map_err(|_| aead::Error)?,
+                nonce.into(),
+                payload,
+            ),
+            Aead::Chacha20Poly1305 => aead::Aead::decrypt(
+                &ChaCha20Poly1305::new_from_slice(key).map_err(|_| aead::Error)?,
+                nonce.into(),
+                payload,
+            ),
+        }
+    }
+}
+
+fn oaep() -> PaddingOptions {
+    PaddingOptions {
+        mode: PaddingMode::Oaep,
+        oaep_digest: Digest::Sha256,
+        mgf1_digest: Digest::Sha256,
+        label: None,
+    }
+}
+
+// KDF2 from ISO 18033-2, a single SHA-256 block is exactly the content key size
+fn kdf2(secret: &[u8]) -> Vec<u8> {
+    HashAlgorithm::Sha256.digest(&[secret, &1u32.to_be_bytes()].concat())
+}
+
+/// Wraps a fresh content key for `key` and encrypts `data` with it.
+pub fn seal<K: PublicKey + ?Sized>(
+    key: &K,
+    data: &[u8],
+    key_wrap: KeyWrap,
+    aead: Aead,
+) -> Result<Vec<u8>, Error> {
+    let (content_key, wrapped_key) = match key_wrap {
+        KeyWrap::Oaep => {
+            let content_key = random_bytes(KEY_LEN)?;
+            let wrapped_key = key.public_encrypt(&content_key, &oaep())?;
+
+            (content_key, wrapped_key)
+        }
+        KeyWrap::Kem => {
+            let modulus = BigUint::from_bytes_be(&key.modulus());
+            let secret = random_range(&BigUint::from(0u8), &modulus)?.to_bytes_be();
+            let mut padded = vec![0; key.size() - secret.len()];
+            padded.extend_from_slice(&secret);
+            let secret = padded;
+            let raw = PaddingOptions {
+                mode: PaddingMode::None,
+                ..oaep()
+            };
+            let wrapped_key = key.public_encrypt(&secret, &raw)?;
+
+            (kdf2(&secret), wrapped_key)
+        }
+    };
+
+    let nonce = random_bytes(NONCE_LEN)?;
+
+    let mut container =
+        Vec::with_capacity(FIXED_HEADER_LEN + wrapped_key.len() + data.len() + TAG_LEN);
+    container.extend_from_slice(MAGIC);
+    container.extend_from_slice(&[VERSION, key_wrap.id(), aead.id(), 0]);
+    container.extend_from_slice(&(wrapped_key.len() as u16).to_be_bytes());
+    container.extend_from_slice(&nonce);
+    container.extend_from_slice(&wrapped_key);
+
+    // the tag follows the ciphertext
+    let cypher = aead
+        .encrypt(
+            &content_key,
+            &nonce,
+            Payload {
+                msg: data,
+                aad: &container,
+            },
+        )
+        .map_err(|_| Error::InvalidContainer("data too long for the aead"))?;
+    container.extend_from_slice(&cypher);
+
+    Ok(container)
+}
+
+/// Recovers the plaintext, nothing is returned unless the whole container authenticates.
+pub fn open<K: PrivateKey + ?Sized>(key: &K, container: &[u8]) -> Result<Vec<u8>, Error> {
+    if container.len() < FIXED_HEADER_LEN + TAG_LEN || &container[..4] != MAGIC {
+        return Err(Error::InvalidContainer("missing header"));
+    }
+    if container[4] != VERSION {
+        return Err(Error::UnsupportedVersion(container[4]));
+    }
+
+    let key_wrap = KeyWrap::from_id(container[5])?;
+    let aead = Aead::from_id(container[6])?;
+    let wrapped_len = u16::from_be_bytes([container[8], container[9]]) as usize;
+    let header_len = FIXED_HEADER_LEN + wrapped_len;
+
+    if container.len() < header_len + TAG_LEN {
+        return Err(Error::InvalidContainer("truncated"));
+    }
+
+    let (header, body) = container.split_at(header_len);
+    let nonce = &header[10..FIXED_HEADER_LEN];
+    let wrapped_key = &header[FIXED_HEADER_LEN..];
+
+    let content_key = match key_wrap {
+        KeyWrap::Oaep => {
+            key.private_decrypt(wrapped_key, &oaep()).map_err(|_| Error::Integrity)?
+        }
+        KeyWrap::Kem => {
+            let raw = PaddingOptions {
+                mode: PaddingMode::None,
+                ..oaep()
+            };
+            let secret = key
+                .private_decrypt(wrapped_key, &raw)
+                .map_err(|_| Error::Integrity)?;
+
+            kdf2(&secret)
+        }
+    };
+
+    if content_key.len() != KEY_LEN {
+        return Err(Error::Integrity);
+    }
+
+    aead.decrypt(
+        &content_key,
+        nonce,
+        Payload {
+            msg: body,
+            aad: header,
+        },
+    )
+    .map_err(|_| Error::Integrity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::RsaPrivateKey;
+
+    #[test]
+    fn test_native_round_trip() {
+        let key = RsaPrivateKey::generate(1024).unwrap();
+        let data = vec![42u8; 1000];
+
+        for key_wrap in [KeyWrap::Oaep, KeyWrap::Kem] {
+            for aead in [Aead::Aes256Gcm, Aead::Chacha20Poly1305] {
+                let container = seal(&key.to_public_key(), &data, key_wrap, aead).unwrap();
+                assert_eq!(open(&key, &container).unwrap(), data);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod openssl_tests {
+    use super::*;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_round_trip() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        // far larger than a single RSA block
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i * 7) as u8).collect();
+
+        for key_wrap in [KeyWrap::Oaep, KeyWrap::Kem] {
+            for aead in [Aead::Aes256Gcm, Aead::Chacha20Poly1305] {
+                let container = seal(&key, &data, key_wrap, aead).unwrap();
+
+                assert_eq!(&container[..5], b"RSAH\x01");
+                assert_eq!(open(&key, &container).unwrap(), data);
+                assert_eq!(
+                    open(&key, &seal(&key, b"", key_wrap, aead).unwrap()).unwrap(),
+                    b""
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let container = seal(&key, b"attack at dawn", KeyWrap::Oaep, Aead::Aes256Gcm).unwrap();
+
+        // reserved header byte, wrapped key, ciphertext and tag
+        for position in [7, 30, container.len() - 20, container.len() - 1] {
+            let mut tampered = container.clone();
+            tampered[position] ^= 1;
+
+            assert!(matches!(open(&key, &tampered), Err(Error::Integrity)));
+        }
+
+        let mut future = container.clone();
+        future[4] = 2;
+        assert!(matches!(
+            open(&key, &future),
+            Err(Error::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            open(&key, &container[..40]),
+            Err(Error::InvalidContainer(_))
+        ));
+    }
+}
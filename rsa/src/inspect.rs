@@ -0,0 +1,96 @@
+use openssl::error::ErrorStack;
+use openssl::hash::{hash, MessageDigest};
+use openssl::pkey::{HasPublic, PKeyRef};
+use pki::passphrase;
+use serde::Serialize;
+
+use crate::format::{self, Error};
+
+#[derive(Serialize, Debug)]
+pub struct KeyInfo {
+    pub key_type: &'static str,
+    pub private: bool,
+    pub bits: u32,
+    pub public_exponent: String,
+    pub modulus: String,
+    /// SHA-256 of the DER SubjectPublicKeyInfo
+    pub sha256_fingerprint: String,
+    /// MD5 of the DER SubjectPublicKeyInfo
+    pub md5_fingerprint: String,
+    #[serde(skip)]
+    spki: Vec<u8>,
+}
+
+fn fingerprint(digest: MessageDigest, data: &[u8]) -> Result<String, ErrorStack> {
+    let bytes: Vec<String> = hash(digest, data)?
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+
+    Ok(bytes.join(":"))
+}
+
+fn describe<T: HasPublic>(key: &PKeyRef<T>, private: bool) -> Result<KeyInfo, ErrorStack> {
+    let rsa = key.rsa()?;
+    let spki = key.public_key_to_der()?;
+
+    Ok(KeyInfo {
+        key_type: "RSA",
+        private,
+        bits: key.bits(),
+        public_exponent: rsa.e().to_dec_str()?.to_string(),
+        modulus: rsa.n().to_hex_str()?.to_lowercase(),
+        sha256_fingerprint: fingerprint(MessageDigest::sha256(), &spki)?,
+        md5_fingerprint: fingerprint(MessageDigest::md5(), &spki)?,
+        spki,
+    })
+}
+
+/// Describes a private or public key in any format [`format`] reads.
+pub fn inspect(data: &[u8], passphrase: Option<&str>) -> Result<KeyInfo, Error> {
+    match format::load_private_key(data, passphrase) {
+        Ok(key) => Ok(describe(&key, true)?),
+        Err(e) if passphrase::is_encrypted(data) => Err(e),
+        Err(_) => {
+            let key = format::load_public_key(data)?;
+            Ok(describe(&key, false)?)
+        }
+    }
+}
+
+impl KeyInfo {
+    /// Whether both keys share the same public half.
+    pub fn matches(&self, other: &KeyInfo) -> bool {
+        self.spki == other.spki
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_private_and_public_key_match() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let private = inspect(&rsa.private_key_to_pem().unwrap(), None).unwrap();
+        let public = inspect(&rsa.public_key_to_pem_pkcs1().unwrap(), None).unwrap();
+        let spki = inspect(&rsa.public_key_to_pem().unwrap(), None).unwrap();
+        let other = inspect(
+            &Rsa::generate(2048).unwrap().public_key_to_pem().unwrap(),
+            None,
+        )
+        .unwrap();
+
+        assert!(private.private);
+        assert!(!public.private);
+        assert_eq!(private.bits, 2048);
+        assert_eq!(private.public_exponent, "65537");
+        assert_eq!(private.sha256_fingerprint.len(), 32 * 3 - 1);
+        assert_eq!(private.md5_fingerprint.len(), 16 * 3 - 1);
+        assert!(private.matches(&public));
+        assert!(private.matches(&spki));
+        assert!(!private.matches(&other));
+        assert!(inspect(b"not a key", None).is_err());
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(feature = "openssl")]
+extern crate openssl;
+
+pub mod blind;
+pub mod cipher;
+pub mod format;
+pub mod hybrid;
+#[cfg(feature = "openssl")]
+pub mod inspect;
+pub mod native;
+#[cfg(feature = "openssl")]
+pub mod oracle;
+#[cfg(feature = "openssl")]
+pub mod shares;
+pub mod signature;
+pub mod stream;
+#[cfg(feature = "openssl")]
+pub mod timing;
+
+use clap::ValueEnum;
+#[cfg(feature = "openssl")]
+use openssl::hash::MessageDigest;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Digest {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Digest {
+    #[cfg(feature = "openssl")]
+    pub fn message_digest(self) -> MessageDigest {
+        match self {
+            Digest::Sha1 => MessageDigest::sha1(),
+            Digest::Sha224 => MessageDigest::sha224(),
+            Digest::Sha256 => MessageDigest::sha256(),
+            Digest::Sha384 => MessageDigest::sha384(),
+            Digest::Sha512 => MessageDigest::sha512(),
+        }
+    }
+}
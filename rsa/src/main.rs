@@ -1,9 +1,52 @@
+#[cfg(feature = "openssl")]
 extern crate openssl;
 
-use std::fs;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use std::process;
+#[cfg(feature = "openssl")]
+use openssl::bn::BigNum;
+#[cfg(feature = "openssl")]
+use openssl::pkey::{PKey, Private};
+#[cfg(feature = "openssl")]
 use openssl::rsa::Rsa;
-use openssl::rsa::Padding;
+#[cfg(feature = "openssl")]
+use openssl::x509::X509;
+use pki::passphrase::{self, Passphrase};
+#[cfg(feature = "openssl")]
+use pki::x509::{self, Issuer};
 use clap::{Args, Subcommand, Parser, ValueEnum};
+use rsa::blind::{self, Variant};
+#[cfg(feature = "openssl")]
+use rsa::cipher;
+use rsa::cipher::{PaddingMode, PaddingOptions, PrivateKey, PublicKey};
+use rsa::format::{self, KeyFormat};
+use rsa::hybrid::{self, Aead, KeyWrap};
+#[cfg(feature = "openssl")]
+use rsa::inspect::{self, KeyInfo};
+use rsa::native;
+#[cfg(feature = "openssl")]
+use rsa::oracle::PaddingOracle;
+#[cfg(feature = "openssl")]
+use rsa::shares;
+#[cfg(feature = "openssl")]
+use rsa::signature;
+use rsa::signature::{SaltLength, SignatureOptions, SignaturePadding};
+use rsa::stream;
+#[cfg(feature = "openssl")]
+use rsa::timing::{self, Target};
+use rsa::Digest;
+use rsa_core::RsaPrivateKey;
+use num_bigint::BigUint;
+#[cfg(feature = "openssl")]
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+/// Smallest key size accepted at all, keys below SAFE_KEY_SIZE get a warning
+const MIN_KEY_SIZE: u32 = 1024;
+const SAFE_KEY_SIZE: u32 = 2048;
+const MAX_KEY_SIZE: u32 = 16384;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -11,13 +54,49 @@ use clap::{Args, Subcommand, Parser, ValueEnum};
 struct Cli {
     #[command(subcommand)]
     operation: Operation,
+
+    /// RSA implementation, native needs no system OpenSSL
+    #[arg(long, global = true, value_enum, default_value_t = DEFAULT_BACKEND)]
+    backend: Backend,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Backend {
+    #[cfg(feature = "openssl")]
+    Openssl,
+    Native,
 }
 
+#[cfg(feature = "openssl")]
+const DEFAULT_BACKEND: Backend = Backend::Openssl;
+#[cfg(not(feature = "openssl"))]
+const DEFAULT_BACKEND: Backend = Backend::Native;
+
 #[derive(Subcommand, Debug,)]
 enum Operation {
     Keys(KeysArgs),
     Encrypt(CryptoArgs),
     Decrypt(CryptoArgs),
+    Sign(SignArgs),
+    Verify(SignArgs),
+    #[cfg(feature = "openssl")]
+    Inspect(InspectArgs),
+    #[cfg(feature = "openssl")]
+    ChangePassphrase(ChangePassphraseArgs),
+    #[cfg(feature = "openssl")]
+    Convert(ConvertArgs),
+    Attack(AttackArgs),
+    BlindRsa(BlindArgs),
+    #[cfg(feature = "openssl")]
+    Shares(SharesArgs),
+    #[cfg(feature = "openssl")]
+    Csr(CsrArgs),
+    #[cfg(feature = "openssl")]
+    Cert(CertArgs),
+    #[cfg(feature = "openssl")]
+    VerifyChain(VerifyChainArgs),
+    #[cfg(feature = "openssl")]
+    Timing(TimingArgs),
 }
 
 #[derive(Args, Debug,)]
@@ -26,14 +105,472 @@ struct KeysArgs {
     #[arg(short, long)]
     keys_name: String,
     
-    /// Save path 
+    /// Directory to save the keys in, created when missing
     #[arg(short, long, default_value = "")]
     save_path: String,
 
+    /// Key size in bits, a multiple of 8 up to 16384
     #[arg(short, long, default_value_t = 2048)]
     block_size: u32,
+
+    /// Public exponent, odd and at least 3
+    #[arg(short, long, default_value_t = 65537)]
+    exponent: u32,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+
+    /// Prompt for a passphrase to encrypt the private key with
+    #[arg(long, conflicts_with_all = ["passphrase", "passphrase_file"])]
+    ask_passphrase: bool,
+
+    /// Key file format
+    #[arg(long, value_enum, default_value_t = KeyFormat::PemPkcs1)]
+    format: KeyFormat,
 }    
 
+#[derive(Args, Debug,)]
+struct PassphraseArgs {
+    /// Private key passphrase, prompted for when an encrypted key is read without one
+    #[arg(long, conflicts_with = "passphrase_file")]
+    passphrase: Option<String>,
+
+    /// File with the private key passphrase on its first line
+    #[arg(long)]
+    passphrase_file: Option<String>,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct ChangePassphraseArgs {
+    /// File path to private key, rewritten in place in the same format
+    #[arg(short, long)]
+    keys_path: String,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+
+    /// New passphrase, an empty one stores the key unencrypted
+    #[arg(long, conflicts_with = "new_passphrase_file")]
+    new_passphrase: Option<String>,
+
+    /// File with the new passphrase on its first line
+    #[arg(long)]
+    new_passphrase_file: Option<String>,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct ConvertArgs {
+    /// File path to private or public key in any supported format
+    #[arg(short, long)]
+    input_path: String,
+
+    /// File path to save the converted key
+    #[arg(short, long)]
+    output_path: String,
+
+    /// Output key format
+    #[arg(long, value_enum)]
+    format: KeyFormat,
+
+    /// Write only the public half of a private key
+    #[arg(long)]
+    public: bool,
+
+    /// Passphrase of the input key, also used to encrypt the output
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug,)]
+struct AttackArgs {
+    #[command(subcommand)]
+    attack: Attack,
+}
+
+/// Attacks on textbook RSA and weak keys, for demonstration only
+#[derive(Subcommand, Debug,)]
+enum Attack {
+    /// Recover a small private exponent from the public key
+    Wiener(RecoverKeyArgs),
+    /// Factor a modulus whose primes are close to each other
+    Fermat(FermatArgs),
+    /// Decrypt one message sent unpadded to e keys with public exponent e
+    Hastad(BroadcastArgs),
+    /// Decrypt one message sent unpadded to two keys sharing the modulus
+    CommonModulus(BroadcastArgs),
+    /// Find moduli sharing a prime in a set of public keys
+    BatchGcd(BatchGcdArgs),
+    /// Decrypt a PKCS#1 v1.5 cypher through a simulated padding oracle
+    #[cfg(feature = "openssl")]
+    Bleichenbacher(OracleArgs),
+}
+
+#[derive(Args, Debug,)]
+struct RecoverKeyArgs {
+    /// File path to the attacked public key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// File path to save the recovered private key
+    #[arg(short, long)]
+    output_path: String,
+
+    /// Recovered key format
+    #[arg(long, value_enum, default_value_t = KeyFormat::PemPkcs1)]
+    format: KeyFormat,
+}
+
+#[derive(Args, Debug,)]
+struct FermatArgs {
+    #[command(flatten)]
+    key: RecoverKeyArgs,
+
+    /// Steps to try before giving up
+    #[arg(long, default_value_t = 1_000_000)]
+    iterations: u64,
+}
+
+#[derive(Args, Debug,)]
+struct BroadcastArgs {
+    /// Public keys, in the same order as the cyphers
+    #[arg(short, long, required = true)]
+    keys_path: Vec<String>,
+
+    /// Cyphers of the same message made with --padding none
+    #[arg(short, long, required = true)]
+    cypher_path: Vec<String>,
+
+    /// File path to save the recovered message
+    #[arg(short, long)]
+    result_path: String,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct OracleArgs {
+    /// Private key of the simulated server, the attack itself sees only the public half
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// PKCS#1 v1.5 cypher to decrypt
+    #[arg(short, long)]
+    cypher_path: String,
+
+    /// File path to save the recovered message
+    #[arg(short, long)]
+    result_path: String,
+
+    /// Oracle queries to give up after
+    #[arg(long, default_value_t = 10_000_000)]
+    max_queries: u64,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug,)]
+struct BlindArgs {
+    #[command(subcommand)]
+    step: BlindStep,
+}
+
+/// RSABSSA blind signatures (RFC 9474)
+#[derive(Subcommand, Debug,)]
+enum BlindStep {
+    /// Prepare and blind a message for the signer's public key
+    Blind(BlindMessageArgs),
+    /// Sign a blinded message without seeing the message
+    BlindSign(BlindSignArgs),
+    /// Unblind the blind signature into a signature of the prepared message
+    Finalize(FinalizeArgs),
+    /// Verify a finalized signature of the prepared message
+    Verify(BlindVerifyArgs),
+}
+
+#[derive(Args, Debug,)]
+struct BlindMessageArgs {
+    /// File path to the signer's public key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// File path to read the message
+    #[arg(short, long)]
+    data_path: String,
+
+    /// Path to save the prepared message, the one that ends up signed
+    #[arg(short, long)]
+    prepared_path: String,
+
+    /// Path to save the blinded message for the signer
+    #[arg(short, long)]
+    blinded_path: String,
+
+    /// Path to save the blinding inverse, needed to finalize
+    #[arg(short, long)]
+    inverse_path: String,
+
+    /// RFC 9474 variant, the same one at every step
+    #[arg(long, value_enum, default_value_t = Variant::PssRandomized)]
+    variant: Variant,
+}
+
+#[derive(Args, Debug,)]
+struct BlindSignArgs {
+    /// File path to private key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// File path to read the blinded message
+    #[arg(short, long)]
+    blinded_path: String,
+
+    /// Path to save the blind signature
+    #[arg(short, long)]
+    signature_path: String,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug,)]
+struct FinalizeArgs {
+    /// File path to the signer's public key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// File path to the prepared message
+    #[arg(short, long)]
+    prepared_path: String,
+
+    /// File path to the blind signature
+    #[arg(short, long)]
+    blind_signature_path: String,
+
+    /// File path to the blinding inverse
+    #[arg(short, long)]
+    inverse_path: String,
+
+    /// Path to save the signature
+    #[arg(short, long)]
+    signature_path: String,
+
+    /// RFC 9474 variant, the same one at every step
+    #[arg(long, value_enum, default_value_t = Variant::PssRandomized)]
+    variant: Variant,
+}
+
+#[derive(Args, Debug,)]
+struct BlindVerifyArgs {
+    /// File path to the signer's public key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// File path to the prepared message
+    #[arg(short, long)]
+    prepared_path: String,
+
+    /// Path to signature file
+    #[arg(short, long)]
+    signature_path: String,
+
+    /// RFC 9474 variant, the same one at every step
+    #[arg(long, value_enum, default_value_t = Variant::PssRandomized)]
+    variant: Variant,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct SharesArgs {
+    #[command(subcommand)]
+    step: ShareStep,
+}
+
+#[cfg(feature = "openssl")]
+/// Private keys split into Shamir shares, any threshold of which restore the key
+#[derive(Subcommand, Debug,)]
+enum ShareStep {
+    /// Split a private key into share files
+    Split(SplitArgs),
+    /// Restore the private key from threshold shares
+    Combine(CombineArgs),
+    /// Sign with the key restored in memory from threshold shares
+    Sign(ShareSignArgs),
+    /// Check shares against each other and report corrupt ones
+    Check(CheckSharesArgs),
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct SplitArgs {
+    /// File path to private key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Shares needed to restore the key
+    #[arg(short, long)]
+    threshold: u8,
+
+    /// Number of shares to create
+    #[arg(short = 'n', long)]
+    shares: u8,
+
+    /// Directory to save the shares in, created when missing
+    #[arg(short, long, default_value = "")]
+    save_path: String,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct CombineArgs {
+    /// Share files, at least the threshold
+    #[arg(required = true)]
+    shares_paths: Vec<String>,
+
+    /// Path to save the restored private key
+    #[arg(short, long)]
+    output_path: String,
+
+    #[arg(long, value_enum, default_value_t = KeyFormat::PemPkcs1)]
+    format: KeyFormat,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+
+    /// Prompt for a passphrase to encrypt the restored key with
+    #[arg(long, conflicts_with_all = ["passphrase", "passphrase_file"])]
+    ask_passphrase: bool,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct ShareSignArgs {
+    /// Share files, at least the threshold
+    #[arg(required = true)]
+    shares_paths: Vec<String>,
+
+    /// File path to read data
+    #[arg(short, long)]
+    data_path: String,
+
+    /// Path to signature file
+    #[arg(short, long)]
+    signature_path: String,
+
+    /// Signature padding
+    #[arg(long, value_enum, default_value_t = SignaturePadding::Pss)]
+    padding: SignaturePadding,
+
+    /// Message digest
+    #[arg(long, value_enum, default_value_t = Digest::Sha256)]
+    digest: Digest,
+
+    /// PSS salt length: digest, max or number of bytes
+    #[arg(long, default_value = "digest")]
+    salt_length: SaltLength,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct CheckSharesArgs {
+    /// Share files to check
+    #[arg(required = true)]
+    shares_paths: Vec<String>,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct CsrArgs {
+    /// File path to private key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Path to save the PEM request
+    #[arg(short, long)]
+    output_path: String,
+
+    #[command(flatten)]
+    profile: x509::Profile,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct CertArgs {
+    /// File path to the signing private key: the key itself when self-signing, the CA's key otherwise
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Path to save the PEM certificate
+    #[arg(short, long)]
+    output_path: String,
+
+    /// Request to certify, without it the certificate is self-signed
+    #[arg(short, long, requires = "issuer_path")]
+    request_path: Option<String>,
+
+    /// CA certificate matching the signing key
+    #[arg(short, long, requires = "request_path")]
+    issuer_path: Option<String>,
+
+    #[command(flatten)]
+    profile: x509::Profile,
+
+    #[command(flatten)]
+    validity: x509::Validity,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct VerifyChainArgs {
+    /// PEM certificates, the leaf first and its intermediates after it
+    #[arg(short, long)]
+    chain_path: String,
+
+    /// PEM certificates trusted as roots
+    #[arg(short, long)]
+    anchors_path: String,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct TimingArgs {
+    /// Operation to measure
+    #[arg(value_enum)]
+    target: Target,
+
+    /// Private key to measure with, a new 2048 bit key if not given
+    #[arg(short, long)]
+    keys_path: Option<String>,
+
+    /// Measurements per batch, the first batch only calibrates
+    #[arg(long, default_value_t = 10_000)]
+    batch_size: usize,
+
+    /// Batches to measure, stops early once a leak is certain
+    #[arg(long, default_value_t = 10)]
+    batches: usize,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug,)]
+struct BatchGcdArgs {
+    /// Public keys to check against each other
+    #[arg(required = true)]
+    keys_paths: Vec<String>,
+}
+
 #[derive(ValueEnum, Clone, Debug,)]
 enum KeyType {
     Private,
@@ -57,86 +594,957 @@ struct CryptoArgs {
     /// Key Type
     #[arg(value_enum)]
     key_type: KeyType,
+
+    #[command(flatten)]
+    padding: PaddingArgs,
+
+    #[command(flatten)]
+    hybrid: HybridArgs,
+
+    /// Encrypt the whole file as a stream of OAEP blocks, one per modulus-sized chunk
+    #[arg(long, conflicts_with = "hybrid")]
+    chunked: bool,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[derive(Args, Debug,)]
+struct HybridArgs {
+    /// Encrypt data of any size with an AEAD under an RSA-wrapped content key
+    #[arg(long)]
+    hybrid: bool,
+
+    /// Content key wrapping for hybrid encryption
+    #[arg(long, value_enum, default_value_t = KeyWrap::Oaep)]
+    key_wrap: KeyWrap,
+
+    /// Authenticated cipher for hybrid encryption
+    #[arg(long, value_enum, default_value_t = Aead::Aes256Gcm)]
+    aead: Aead,
+}
+
+#[derive(Args, Debug,)]
+struct PaddingArgs {
+    /// Encryption padding, pkcs1 unless given. Chunked encryption always uses OAEP
+    #[arg(long, value_enum)]
+    padding: Option<PaddingMode>,
+
+    /// OAEP digest
+    #[arg(long, value_enum, default_value_t = Digest::Sha256)]
+    oaep_digest: Digest,
+
+    /// OAEP mask generation function digest
+    #[arg(long, value_enum, default_value_t = Digest::Sha256)]
+    mgf1_digest: Digest,
+
+    /// OAEP label
+    #[arg(long)]
+    label: Option<String>,
+}
+
+#[derive(Args, Debug,)]
+struct SignArgs {
+    /// File path to read data
+    #[arg(short, long)]
+    data_path: String,
+
+    /// Path to signature file
+    #[arg(short, long)]
+    signature_path: String,
+
+    /// File path to private key for signing or public key for verification
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Signature padding
+    #[arg(long, value_enum, default_value_t = SignaturePadding::Pss)]
+    padding: SignaturePadding,
+
+    /// Message digest
+    #[arg(long, value_enum, default_value_t = Digest::Sha256)]
+    digest: Digest,
+
+    /// PSS salt length: digest, max or number of bytes.
+    /// Signing defaults to digest, verification accepts any length
+    #[arg(long)]
+    salt_length: Option<SaltLength>,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Args, Debug,)]
+struct InspectArgs {
+    /// File path to private or public key
+    #[arg(short, long)]
+    keys_path: String,
+
+    /// Public key to check against the inspected key
+    #[arg(short, long)]
+    public_key_path: Option<String>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    json: bool,
+
+    #[command(flatten)]
+    passphrase: PassphraseArgs,
+}
+
+#[cfg(feature = "openssl")]
+#[derive(Serialize)]
+struct InspectReport {
+    key: KeyInfo,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<KeyInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches: Option<bool>,
+}
+
+impl PassphraseArgs {
+    /// Passphrase given on the command line or in a file, the argument is wiped on the way.
+    fn take(&mut self) -> Option<Passphrase> {
+        if let Some(passphrase) = self.passphrase.take() {
+            return Some(Zeroizing::new(passphrase));
+        }
+
+        self.passphrase_file.as_deref().map(|path| {
+            passphrase::read_file(path).expect("Unable to read passphrase from the file!")
+        })
+    }
+
+    /// Passphrase to read `pem` with, prompting when the key is encrypted and none is given.
+    fn for_key(&mut self, pem: &[u8]) -> Option<Passphrase> {
+        self.take().or_else(|| {
+            passphrase::is_encrypted(pem).then(|| {
+                passphrase::prompt("Private key passphrase: ", false)
+                    .expect("Failed to read passphrase!")
+            })
+        })
+    }
+}
+
+impl PaddingArgs {
+    fn options(&self) -> PaddingOptions {
+        PaddingOptions {
+            mode: self.padding.unwrap_or(PaddingMode::Pkcs1),
+            oaep_digest: self.oaep_digest,
+            mgf1_digest: self.mgf1_digest,
+            label: self.label.clone().map(String::into_bytes),
+        }
+    }
+}
+
+impl SignArgs {
+    fn options(&self, default_salt_length: SaltLength) -> SignatureOptions {
+        SignatureOptions {
+            padding: self.padding,
+            digest: self.digest,
+            salt_length: self.salt_length.unwrap_or(default_salt_length),
+        }
+    }
+}
+
+#[cfg(feature = "openssl")]
+fn read_key_info(path: &str, passphrase: &mut PassphraseArgs) -> KeyInfo {
+    let pem_key_data = fs::read(path)
+        .expect("Unable to read key from the file!");
+    let key_passphrase = passphrase.for_key(&pem_key_data);
+
+    inspect::inspect(&pem_key_data, key_passphrase.as_ref().map(|p| p.as_str()))
+        .unwrap_or_else(|e| panic!("Failed to parse key from the file: {}", e))
+}
+
+fn as_str(passphrase: &Option<Passphrase>) -> Option<&str> {
+    passphrase.as_ref().map(|p| p.as_str())
+}
+
+// Modulus and public exponent of a public key file in any supported format
+fn read_public_numbers(path: &str) -> (BigUint, BigUint) {
+    let key_data = fs::read(path)
+        .expect("Unable to read key from the file!");
+    let key = native::public_key(&key_data)
+        .unwrap_or_else(|e| panic!("Failed to read public key {}: {}", path, e));
+
+    (key.n().clone(), key.e().clone())
+}
+
+fn read_cypher(path: &str) -> BigUint {
+    let cypher = fs::read(path)
+        .expect("Unable to read cypher from the file!");
+
+    BigUint::from_bytes_be(&cypher)
+}
+
+fn write_recovered_key(key: &RsaPrivateKey, args: &RecoverKeyArgs) {
+    let private_key = native::private_key_to_bytes(key, args.format, None)
+        .unwrap_or_else(|e| panic!("Failed to generate private key: {}", e));
+
+    format::write_private_key(&args.output_path, &private_key)
+        .expect("Failed to save private key to file!");
+    println!("Recovered the private key!");
+}
+
+fn run_attack(attack: Attack) {
+    match attack {
+        Attack::Wiener(args) => {
+            let (n, e) = read_public_numbers(&args.keys_path);
+            let key = rsa_attacks::wiener::attack(&n, &e)
+                .unwrap_or_else(|e| panic!("Wiener attack failed: {}", e));
+            write_recovered_key(&key, &args);
+        },
+        Attack::Fermat(args) => {
+            let (n, e) = read_public_numbers(&args.key.keys_path);
+            let key = rsa_attacks::fermat::attack(&n, &e, args.iterations)
+                .unwrap_or_else(|e| panic!("Fermat factoring failed: {}", e));
+            write_recovered_key(&key, &args.key);
+        },
+        Attack::Hastad(args) | Attack::CommonModulus(args) if args.keys_path.len() != args.cypher_path.len() => {
+            panic!("Every cypher needs its public key, got {} keys and {} cyphers!", args.keys_path.len(), args.cypher_path.len());
+        },
+        Attack::Hastad(args) => {
+            let keys: Vec<(BigUint, BigUint)> = args.keys_path.iter()
+                .map(|path| read_public_numbers(path))
+                .collect();
+            let e = &keys[0].1;
+            if keys.iter().any(|(_, other)| other != e) {
+                panic!("All keys should share the public exponent!");
+            }
+            let e = match e.to_u32_digits()[..] {
+                [e] => e,
+                _ => panic!("Public exponent is too large for the broadcast attack!"),
+            };
+
+            let cyphers: Vec<(BigUint, BigUint)> = args.cypher_path.iter().zip(keys)
+                .map(|(path, (n, _))| (read_cypher(path), n))
+                .collect();
+            let message = rsa_attacks::hastad::attack(e, &cyphers)
+                .unwrap_or_else(|e| panic!("Håstad attack failed: {}", e));
+            fs::write(&args.result_path, message.to_bytes_be())
+                .expect("Failed to save decrypted data to file");
+        },
+        Attack::CommonModulus(args) => {
+            if args.keys_path.len() != 2 {
+                panic!("Common modulus attack needs exactly two keys!");
+            }
+
+            let (n1, e1) = read_public_numbers(&args.keys_path[0]);
+            let (n2, e2) = read_public_numbers(&args.keys_path[1]);
+            if n1 != n2 {
+                panic!("Keys don't share the modulus!");
+            }
+            let c1 = read_cypher(&args.cypher_path[0]);
+            let c2 = read_cypher(&args.cypher_path[1]);
+
+            let message = rsa_attacks::common_modulus::attack(&n1, &e1, &c1, &e2, &c2)
+                .unwrap_or_else(|e| panic!("Common modulus attack failed: {}", e));
+            fs::write(&args.result_path, message.to_bytes_be())
+                .expect("Failed to save decrypted data to file");
+        },
+        Attack::BatchGcd(args) => {
+            let moduli: Vec<BigUint> = args.keys_paths.iter()
+                .map(|path| read_public_numbers(path).0)
+                .collect();
+            let shared = rsa_attacks::batch_gcd::shared_primes(&moduli);
+
+            for (i, j, factor) in &shared {
+                match *factor == moduli[*i] {
+                    true => println!("{} and {} share the modulus", args.keys_paths[*i], args.keys_paths[*j]),
+                    false => println!("{} and {} share the prime {:x}", args.keys_paths[*i], args.keys_paths[*j], factor),
+                }
+            }
+            match shared.len() {
+                0 => println!("No shared primes found!"),
+                _ => process::exit(1),
+            }
+        },
+        #[cfg(feature = "openssl")]
+        Attack::Bleichenbacher(mut args) => {
+            let key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&key_data);
+            let private_key = format::load_private_key(&key_data, as_str(&key_passphrase))
+                .expect("Failed to generate private key from key file!");
+            let cypher = fs::read(&args.cypher_path)
+                .expect("Unable to read cypher from the file!");
+
+            let mut oracle = PaddingOracle::new(&private_key);
+            let recovery = oracle.attack(&cypher, args.max_queries)
+                .unwrap_or_else(|e| panic!("Bleichenbacher attack failed after {} queries: {}", oracle.queries(), e));
+
+            println!("Recovered the message with {} oracle queries!", recovery.queries);
+            fs::write(&args.result_path, recovery.message())
+                .expect("Failed to save decrypted data to file");
+        },
+    }
+}
+
+fn run_blind_step(step: BlindStep) {
+    match step {
+        BlindStep::Blind(args) => {
+            let data = fs::read(&args.data_path)
+                .expect("Unable to read data from the file!");
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+
+            let (prepared, blinded) = blind::blind(&pem_key_data, args.variant, &data)
+                .unwrap_or_else(|e| panic!("Failed to blind the message: {}", e));
+
+            fs::write(&args.prepared_path, prepared)
+                .expect("Failed to save prepared message to file");
+            fs::write(&args.blinded_path, blinded.blinded_msg)
+                .expect("Failed to save blinded message to file");
+            // the inverse links the finalized signature to the signing request
+            format::write_private_key(&args.inverse_path, &blinded.inv)
+                .expect("Failed to save blinding inverse to file");
+        },
+        BlindStep::BlindSign(mut args) => {
+            let blinded_msg = fs::read(&args.blinded_path)
+                .expect("Unable to read blinded message from the file!");
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+
+            let blind_sig = blind::blind_sign(&pem_key_data, as_str(&key_passphrase), &blinded_msg)
+                .unwrap_or_else(|e| panic!("Failed to sign the blinded message: {}", e));
+
+            fs::write(&args.signature_path, blind_sig)
+                .expect("Failed to save blind signature to file");
+        },
+        BlindStep::Finalize(args) => {
+            let prepared = fs::read(&args.prepared_path)
+                .expect("Unable to read prepared message from the file!");
+            let blind_sig = fs::read(&args.blind_signature_path)
+                .expect("Unable to read blind signature from the file!");
+            let inv = fs::read(&args.inverse_path)
+                .expect("Unable to read blinding inverse from the file!");
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+
+            let signature = blind::finalize(&pem_key_data, args.variant, &prepared, &blind_sig, &inv)
+                .unwrap_or_else(|e| panic!("Failed to finalize the signature: {}", e));
+
+            fs::write(&args.signature_path, signature)
+                .expect("Failed to save signature to file");
+        },
+        BlindStep::Verify(args) => {
+            let prepared = fs::read(&args.prepared_path)
+                .expect("Unable to read prepared message from the file!");
+            let signature = fs::read(&args.signature_path)
+                .expect("Unable to read signature from the file!");
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+
+            let verified = blind::verify(&pem_key_data, args.variant, &prepared, &signature)
+                .unwrap_or_else(|e| panic!("Failed to verify the signature: {}", e));
+
+            match verified {
+                true => println!("Successfully passed verification!"),
+                false => {
+                    println!("Failed verification!");
+                    process::exit(1);
+                },
+            }
+        },
+    }
+}
+
+#[cfg(feature = "openssl")]
+// Restores the key from the share files, skipping damaged files and warning
+// about shares that disagree with the rest
+fn combine_share_files(paths: &[String]) -> PKey<Private> {
+    let mut loaded = Vec::new();
+    let mut indexes = Vec::new();
+    for path in paths {
+        let data = fs::read(path)
+            .expect("Unable to read share from the file!");
+
+        match shares::read_share(&data) {
+            Ok(share) => {
+                indexes.push((share.index(), path));
+                loaded.push(share);
+            },
+            Err(e) => eprintln!("Skipping share {}: {}", path, e),
+        }
+    }
+
+    let (key, corrupt) = shares::combine_key(&loaded)
+        .unwrap_or_else(|e| panic!("Failed to restore the key from shares: {}", e));
+    for (index, path) in indexes.iter().filter(|(index, _)| corrupt.contains(index)) {
+        eprintln!("Warning: share {} ({}) is corrupt!", index, path);
+    }
+
+    key
+}
+
+#[cfg(feature = "openssl")]
+fn run_share_step(step: ShareStep) {
+    match step {
+        ShareStep::Split(mut args) => {
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+            let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                .expect("Failed to generate private key from key file!");
+
+            let pems = shares::split_key(&private_key, args.threshold, args.shares)
+                .unwrap_or_else(|e| panic!("Failed to split the key: {}", e));
+
+            let save_path = Path::new(&args.save_path);
+            if !args.save_path.is_empty() {
+                fs::create_dir_all(save_path)
+                    .expect("Failed to create the save directory!");
+            }
+
+            let name = Path::new(&args.keys_path).file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("key");
+            for (index, pem) in (1..).zip(&pems) {
+                format::write_private_key(save_path.join(shares::file_name(name, index)), pem.as_bytes())
+                    .expect("Failed to save share to file!");
+            }
+            println!("Split the key into {} shares, {} of them restore it", args.shares, args.threshold);
+        },
+        ShareStep::Combine(mut args) => {
+            let key = combine_share_files(&args.shares_paths);
+            let key_passphrase = args.passphrase.take().or_else(|| {
+                args.ask_passphrase.then(|| {
+                    passphrase::prompt("New passphrase: ", true)
+                        .expect("Failed to read passphrase!")
+                })
+            });
+
+            let private_key = format::private_key_to_bytes(&key, args.format, as_str(&key_passphrase))
+                .unwrap_or_else(|e| panic!("Failed to generate private key: {}", e));
+
+            format::write_private_key(&args.output_path, &private_key)
+                .expect("Failed to save private key to file!");
+        },
+        ShareStep::Sign(args) => {
+            let key = combine_share_files(&args.shares_paths);
+            let data = File::open(&args.data_path)
+                .expect("Unable to read data from the file!");
+            let options = SignatureOptions {
+                padding: args.padding,
+                digest: args.digest,
+                salt_length: args.salt_length,
+            };
+
+            let signature = signature::sign(&key, data, &options)
+                .unwrap_or_else(|e| panic!("Failed to generate the signature: {}", e));
+
+            fs::write(&args.signature_path, signature)
+                .expect("Failed to save data signature to file");
+        },
+        ShareStep::Check(args) => {
+            let mut intact = true;
+            let mut loaded = Vec::new();
+            for path in &args.shares_paths {
+                let data = fs::read(path)
+                    .expect("Unable to read share from the file!");
+
+                match shares::read_share(&data) {
+                    Ok(share) => loaded.push((share, path)),
+                    Err(e) => {
+                        println!("{}: {}", path, e);
+                        intact = false;
+                    },
+                }
+            }
+
+            let parsed: Vec<_> = loaded.iter().map(|(share, _)| share.clone()).collect();
+            match shares::combine_key(&parsed) {
+                Ok((_, corrupt)) => {
+                    for (share, path) in &loaded {
+                        if corrupt.contains(&share.index()) {
+                            println!("{}: share {} doesn't match the other shares", path, share.index());
+                            intact = false;
+                        } else {
+                            println!("{}: share {} is intact", path, share.index());
+                        }
+                    }
+                },
+                Err(e) => {
+                    println!("Shares don't restore the key: {}", e);
+                    intact = false;
+                },
+            }
+
+            if !intact {
+                process::exit(1);
+            }
+        },
+    }
+}
+
+#[cfg(feature = "openssl")]
+fn read_certificates(path: &str) -> Vec<X509> {
+    let pem = fs::read(path)
+        .expect("Unable to read certificates from the file!");
+
+    X509::stack_from_pem(&pem)
+        .unwrap_or_else(|e| panic!("Failed to parse certificates in {}: {}", path, e))
+}
+
+// Public key of the chosen backend, for the commands that work with either
+fn load_public_key(backend: Backend, key_data: &[u8]) -> Box<dyn PublicKey> {
+    match backend {
+        #[cfg(feature = "openssl")]
+        Backend::Openssl => Box::new(format::load_public_key(key_data)
+            .expect("Failed to generate public key from key file!")),
+        Backend::Native => Box::new(native::public_key(key_data)
+            .expect("Failed to generate public key from key file!")),
+    }
+}
+
+fn load_private_key(backend: Backend, key_data: &[u8], passphrase: Option<&str>) -> Box<dyn PrivateKey> {
+    match backend {
+        #[cfg(feature = "openssl")]
+        Backend::Openssl => Box::new(format::load_private_key(key_data, passphrase)
+            .expect("Failed to generate private key from key file!")),
+        Backend::Native => Box::new(native::private_key(key_data, passphrase)
+            .expect("Failed to generate private key from key file!")),
+    }
+}
+
+// The block stream always pads with OAEP and labels the blocks itself.
+// Both directions stream between files.
+fn check_chunked(args: &CryptoArgs, action: &str) {
+    if args.padding.padding.is_some_and(|mode| mode != PaddingMode::Oaep) {
+        panic!("Chunked {} always pads with OAEP, no other --padding can be used!", action);
+    }
+    if args.padding.label.is_some() {
+        panic!("Chunked {} labels the blocks itself, --label can't be used!", action);
+    }
+}
+
+fn encrypt_chunked(args: &mut CryptoArgs, backend: Backend) {
+    check_chunked(args, "encryption");
+    if matches!(args.key_type, KeyType::Private) {
+        panic!("Chunked encryption is supported only with public key!");
+    }
+
+    let data = File::open(&args.data_path)
+        .expect("Unable to read data from the file!");
+    let pem_key_data = fs::read(&args.keys_path)
+        .expect("Unable to read key from the file!");
+    let public_key = load_public_key(backend, &pem_key_data);
+    let result = File::create(&args.result_path)
+        .expect("Failed to save encrypted data to file!");
+
+    stream::encrypt(&*public_key, BufReader::new(data), BufWriter::new(result), args.padding.oaep_digest, args.padding.mgf1_digest)
+        .unwrap_or_else(|e| panic!("Failed to encrypt data: {}", e));
+}
+
+fn decrypt_chunked(args: &mut CryptoArgs, backend: Backend) {
+    check_chunked(args, "decryption");
+    if matches!(args.key_type, KeyType::Public) {
+        panic!("Chunked decryption is supported only with private key!");
+    }
+
+    let cypher = File::open(&args.data_path)
+        .expect("Unable to read cypher from the file!");
+    let pem_key_data = fs::read(&args.keys_path)
+        .expect("Unable to read key from the file!");
+    let key_passphrase = args.passphrase.for_key(&pem_key_data);
+    let private_key = load_private_key(backend, &pem_key_data, as_str(&key_passphrase));
+    let result = File::create(&args.result_path)
+        .expect("Failed to save decrypted data to file!");
+
+    // blocks before a damaged one are already written, don't leave them looking complete
+    if let Err(e) = stream::decrypt(&*private_key, BufReader::new(cypher), BufWriter::new(result)) {
+        let _ = fs::remove_file(&args.result_path);
+        panic!("Failed to decrypt data: {}", e);
+    }
+}
+
+#[cfg(feature = "openssl")]
+fn print_key_info(info: &KeyInfo) {
+    let kind = if info.private { "private" } else { "public" };
+    println!("Type: {} {} key", info.key_type, kind);
+    println!("Bits: {}", info.bits);
+    println!("Public exponent: {}", info.public_exponent);
+    println!("Modulus: {}", info.modulus);
+    println!("SHA-256 fingerprint: {}", info.sha256_fingerprint);
+    println!("MD5 fingerprint: {}", info.md5_fingerprint);
 }
 
 fn main() { 
     let cli = Cli::parse();
 
     match cli.operation {
-        Operation::Keys(args) => {
-            // Generate an RSA key pair with 2048 bits
-            if !(args.block_size != 0 && (args.block_size & (args.block_size - 1)) == 0) {
-                panic!("Block size should be 2 in power of n. Current value is: {}", args.block_size);
+        Operation::Keys(mut args) => {
+            if !(MIN_KEY_SIZE..=MAX_KEY_SIZE).contains(&args.block_size) || !args.block_size.is_multiple_of(8) {
+                panic!("Block size should be a multiple of 8 between {} and {}. Current value is: {}", MIN_KEY_SIZE, MAX_KEY_SIZE, args.block_size);
+            }
+            if args.block_size < SAFE_KEY_SIZE {
+                eprintln!("Warning: {} bit keys are below the recommended minimum of {} bits!", args.block_size, SAFE_KEY_SIZE);
+            }
+            if args.exponent < 3 || args.exponent.is_multiple_of(2) {
+                panic!("Public exponent should be odd and at least 3. Current value is: {}", args.exponent);
             }
 
-            let rsa = Rsa::generate(args.block_size)
-                .expect("Failed to generate rsa keys!");
+            let key_passphrase = args.passphrase.take().or_else(|| {
+                args.ask_passphrase.then(|| {
+                    passphrase::prompt("New passphrase: ", true)
+                        .expect("Failed to read passphrase!")
+                })
+            });
 
             // Extract the private key and public key
-            let private_key = rsa.private_key_to_pem()
-                .expect("Failed to generate private pem key!");
-            let public_key = rsa.public_key_to_pem_pkcs1()
-                .expect("Failed to generate public pem key!");
+            let (private_key, public_key) = match cli.backend {
+                Backend::Native => {
+                    let key = native::generate_key(args.block_size as usize, args.exponent)
+                        .unwrap_or_else(|e| panic!("Failed to generate rsa keys: {}", e));
+                    (native::private_key_to_bytes(&key, args.format, as_str(&key_passphrase)),
+                        native::public_key_to_bytes(&key.to_public_key(), args.format))
+                },
+                #[cfg(feature = "openssl")]
+                Backend::Openssl => {
+                    let exponent = BigNum::from_u32(args.exponent).unwrap();
+                    let rsa = Rsa::generate_with_e(args.block_size, &exponent)
+                        .expect("Failed to generate rsa keys!");
+                    let key = PKey::from_rsa(rsa).unwrap();
+                    (format::private_key_to_bytes(&key, args.format, as_str(&key_passphrase)),
+                        format::public_key_to_bytes(&key, args.format))
+                },
+            };
+            let private_key = private_key
+                .unwrap_or_else(|e| panic!("Failed to generate private key: {}", e));
+            let public_key = public_key
+                .unwrap_or_else(|e| panic!("Failed to generate public key: {}", e));
 
             // You can save the keys to files or use them in your application
-            fs::write(format!("{}_key.pem", args.keys_name), private_key)
-                .expect("Failed to save private pem key to file!");
-            fs::write(format!("{}_key.pem.pub", args.keys_name), public_key)
-                .expect("Failed to save public pem key to file!");
+            let save_path = Path::new(&args.save_path);
+            if !args.save_path.is_empty() {
+                fs::create_dir_all(save_path)
+                    .expect("Failed to create the save directory!");
+            }
+
+            let file_name = save_path.join(args.format.file_name(&args.keys_name));
+            format::write_private_key(&file_name, &private_key)
+                .expect("Failed to save private key to file!");
+            let mut public_file_name = file_name.into_os_string();
+            public_file_name.push(".pub");
+            fs::write(public_file_name, public_key)
+                .expect("Failed to save public key to file!");
         },
-        Operation::Encrypt(args) => {
+        Operation::Encrypt(mut args) if args.chunked => encrypt_chunked(&mut args, cli.backend),
+        Operation::Decrypt(mut args) if args.chunked => decrypt_chunked(&mut args, cli.backend),
+        Operation::Encrypt(mut args) => {
             let data = fs::read(args.data_path)
                 .expect("Unable to read data from the file!");
             let pem_key_data = fs::read(args.keys_path)
                 .expect("Unable to read key from the file!");
-            let mut cypher: Vec<u8>;
-            
-            let cypher_len =  match args.key_type {
+            let options = args.padding.options();
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+
+            let cypher = match args.key_type {
+                KeyType::Private if args.hybrid.hybrid => {
+                    panic!("Hybrid encryption is supported only with public key!");
+                },
+                KeyType::Public if args.hybrid.hybrid => {
+                    let public_key = load_public_key(cli.backend, &pem_key_data);
+                    hybrid::seal(&*public_key, &data, args.hybrid.key_wrap, args.hybrid.aead)
+                        .unwrap_or_else(|e| panic!("Failed to encrypt data: {}", e))
+                },
+                KeyType::Private if options.mode == PaddingMode::Oaep => {
+                    panic!("OAEP padding is supported only for public key encryption!");
+                },
+                #[cfg(feature = "openssl")]
+                KeyType::Private if cli.backend == Backend::Openssl => {
+                    let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                        .expect("Failed to generate private key from key file!");
+                    cipher::private_encrypt(&private_key.rsa().unwrap(), &data, options.mode).unwrap()
+                },
+                #[cfg(feature = "openssl")]
+                KeyType::Public if cli.backend == Backend::Openssl => {
+                    let public_key = format::load_public_key(&pem_key_data)
+                        .expect("Failed to generate public key from key file!");
+                    let max_len = options.max_data_len(public_key.size() as usize);
+                    if data.len() > max_len {
+                        panic!("Data of {} bytes doesn't fit into a single RSA block of {} bytes, use --hybrid!", data.len(), max_len);
+                    }
+
+                    cipher::public_encrypt(&public_key, &data, &options).unwrap()
+                },
                 KeyType::Private => {
-                    let private_key = Rsa::private_key_from_pem(&pem_key_data)
-                        .expect("Failed to generate private key from pem file!");
-                    cypher = vec![0; private_key.size() as usize];
-                    private_key.private_encrypt(&data, &mut cypher, Padding::PKCS1).unwrap()
+                    native::private_encrypt(&pem_key_data, as_str(&key_passphrase), &data, &options)
+                        .unwrap_or_else(|e| panic!("Failed to encrypt data: {}", e))
                 },
                 KeyType::Public => {
-                    let public_key = Rsa::public_key_from_pem_pkcs1(&pem_key_data)
-                        .expect("Failed to generate public key from pem file!");
-                    cypher = vec![0; public_key.size() as usize];
-                    public_key.public_encrypt(&data, &mut cypher, Padding::PKCS1).unwrap()
+                    native::public_encrypt(&pem_key_data, &data, &options)
+                        .unwrap_or_else(|e| match e {
+                            rsa_core::Error::MessageTooLong => panic!("Data of {} bytes doesn't fit into a single RSA block, use --hybrid!", data.len()),
+                            e => panic!("Failed to encrypt data: {}", e),
+                        })
                 },
-            }; 
+            };
 
-            cypher.truncate(cypher_len);
-            
             fs::write(args.result_path, cypher)
                 .expect("Failed to save decrypted data to file");
         },
-        Operation::Decrypt(args) => {
+        Operation::Decrypt(mut args) => {
             let cypher = fs::read(args.data_path)
                 .expect("Unable to read cypher from the file!");
             let pem_key_data = fs::read(args.keys_path)
                 .expect("Unable to read key from the file!");
-            let mut decrypted: Vec<u8>;
-            
-            let decrypted_len =  match args.key_type {
+            let options = args.padding.options();
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+
+            let decrypted = match args.key_type {
+                KeyType::Private if args.hybrid.hybrid => {
+                    let private_key = load_private_key(cli.backend, &pem_key_data, as_str(&key_passphrase));
+                    hybrid::open(&*private_key, &cypher)
+                        .unwrap_or_else(|e| panic!("Failed to decrypt data: {}", e))
+                },
+                KeyType::Public if args.hybrid.hybrid => {
+                    panic!("Hybrid decryption is supported only with private key!");
+                },
+                KeyType::Public if options.mode == PaddingMode::Oaep => {
+                    panic!("OAEP padding is supported only for private key decryption!");
+                },
+                #[cfg(feature = "openssl")]
+                KeyType::Private if cli.backend == Backend::Openssl => {
+                    let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                        .expect("Failed to generate private key from key file!");
+                    cipher::private_decrypt(&private_key, &cypher, &options).unwrap()
+                },
+                #[cfg(feature = "openssl")]
+                KeyType::Public if cli.backend == Backend::Openssl => {
+                    let public_key = format::load_public_key(&pem_key_data)
+                        .expect("Failed to generate public key from key file!");
+                    cipher::public_decrypt(&public_key.rsa().unwrap(), &cypher, options.mode).unwrap()
+                },
                 KeyType::Private => {
-                    let private_key = Rsa::private_key_from_pem(&pem_key_data)
-                        .expect("Failed to generate private key from pem file!");
-                    decrypted = vec![0; private_key.size() as usize];
-                    private_key.private_decrypt(&cypher, &mut decrypted, Padding::PKCS1).unwrap()
+                    native::private_decrypt(&pem_key_data, as_str(&key_passphrase), &cypher, &options)
+                        .unwrap_or_else(|e| panic!("Failed to decrypt data: {}", e))
                 },
                 KeyType::Public => {
-                    let public_key = Rsa::public_key_from_pem_pkcs1(&pem_key_data)
-                        .expect("Failed to generate public key from pem file!");
-                    decrypted = vec![0; public_key.size() as usize];
-                    public_key.public_decrypt(&cypher, &mut decrypted, Padding::PKCS1).unwrap()
+                    native::public_decrypt(&pem_key_data, &cypher, &options)
+                        .unwrap_or_else(|e| panic!("Failed to decrypt data: {}", e))
                 },
-            }; 
+            };
 
-            decrypted.truncate(decrypted_len);
-            
             fs::write(args.result_path, decrypted)
                 .expect("Failed to save decrypted data to file");
         },
+        Operation::Sign(mut args) => {
+            // streamed, so files of any size can be signed
+            let data = File::open(&args.data_path)
+                .expect("Unable to read data from the file!");
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+            let options = args.options(SaltLength::Digest);
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+
+            let signature = match cli.backend {
+                Backend::Native => native::sign(&pem_key_data, as_str(&key_passphrase), data, &options)
+                    .unwrap_or_else(|e| panic!("Failed to generate the signature: {}", e)),
+                #[cfg(feature = "openssl")]
+                Backend::Openssl => {
+                    let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                        .expect("Failed to generate private key from key file!");
+                    signature::sign(&private_key, data, &options)
+                        .unwrap_or_else(|e| panic!("Failed to generate the signature: {}", e))
+                },
+            };
+
+            fs::write(&args.signature_path, signature)
+                .expect("Failed to save data signature to file");
+        },
+        Operation::Verify(args) => {
+            let data = File::open(&args.data_path)
+                .expect("Unable to read data from the file!");
+            let signature = fs::read(&args.signature_path)
+                .expect("Unable to read signature from the file!");
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+            let options = args.options(SaltLength::Max);
+
+            let verified = match cli.backend {
+                Backend::Native => native::verify(&pem_key_data, data, &signature, &options)
+                    .unwrap_or_else(|e| panic!("Failed to verify the data by signature: {}", e)),
+                #[cfg(feature = "openssl")]
+                Backend::Openssl => {
+                    let public_key = format::load_public_key(&pem_key_data)
+                        .expect("Failed to generate public key from key file!");
+                    signature::verify(&public_key, data, &signature, &options)
+                        .unwrap_or_else(|e| panic!("Failed to verify the data by signature: {}", e))
+                },
+            };
+
+            match verified {
+                true => println!("Successfully passed verification!"),
+                false => {
+                    println!("Failed verification!");
+                    process::exit(1);
+                },
+            }
+        },
+        #[cfg(feature = "openssl")]
+        Operation::Inspect(mut args) => {
+            let key = read_key_info(&args.keys_path, &mut args.passphrase);
+            let public_key = args.public_key_path.as_deref()
+                .map(|path| read_key_info(path, &mut args.passphrase));
+            let matches = public_key.as_ref().map(|public_key| key.matches(public_key));
+
+            if args.json {
+                let report = InspectReport { key, public_key, matches };
+                println!("{}", serde_json::to_string_pretty(&report).expect("Failed to serialize report!"));
+            } else {
+                print_key_info(&key);
+
+                if let Some(public_key) = &public_key {
+                    println!();
+                    print_key_info(public_key);
+                    println!();
+                    match matches {
+                        Some(true) => println!("Keys belong to the same pair!"),
+                        _ => println!("Keys don't belong to the same pair!"),
+                    }
+                }
+            }
+
+            if matches == Some(false) {
+                process::exit(1);
+            }
+        },
+        #[cfg(feature = "openssl")]
+        Operation::ChangePassphrase(mut args) => {
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+            let old_passphrase = args.passphrase.for_key(&pem_key_data);
+            let new_passphrase = match (args.new_passphrase.take(), &args.new_passphrase_file) {
+                (Some(new_passphrase), _) => Zeroizing::new(new_passphrase),
+                (None, Some(path)) => passphrase::read_file(path)
+                    .expect("Unable to read passphrase from the file!"),
+                (None, None) => passphrase::prompt("New passphrase: ", true)
+                    .expect("Failed to read passphrase!"),
+            };
+            let new_passphrase = Some(new_passphrase).filter(|p| !p.is_empty());
+
+            let private_key = format::load_private_key(&pem_key_data, as_str(&old_passphrase))
+                .expect("Failed to generate private key from key file!");
+            let key_format = format::private_key_format(&pem_key_data);
+            let private_key = format::private_key_to_bytes(&private_key, key_format, as_str(&new_passphrase))
+                .unwrap_or_else(|e| panic!("Failed to generate private key: {}", e));
+
+            format::write_private_key(&args.keys_path, &private_key)
+                .expect("Failed to save private key to file!");
+        },
+        #[cfg(feature = "openssl")]
+        Operation::Convert(mut args) => {
+            let key_data = fs::read(&args.input_path)
+                .expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&key_data);
+
+            let (converted, private) = match format::load_private_key(&key_data, as_str(&key_passphrase)) {
+                Ok(key) if args.public => (format::public_key_to_bytes(&key, args.format), false),
+                Ok(key) => (format::private_key_to_bytes(&key, args.format, as_str(&key_passphrase)), true),
+                Err(e) if passphrase::is_encrypted(&key_data) => panic!("Failed to read private key: {}", e),
+                Err(_) => {
+                    let key = format::load_public_key(&key_data)
+                        .unwrap_or_else(|e| panic!("Failed to read key: {}", e));
+                    (format::public_key_to_bytes(&key, args.format), false)
+                },
+            };
+            let converted = converted
+                .unwrap_or_else(|e| panic!("Failed to convert key: {}", e));
+
+            match private {
+                true => format::write_private_key(&args.output_path, &converted),
+                false => fs::write(&args.output_path, converted),
+            }
+            .expect("Failed to save converted key to file!");
+        },
+        Operation::Attack(args) => run_attack(args.attack),
+        Operation::BlindRsa(args) => run_blind_step(args.step),
+        #[cfg(feature = "openssl")]
+        Operation::Shares(args) => run_share_step(args.step),
+        #[cfg(feature = "openssl")]
+        Operation::Csr(mut args) => {
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+            let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                .expect("Failed to generate private key from key file!");
+
+            let request = x509::build_request(&private_key, &args.profile)
+                .unwrap_or_else(|e| panic!("Failed to build the request: {}", e));
+
+            fs::write(&args.output_path, request.to_pem().unwrap())
+                .expect("Failed to save request to file!");
+        },
+        #[cfg(feature = "openssl")]
+        Operation::Cert(mut args) => {
+            let pem_key_data = fs::read(&args.keys_path)
+                .expect("Unable to read key from the file!");
+            let key_passphrase = args.passphrase.for_key(&pem_key_data);
+            let private_key = format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                .expect("Failed to generate private key from key file!");
+
+            let certificate = match (&args.request_path, &args.issuer_path) {
+                (Some(request_path), Some(issuer_path)) => {
+                    let request = fs::read(request_path)
+                        .expect("Unable to read request from the file!");
+                    let request = x509::load_request(&request)
+                        .unwrap_or_else(|e| panic!("Failed to read the request: {}", e));
+                    let issuer = read_certificates(issuer_path).into_iter().next()
+                        .expect("No certificate in the issuer file!");
+
+                    x509::sign_request(&request, &args.profile, &args.validity, Issuer { certificate: &issuer, key: &private_key })
+                },
+                _ => x509::self_signed(&private_key, &args.profile, &args.validity),
+            }
+            .unwrap_or_else(|e| panic!("Failed to build the certificate: {}", e));
+
+            fs::write(&args.output_path, certificate.to_pem().unwrap())
+                .expect("Failed to save certificate to file!");
+        },
+        #[cfg(feature = "openssl")]
+        Operation::VerifyChain(args) => {
+            let chain = read_certificates(&args.chain_path);
+            let anchors = read_certificates(&args.anchors_path);
+
+            match x509::verify_chain(&chain, &anchors) {
+                Ok(path) => {
+                    for (depth, certificate) in path.iter().enumerate() {
+                        println!("{}: {}", depth, x509::subject_line(certificate));
+                    }
+                    println!("Successfully passed verification!");
+                },
+                Err(e) => {
+                    println!("Failed verification! {}", e);
+                    process::exit(1);
+                },
+            }
+        },
+        #[cfg(feature = "openssl")]
+        Operation::Timing(mut args) => {
+            let key = match &args.keys_path {
+                Some(path) => {
+                    let pem_key_data = fs::read(path)
+                        .expect("Unable to read key from the file!");
+                    let key_passphrase = args.passphrase.for_key(&pem_key_data);
+                    format::load_private_key(&pem_key_data, as_str(&key_passphrase))
+                        .expect("Failed to generate private key from key file!")
+                },
+                None => PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap(),
+            };
+
+            println!("Measuring {:?}, t above 4.5 suggests a leak, above 10 proves one", args.target);
+            let report = |status: &timing::Status| {
+                println!("{:>10} measurements, max |t| = {:>8.2}: {}", status.measurements, status.max_t, status.verdict());
+            };
+            let status = timing::measure(args.target, &key, cli.backend == Backend::Native, args.batch_size, args.batches, report)
+                .unwrap_or_else(|e| panic!("Failed to measure timing: {}", e));
+
+            if status.verdict() != timing::Verdict::NoLeakFound {
+                process::exit(1);
+            }
+        },
     };
 }
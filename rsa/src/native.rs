@@ -0,0 +1,323 @@
+// Adapter from the CLI options to the OpenSSL-free rsa_core backend
+use crate::cipher::{self, PaddingMode, PaddingOptions, PrivateKey, PublicKey};
+use crate::format::{self, KeyFormat};
+use crate::signature::{SaltLength, SignatureOptions, SignaturePadding};
+use crate::Digest;
+use num_bigint::BigUint;
+#[cfg(feature = "openssl")]
+use openssl::pkey::{PKey, Private};
+use pki::passphrase;
+use rsa_core::{EncryptionPadding, Error, HashAlgorithm, RsaPrivateKey, RsaPublicKey};
+use std::io::{self, Read};
+
+impl Digest {
+    pub fn hash_algorithm(self) -> HashAlgorithm {
+        match self {
+            Digest::Sha1 => HashAlgorithm::Sha1,
+            Digest::Sha224 => HashAlgorithm::Sha224,
+            Digest::Sha256 => HashAlgorithm::Sha256,
+            Digest::Sha384 => HashAlgorithm::Sha384,
+            Digest::Sha512 => HashAlgorithm::Sha512,
+        }
+    }
+}
+
+impl PaddingOptions {
+    pub fn encryption_padding(&self) -> EncryptionPadding {
+        match self.mode {
+            PaddingMode::Pkcs1 => EncryptionPadding::Pkcs1v15,
+            PaddingMode::Oaep => EncryptionPadding::Oaep {
+                hash: self.oaep_digest.hash_algorithm(),
+                mgf1_hash: self.mgf1_digest.hash_algorithm(),
+                label: self.label.clone().unwrap_or_default(),
+            },
+            PaddingMode::None => EncryptionPadding::None,
+        }
+    }
+}
+
+impl SignatureOptions {
+    pub fn signature_padding(&self) -> rsa_core::SignaturePadding {
+        match self.padding {
+            SignaturePadding::Pkcs1 => rsa_core::SignaturePadding::Pkcs1v15,
+            SignaturePadding::Pss => rsa_core::SignaturePadding::Pss {
+                salt_len: match self.salt_length {
+                    SaltLength::Digest => Some(self.digest.hash_algorithm().size()),
+                    SaltLength::Max => None,
+                    SaltLength::Bytes(len) => Some(len as usize),
+                },
+            },
+        }
+    }
+}
+
+fn private_key_from_der(der: &[u8], passphrase: Option<&str>) -> Result<RsaPrivateKey, Error> {
+    match passphrase {
+        Some(passphrase) if passphrase::is_encrypted(der) => {
+            RsaPrivateKey::from_encrypted_pkcs8_der(der, passphrase.as_bytes())
+        }
+        _ => RsaPrivateKey::from_pkcs8_der(der).or_else(|_| RsaPrivateKey::from_pkcs1_der(der)),
+    }
+}
+
+// PEM and DER are parsed natively, JWK and OpenSSH go through the OpenSSL
+// parsers when they are built in and arrive here as PKCS#8 DER
+pub fn private_key(data: &[u8], passphrase: Option<&str>) -> Result<RsaPrivateKey, Error> {
+    let native = match std::str::from_utf8(data) {
+        Ok(pem) if pem.contains("-----BEGIN ") => match passphrase {
+            Some(passphrase) => RsaPrivateKey::from_pem_with_passphrase(pem, passphrase.as_bytes()),
+            None => RsaPrivateKey::from_pem(pem),
+        },
+        _ => private_key_from_der(data, passphrase),
+    };
+
+    match native {
+        #[cfg(feature = "openssl")]
+        Err(Error::InvalidKey(_)) => {
+            let key = format::load_private_key(data, passphrase)
+                .map_err(|_| Error::InvalidKey("unrecognized key format"))?;
+            let der = key
+                .private_key_to_pkcs8()
+                .map_err(|_| Error::InvalidKey("unrecognized key format"))?;
+
+            RsaPrivateKey::from_pkcs8_der(&der)
+        }
+        result => result,
+    }
+}
+
+pub fn public_key(data: &[u8]) -> Result<RsaPublicKey, Error> {
+    let native = match std::str::from_utf8(data) {
+        Ok(pem) if pem.contains("-----BEGIN ") => RsaPublicKey::from_pem(pem),
+        _ => RsaPublicKey::from_public_key_der(data)
+            .or_else(|_| RsaPublicKey::from_pkcs1_der(data)),
+    };
+
+    match native {
+        #[cfg(feature = "openssl")]
+        Err(Error::InvalidKey(_)) => {
+            let key = format::load_public_key(data)
+                .map_err(|_| Error::InvalidKey("unrecognized key format"))?;
+            let der = key
+                .public_key_to_der()
+                .map_err(|_| Error::InvalidKey("unrecognized key format"))?;
+
+            RsaPublicKey::from_public_key_der(&der)
+        }
+        result => result,
+    }
+}
+
+/// rsa_core key as an OpenSSL key for serialization.
+#[cfg(feature = "openssl")]
+pub fn openssl_private_key(key: &RsaPrivateKey) -> PKey<Private> {
+    PKey::private_key_from_der(&key.to_pkcs8_der()).expect("Native key should be valid DER")
+}
+
+pub fn generate_key(bits: usize, exponent: u32) -> Result<RsaPrivateKey, Error> {
+    RsaPrivateKey::generate_with_exponent(bits, &BigUint::from(exponent))
+}
+
+/// Serializes a private key the way `format::private_key_to_bytes` does, a
+/// passphrase always produces encrypted PKCS#8.
+pub fn private_key_to_bytes(
+    key: &RsaPrivateKey,
+    format: KeyFormat,
+    passphrase: Option<&str>,
+) -> Result<Vec<u8>, format::Error> {
+    match (format, passphrase) {
+        (KeyFormat::PemPkcs1 | KeyFormat::PemPkcs8, Some(passphrase)) => {
+            Ok(key.to_encrypted_pkcs8_pem(passphrase.as_bytes())?.into_bytes())
+        }
+        (KeyFormat::PemPkcs1, None) => Ok(key.to_pkcs1_pem().into_bytes()),
+        (KeyFormat::PemPkcs8, None) => Ok(key.to_pkcs8_pem().into_bytes()),
+        (KeyFormat::Der, Some(passphrase)) => Ok(key.to_encrypted_pkcs8_der(passphrase.as_bytes())?),
+        (KeyFormat::Der, None) => Ok(key.to_pkcs8_der()),
+        #[cfg(feature = "openssl")]
+        (KeyFormat::Jwk | KeyFormat::Openssh, _) => {
+            format::private_key_to_bytes(&openssl_private_key(key), format, passphrase)
+        }
+        #[cfg(not(feature = "openssl"))]
+        (KeyFormat::Jwk | KeyFormat::Openssh, _) => {
+            Err(format::Error::Unsupported("jwk and openssh keys without the openssl feature"))
+        }
+    }
+}
+
+pub fn public_key_to_bytes(key: &RsaPublicKey, format: KeyFormat) -> Result<Vec<u8>, format::Error> {
+    match format {
+        KeyFormat::PemPkcs1 => Ok(key.to_pkcs1_pem().into_bytes()),
+        KeyFormat::PemPkcs8 => Ok(key.to_public_key_pem().into_bytes()),
+        KeyFormat::Der => Ok(key.to_public_key_der()),
+        #[cfg(feature = "openssl")]
+        KeyFormat::Jwk | KeyFormat::Openssh => {
+            let public_key = PKey::public_key_from_der(&key.to_public_key_der())?;
+            format::public_key_to_bytes(&public_key, format)
+        }
+        #[cfg(not(feature = "openssl"))]
+        KeyFormat::Jwk | KeyFormat::Openssh => {
+            Err(format::Error::Unsupported("jwk and openssh keys without the openssl feature"))
+        }
+    }
+}
+
+impl PublicKey for RsaPublicKey {
+    fn size(&self) -> usize {
+        RsaPublicKey::size(self)
+    }
+
+    fn modulus(&self) -> Vec<u8> {
+        self.n().to_bytes_be()
+    }
+
+    fn public_encrypt(&self, data: &[u8], options: &PaddingOptions) -> Result<Vec<u8>, cipher::Error> {
+        Ok(self.encrypt(data, &options.encryption_padding())?)
+    }
+}
+
+impl PublicKey for RsaPrivateKey {
+    fn size(&self) -> usize {
+        RsaPrivateKey::size(self)
+    }
+
+    fn modulus(&self) -> Vec<u8> {
+        self.to_public_key().n().to_bytes_be()
+    }
+
+    fn public_encrypt(&self, data: &[u8], options: &PaddingOptions) -> Result<Vec<u8>, cipher::Error> {
+        Ok(self.to_public_key().encrypt(data, &options.encryption_padding())?)
+    }
+}
+
+impl PrivateKey for RsaPrivateKey {
+    fn private_decrypt(&self, cypher: &[u8], options: &PaddingOptions) -> Result<Vec<u8>, cipher::Error> {
+        Ok(self.decrypt(cypher, &options.encryption_padding())?)
+    }
+}
+
+pub fn public_encrypt(pem: &[u8], data: &[u8], options: &PaddingOptions) -> Result<Vec<u8>, Error> {
+    public_key(pem)?.encrypt(data, &options.encryption_padding())
+}
+
+pub fn private_decrypt(
+    pem: &[u8],
+    passphrase: Option<&str>,
+    cypher: &[u8],
+    options: &PaddingOptions,
+) -> Result<Vec<u8>, Error> {
+    private_key(pem, passphrase)?.decrypt(cypher, &options.encryption_padding())
+}
+
+pub fn private_encrypt(
+    pem: &[u8],
+    passphrase: Option<&str>,
+    data: &[u8],
+    options: &PaddingOptions,
+) -> Result<Vec<u8>, Error> {
+    private_key(pem, passphrase)?.private_encrypt(data, &options.encryption_padding())
+}
+
+pub fn public_decrypt(
+    pem: &[u8],
+    cypher: &[u8],
+    options: &PaddingOptions,
+) -> Result<Vec<u8>, Error> {
+    public_key(pem)?.public_decrypt(cypher, &options.encryption_padding())
+}
+
+/// Signs everything `data` yields, which is hashed as it is read.
+pub fn sign(
+    pem: &[u8],
+    passphrase: Option<&str>,
+    data: impl Read,
+    options: &SignatureOptions,
+) -> io::Result<Vec<u8>> {
+    let key = private_key(pem, passphrase).map_err(io::Error::other)?;
+    let hash = options.digest.hash_algorithm();
+    let digest = hash.digest_reader(data)?;
+
+    key.sign_digest(hash, &digest, options.signature_padding())
+        .map_err(io::Error::other)
+}
+
+pub fn verify(
+    pem: &[u8],
+    data: impl Read,
+    signature: &[u8],
+    options: &SignatureOptions,
+) -> io::Result<bool> {
+    let key = public_key(pem).map_err(io::Error::other)?;
+    let hash = options.digest.hash_algorithm();
+    let digest = hash.digest_reader(data)?;
+
+    match key.verify_digest(hash, &digest, signature, options.signature_padding()) {
+        Ok(()) => Ok(true),
+        Err(Error::Verification) => Ok(false),
+        Err(e) => Err(io::Error::other(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_key_files() {
+        let key = RsaPrivateKey::generate(1024).unwrap();
+        let public = key.to_public_key();
+
+        for format in [KeyFormat::PemPkcs1, KeyFormat::PemPkcs8, KeyFormat::Der] {
+            for passphrase in [None, Some("secret")] {
+                let data = private_key_to_bytes(&key, format, passphrase).unwrap();
+                assert_eq!(passphrase::is_encrypted(&data), passphrase.is_some());
+                assert_eq!(private_key(&data, passphrase).unwrap().d(), key.d());
+            }
+
+            let data = public_key_to_bytes(&public, format).unwrap();
+            assert_eq!(public_key(&data).unwrap().n(), public.n());
+            assert!(private_key(&data, None).is_err());
+        }
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod openssl_tests {
+    use super::*;
+    use crate::{cipher, signature};
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_backends_interoperate() {
+        let rsa = Rsa::generate(2048).unwrap();
+        let private_pem = rsa.private_key_to_pem().unwrap();
+        let public_pem = rsa.public_key_to_pem_pkcs1().unwrap();
+        let key = PKey::from_rsa(rsa).unwrap();
+        let data = b"either backend";
+
+        let options = PaddingOptions {
+            mode: PaddingMode::Oaep,
+            label: Some(b"label".to_vec()),
+            ..PaddingOptions::default()
+        };
+        let cypher = public_encrypt(&public_pem, data, &options).unwrap();
+        assert_eq!(
+            cipher::private_decrypt(&key, &cypher, &options).unwrap(),
+            data
+        );
+
+        let cypher = cipher::public_encrypt(&key, data, &options).unwrap();
+        assert_eq!(
+            private_decrypt(&private_pem, None, &cypher, &options).unwrap(),
+            data
+        );
+
+        let options = SignatureOptions::default();
+        let native = sign(&private_pem, None, &data[..], &options).unwrap();
+        assert!(signature::verify(&key, &data[..], &native, &options).unwrap());
+
+        let openssl = signature::sign(&key, &data[..], &options).unwrap();
+        assert!(verify(&public_pem, &data[..], &openssl, &options).unwrap());
+        assert!(!verify(&public_pem, &b"other data"[..], &openssl, &options).unwrap());
+    }
+}
@@ -0,0 +1,77 @@
+// Simulated PKCS#1 v1.5 padding oracle for the Bleichenbacher demonstration:
+// a server that decrypts whatever it gets and lets slip whether the padding
+// was valid, through an error message, an alert or its timing.
+use crate::cipher::{self, PaddingOptions};
+use num_bigint::BigUint;
+use openssl::pkey::{HasPrivate, PKeyRef};
+use rsa_attacks::bleichenbacher::{self, Recovery};
+
+pub struct PaddingOracle<'a, T> {
+    key: &'a PKeyRef<T>,
+    options: PaddingOptions,
+    queries: u64,
+}
+
+impl<'a, T: HasPrivate> PaddingOracle<'a, T> {
+    pub fn new(key: &'a PKeyRef<T>) -> Self {
+        PaddingOracle {
+            key,
+            options: PaddingOptions::default(),
+            queries: 0,
+        }
+    }
+
+    /// Whether `cypher` decrypts with valid PKCS#1 v1.5 padding, the plaintext never leaves.
+    pub fn is_conforming(&mut self, cypher: &[u8]) -> bool {
+        self.queries += 1;
+
+        cipher::private_decrypt(self.key, cypher, &self.options).is_ok()
+    }
+
+    /// Cyphers decrypted so far.
+    pub fn queries(&self) -> u64 {
+        self.queries
+    }
+
+    /// Runs Bleichenbacher's attack on `cypher` against this oracle.
+    pub fn attack(
+        &mut self,
+        cypher: &[u8],
+        max_queries: u64,
+    ) -> Result<Recovery, rsa_attacks::Error> {
+        let rsa = self.key.rsa().expect("Padding oracle needs an RSA key!");
+        let size = rsa.size() as usize;
+        let n = BigUint::from_bytes_be(&rsa.n().to_vec());
+        let e = BigUint::from_bytes_be(&rsa.e().to_vec());
+
+        let oracle = |c: &BigUint| {
+            let mut bytes = c.to_bytes_be();
+            bytes.splice(0..0, std::iter::repeat_n(0, size - bytes.len()));
+            self.is_conforming(&bytes)
+        };
+
+        bleichenbacher::attack(&n, &e, &BigUint::from_bytes_be(cypher), oracle, max_queries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_recovers_plaintext_with_1024_bit_key() {
+        let key = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        let message = b"Some secret message";
+        let cypher = cipher::public_encrypt(&key, message, &PaddingOptions::default()).unwrap();
+
+        let mut oracle = PaddingOracle::new(&key);
+        assert!(oracle.is_conforming(&cypher));
+        assert!(!oracle.is_conforming(&[0x42; 128]));
+
+        let recovery = oracle.attack(&cypher, u64::MAX).unwrap();
+        assert_eq!(recovery.message(), message);
+        assert_eq!(recovery.queries + 2, oracle.queries());
+    }
+}
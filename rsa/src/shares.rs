@@ -0,0 +1,73 @@
+// Private keys split into Shamir shares, the shared secret is the key's PKCS#8 DER
+use openssl::pkey::{PKey, PKeyRef, Private};
+use rsa_core::shamir::{self, Share};
+use rsa_core::Error;
+use zeroize::Zeroizing;
+
+/// Share file name for the `index`th share of the key in `name`.
+pub fn file_name(name: &str, index: u8) -> String {
+    format!("{}_share_{}.pem", name, index)
+}
+
+/// Splits `key` into `count` PEM encoded shares, any `threshold` of which restore it.
+pub fn split_key(
+    key: &PKeyRef<Private>,
+    threshold: u8,
+    count: u8,
+) -> Result<Vec<Zeroizing<String>>, Error> {
+    let der = Zeroizing::new(
+        key.private_key_to_pkcs8()
+            .map_err(|_| Error::InvalidKey("unserializable private key"))?,
+    );
+
+    Ok(shamir::split(&der, threshold, count)?
+        .iter()
+        .map(Share::to_pem)
+        .collect())
+}
+
+pub fn read_share(data: &[u8]) -> Result<Share, Error> {
+    let pem = std::str::from_utf8(data).map_err(|_| Error::InvalidInput("not an RSA key share"))?;
+
+    Share::from_pem(pem)
+}
+
+/// Restores the key from `shares`, along with the indexes of the shares that
+/// didn't agree with the rest.
+pub fn combine_key(shares: &[Share]) -> Result<(PKey<Private>, Vec<u8>), Error> {
+    let recovered = shamir::combine(shares)?;
+    let key = PKey::private_key_from_pkcs8(&recovered.secret)
+        .map_err(|_| Error::InvalidKey("shares don't hold a private key"))?;
+
+    Ok((key, recovered.corrupt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_key_round_trip_through_shares() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let pems = split_key(&key, 2, 3).unwrap();
+        let shares: Vec<Share> = pems
+            .iter()
+            .map(|pem| read_share(pem.as_bytes()).unwrap())
+            .collect();
+
+        let (restored, corrupt) = combine_key(&shares[1..]).unwrap();
+        assert!(restored.public_eq(&key));
+        assert_eq!(
+            restored.private_key_to_pkcs8().unwrap(),
+            key.private_key_to_pkcs8().unwrap()
+        );
+        assert!(corrupt.is_empty());
+
+        // a flipped character in the file
+        let mut damaged = pems[0].as_bytes().to_vec();
+        damaged[100] = if damaged[100] == b'A' { b'B' } else { b'A' };
+        assert!(read_share(&damaged).is_err());
+        assert!(combine_key(&shares[..1]).is_err());
+    }
+}
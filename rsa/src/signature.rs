@@ -0,0 +1,186 @@
+use crate::Digest;
+use clap::ValueEnum;
+#[cfg(feature = "openssl")]
+use openssl::pkey::{HasPrivate, HasPublic, PKeyRef};
+#[cfg(feature = "openssl")]
+use openssl::rsa::Padding;
+#[cfg(feature = "openssl")]
+use openssl::sign::{RsaPssSaltlen, Signer, Verifier};
+#[cfg(feature = "openssl")]
+use std::io::{self, Read};
+use std::str::FromStr;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum SignaturePadding {
+    /// PKCS#1 v1.5 signature
+    Pkcs1,
+    /// Probabilistic signature scheme
+    Pss,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SaltLength {
+    /// Same as the digest size
+    Digest,
+    /// Largest salt the modulus fits, accepts any salt length on verification
+    Max,
+    Bytes(u32),
+}
+
+impl FromStr for SaltLength {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "digest" => Ok(SaltLength::Digest),
+            "max" | "auto" => Ok(SaltLength::Max),
+            _ => value
+                .parse()
+                .map(SaltLength::Bytes)
+                .map_err(|_| format!("Salt length should be digest, max or a number of bytes. Current value is: {}", value)),
+        }
+    }
+}
+
+#[cfg(feature = "openssl")]
+impl SaltLength {
+    fn saltlen(self) -> RsaPssSaltlen {
+        match self {
+            SaltLength::Digest => RsaPssSaltlen::DIGEST_LENGTH,
+            SaltLength::Max => RsaPssSaltlen::MAXIMUM_LENGTH,
+            SaltLength::Bytes(len) => RsaPssSaltlen::custom(len as i32),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SignatureOptions {
+    pub padding: SignaturePadding,
+    pub digest: Digest,
+    pub salt_length: SaltLength,
+}
+
+impl Default for SignatureOptions {
+    fn default() -> Self {
+        SignatureOptions {
+            padding: SignaturePadding::Pss,
+            digest: Digest::Sha256,
+            salt_length: SaltLength::Digest,
+        }
+    }
+}
+
+/// Signs everything `data` yields, which is hashed as it is read.
+#[cfg(feature = "openssl")]
+pub fn sign<T: HasPrivate>(
+    key: &PKeyRef<T>,
+    mut data: impl Read,
+    options: &SignatureOptions,
+) -> io::Result<Vec<u8>> {
+    let mut signer = Signer::new(options.digest.message_digest(), key)?;
+
+    if options.padding == SignaturePadding::Pss {
+        signer.set_rsa_padding(Padding::PKCS1_PSS)?;
+        signer.set_rsa_pss_saltlen(options.salt_length.saltlen())?;
+        signer.set_rsa_mgf1_md(options.digest.message_digest())?;
+    }
+
+    io::copy(&mut data, &mut signer)?;
+    Ok(signer.sign_to_vec()?)
+}
+
+#[cfg(feature = "openssl")]
+pub fn verify<T: HasPublic>(
+    key: &PKeyRef<T>,
+    mut data: impl Read,
+    signature: &[u8],
+    options: &SignatureOptions,
+) -> io::Result<bool> {
+    let mut verifier = Verifier::new(options.digest.message_digest(), key)?;
+
+    if options.padding == SignaturePadding::Pss {
+        verifier.set_rsa_padding(Padding::PKCS1_PSS)?;
+        verifier.set_rsa_pss_saltlen(options.salt_length.saltlen())?;
+        verifier.set_rsa_mgf1_md(options.digest.message_digest())?;
+    }
+
+    io::copy(&mut data, &mut verifier)?;
+    // OpenSSL reports malformed signatures as errors, they are just invalid here
+    Ok(verifier.verify(signature).unwrap_or(false))
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod tests {
+    use super::*;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_sign_verify_round_trip() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let public_key = PKey::public_key_from_pem(&key.public_key_to_pem().unwrap()).unwrap();
+        let data: &[u8] = b"Data to sign";
+
+        for digest in [
+            Digest::Sha1,
+            Digest::Sha224,
+            Digest::Sha256,
+            Digest::Sha384,
+            Digest::Sha512,
+        ] {
+            let mut cases = vec![SignatureOptions {
+                padding: SignaturePadding::Pkcs1,
+                digest,
+                salt_length: SaltLength::Digest,
+            }];
+
+            for salt_length in [SaltLength::Digest, SaltLength::Max, SaltLength::Bytes(0), SaltLength::Bytes(20)] {
+                cases.push(SignatureOptions {
+                    padding: SignaturePadding::Pss,
+                    digest,
+                    salt_length,
+                });
+            }
+
+            for options in &cases {
+                let signature = sign(&key, data, options).unwrap();
+
+                assert!(verify(&public_key, data, &signature, options).unwrap(), "{:?}", options);
+                assert!(!verify(&public_key, &b"Other data"[..], &signature, options).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_streamed_data() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let data = || io::repeat(0x42).take(5 * 1024 * 1024);
+
+        for digest in [Digest::Sha256, Digest::Sha384, Digest::Sha512] {
+            let options = SignatureOptions {
+                digest,
+                ..Default::default()
+            };
+            let signature = sign(&key, data(), &options).unwrap();
+
+            assert!(verify(&key, data(), &signature, &options).unwrap());
+            assert!(!verify(&key, data().take(1024), &signature, &options).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_pss_verify_accepts_any_salt_with_max() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let options = SignatureOptions {
+            salt_length: SaltLength::Bytes(7),
+            ..Default::default()
+        };
+        let signature = sign(&key, &b"data"[..], &options).unwrap();
+        let auto = SignatureOptions {
+            salt_length: SaltLength::Max,
+            ..Default::default()
+        };
+
+        assert!(verify(&key, &b"data"[..], &signature, &auto).unwrap());
+    }
+}
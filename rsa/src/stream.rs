@@ -0,0 +1,352 @@
+use crate::cipher::{self, PaddingMode, PaddingOptions, PrivateKey, PublicKey};
+use crate::Digest;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+// Whole-file RSA: the input cut into chunks that fit one OAEP block each.
+// Stream layout, every number is big-endian:
+//
+//   magic "RSAB" | version u8 | oaep digest u8 | mgf1 digest u8 | reserved u8
+//   | modulus size u16 | { block length u16 | final flag u8 | block } ...
+//
+// The OAEP label of every block is its index u64 and the final flag u8, so
+// blocks can't be reordered, dropped or cut off after the final one without
+// failing to decrypt. The flag is repeated in the frame to pick the label,
+// a forged one fails like any other change. Far slower than `hybrid`, it's
+// meant for comparisons.
+
+const MAGIC: &[u8; 4] = b"RSAB";
+const VERSION: u8 = 2;
+const HEADER_LEN: usize = 10;
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidStream(&'static str),
+    UnsupportedVersion(u8),
+    /// Block didn't decrypt at its position, the stream was altered
+    Integrity {
+        block: u64,
+    },
+    Io(io::Error),
+    Rsa(cipher::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidStream(reason) => write!(f, "Invalid block stream: {}", reason),
+            Error::UnsupportedVersion(version) => {
+                write!(f, "Unsupported block stream version: {}", version)
+            }
+            Error::Integrity { block } => {
+                write!(f, "Block {} failed integrity check", block)
+            }
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Rsa(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<cipher::Error> for Error {
+    fn from(e: cipher::Error) -> Self {
+        Error::Rsa(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+fn digest_id(digest: Digest) -> u8 {
+    match digest {
+        Digest::Sha1 => 1,
+        Digest::Sha224 => 2,
+        Digest::Sha256 => 3,
+        Digest::Sha384 => 4,
+        Digest::Sha512 => 5,
+    }
+}
+
+fn digest_from_id(id: u8) -> Result<Digest, Error> {
+    match id {
+        1 => Ok(Digest::Sha1),
+        2 => Ok(Digest::Sha224),
+        3 => Ok(Digest::Sha256),
+        4 => Ok(Digest::Sha384),
+        5 => Ok(Digest::Sha512),
+        _ => Err(Error::InvalidStream("unknown digest")),
+    }
+}
+
+fn block_options(
+    oaep_digest: Digest,
+    mgf1_digest: Digest,
+    index: u64,
+    last: bool,
+) -> PaddingOptions {
+    let mut label = index.to_be_bytes().to_vec();
+    label.push(last as u8);
+
+    PaddingOptions {
+        mode: PaddingMode::Oaep,
+        oaep_digest,
+        mgf1_digest,
+        label: Some(label),
+    }
+}
+
+// fills `buffer` unless the reader ends first, returns how much was read
+fn read_full(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(filled)
+}
+
+/// Encrypts everything `reader` yields into a block stream, holding two
+/// chunks in memory at a time. Returns the number of blocks written.
+pub fn encrypt<K: PublicKey + ?Sized>(
+    key: &K,
+    mut reader: impl Read,
+    mut writer: impl Write,
+    oaep_digest: Digest,
+    mgf1_digest: Digest,
+) -> Result<u64, Error> {
+    let key_size = key.size();
+    let chunk_len = block_options(oaep_digest, mgf1_digest, 0, false).max_data_len(key_size);
+    if chunk_len == 0 {
+        return Err(Error::InvalidStream("key too small for the OAEP digest"));
+    }
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION, digest_id(oaep_digest), digest_id(mgf1_digest), 0])?;
+    writer.write_all(&(key_size as u16).to_be_bytes())?;
+
+    // one chunk of lookahead tells whether the current one is the last
+    let mut chunk = vec![0; chunk_len];
+    let mut next = vec![0; chunk_len];
+    let mut chunk_filled = read_full(&mut reader, &mut chunk)?;
+    let mut index = 0u64;
+
+    loop {
+        let next_filled = match chunk_filled {
+            filled if filled == chunk_len => read_full(&mut reader, &mut next)?,
+            _ => 0,
+        };
+        let last = next_filled == 0;
+
+        let options = block_options(oaep_digest, mgf1_digest, index, last);
+        let block = key.public_encrypt(&chunk[..chunk_filled], &options)?;
+        writer.write_all(&(block.len() as u16).to_be_bytes())?;
+        writer.write_all(&[last as u8])?;
+        writer.write_all(&block)?;
+        index += 1;
+
+        if last {
+            break;
+        }
+        std::mem::swap(&mut chunk, &mut next);
+        chunk_filled = next_filled;
+    }
+
+    writer.flush()?;
+    Ok(index)
+}
+
+/// Decrypts a block stream block by block into `writer`. The blocks are
+/// checked as they come, so a stream cut off early fails only after the
+/// blocks before the cut were written.
+pub fn decrypt<K: PrivateKey + ?Sized>(
+    key: &K,
+    mut reader: impl Read,
+    mut writer: impl Write,
+) -> Result<u64, Error> {
+    let mut header = [0; HEADER_LEN];
+    if read_full(&mut reader, &mut header)? < HEADER_LEN || &header[..4] != MAGIC {
+        return Err(Error::InvalidStream("missing header"));
+    }
+    if header[4] != VERSION {
+        return Err(Error::UnsupportedVersion(header[4]));
+    }
+
+    let oaep_digest = digest_from_id(header[5])?;
+    let mgf1_digest = digest_from_id(header[6])?;
+    let key_size = u16::from_be_bytes([header[8], header[9]]) as usize;
+    if key_size != key.size() {
+        return Err(Error::InvalidStream("encrypted for a key of another size"));
+    }
+
+    let mut block = vec![0; key_size];
+    let mut index = 0u64;
+
+    loop {
+        let mut frame = [0; 3];
+        if read_full(&mut reader, &mut frame)? < 3 {
+            return Err(Error::InvalidStream("truncated before the final block"));
+        }
+        if u16::from_be_bytes([frame[0], frame[1]]) as usize != key_size
+            || read_full(&mut reader, &mut block)? < key_size
+        {
+            return Err(Error::InvalidStream("truncated block"));
+        }
+        let last = match frame[2] {
+            0 => false,
+            1 => true,
+            _ => return Err(Error::InvalidStream("unknown final flag")),
+        };
+
+        // a block decrypts under the label of its own position only
+        let options = block_options(oaep_digest, mgf1_digest, index, last);
+        let chunk = key
+            .private_decrypt(&block, &options)
+            .map_err(|_| Error::Integrity { block: index })?;
+
+        writer.write_all(&chunk)?;
+        index += 1;
+
+        if last {
+            break;
+        }
+    }
+
+    if read_full(&mut reader, &mut [0])? != 0 {
+        return Err(Error::InvalidStream("data after the final block"));
+    }
+
+    writer.flush()?;
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::RsaPrivateKey;
+
+    #[test]
+    fn test_native_round_trip() {
+        let key = RsaPrivateKey::generate(1024).unwrap();
+        // 62 bytes per block with SHA-256
+        let data: Vec<u8> = (0..200u32).map(|i| (i * 7) as u8).collect();
+
+        let mut stream = Vec::new();
+        let blocks = encrypt(
+            &key.to_public_key(),
+            &data[..],
+            &mut stream,
+            Digest::Sha256,
+            Digest::Sha256,
+        )
+        .unwrap();
+        assert_eq!(blocks, 4);
+
+        let mut decrypted = Vec::new();
+        assert_eq!(decrypt(&key, &stream[..], &mut decrypted).unwrap(), blocks);
+        assert_eq!(decrypted, data);
+    }
+}
+
+#[cfg(all(test, feature = "openssl"))]
+mod openssl_tests {
+    use super::*;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_round_trip() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        // 190 bytes per block with SHA-256
+        for len in [0, 1, 190, 191, 1000] {
+            let data: Vec<u8> = (0..len as u32).map(|i| (i * 7) as u8).collect();
+
+            let mut stream = Vec::new();
+            let blocks =
+                encrypt(&key, &data[..], &mut stream, Digest::Sha256, Digest::Sha256).unwrap();
+            assert_eq!(blocks, (len as u64).div_ceil(190).max(1));
+            assert_eq!(stream.len(), HEADER_LEN + blocks as usize * (3 + 256));
+
+            let mut decrypted = Vec::new();
+            assert_eq!(decrypt(&key, &stream[..], &mut decrypted).unwrap(), blocks);
+            assert_eq!(decrypted, data);
+        }
+    }
+
+    #[test]
+    fn test_altered_streams_fail() {
+        let key = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        // 86 bytes per block with SHA-1
+        let data = vec![42u8; 250];
+        let mut stream = Vec::new();
+        encrypt(&key, &data[..], &mut stream, Digest::Sha1, Digest::Sha256).unwrap();
+        let block_len = 3 + 128;
+        assert_eq!(stream.len(), HEADER_LEN + 3 * block_len);
+
+        let decrypt_err = |stream: &[u8]| decrypt(&key, stream, io::sink()).unwrap_err();
+
+        // swapped blocks
+        let mut swapped = stream.clone();
+        let (first, second) = (HEADER_LEN, HEADER_LEN + block_len);
+        let block: Vec<u8> = swapped[first..second].to_vec();
+        swapped.copy_within(second..second + block_len, first);
+        swapped[second..second + block_len].copy_from_slice(&block);
+        assert!(matches!(
+            decrypt_err(&swapped),
+            Error::Integrity { block: 0 }
+        ));
+
+        // dropped final block, and the stream cut off in the middle of one
+        assert!(matches!(
+            decrypt_err(&stream[..HEADER_LEN + 2 * block_len]),
+            Error::InvalidStream(_)
+        ));
+        assert!(matches!(
+            decrypt_err(&stream[..stream.len() - 1]),
+            Error::InvalidStream(_)
+        ));
+
+        // a forged final flag, on the first block or the last one
+        let mut flagged = stream.clone();
+        flagged[HEADER_LEN + 2] = 1;
+        assert!(matches!(
+            decrypt_err(&flagged),
+            Error::Integrity { block: 0 }
+        ));
+        let mut flagged = stream.clone();
+        flagged[HEADER_LEN + 2 * block_len + 2] = 0;
+        assert!(matches!(
+            decrypt_err(&flagged),
+            Error::Integrity { block: 2 }
+        ));
+
+        let mut extended = stream.clone();
+        extended.push(0);
+        assert!(matches!(decrypt_err(&extended), Error::InvalidStream(_)));
+
+        let other = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        assert!(decrypt(&other, &stream[..], io::sink()).is_err());
+    }
+
+    #[test]
+    fn test_backends_share_the_format() {
+        let key = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+        let native =
+            rsa_core::RsaPrivateKey::from_pkcs8_der(&key.private_key_to_pkcs8().unwrap()).unwrap();
+        let data = vec![7u8; 300];
+
+        let mut stream = Vec::new();
+        encrypt(&key, &data[..], &mut stream, Digest::Sha256, Digest::Sha1).unwrap();
+        let mut decrypted = Vec::new();
+        decrypt(&native, &stream[..], &mut decrypted).unwrap();
+        assert_eq!(decrypted, data);
+    }
+}
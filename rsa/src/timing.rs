@@ -0,0 +1,229 @@
+// Timing side-channel measurements of the RSA operations with the dudect
+// harness, along with a padding check that leaks on purpose to show what a
+// detected leak looks like.
+use crate::cipher::{self, PaddingOptions};
+use crate::signature::{self, SaltLength, SignatureOptions, SignaturePadding};
+use crate::Digest;
+use clap::ValueEnum;
+use openssl::pkey::{PKey, Private};
+use rsa_attacks::dudect::{Class, Dudect};
+pub use rsa_attacks::dudect::{Status, Verdict};
+use rsa_attacks::Error;
+use rsa_core::prime::random_bytes;
+use rsa_core::{padding, EncryptionPadding, HashAlgorithm, RsaPrivateKey};
+
+const MESSAGE_LEN: usize = 32;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    /// PKCS#1 v1.5 decryption, one fixed cypher against cyphers of random messages
+    Decrypt,
+    /// rsa_core's PKCS#1 v1.5 padding check, a block failing at its end against random blocks
+    Padding,
+    /// PKCS#1 v1.5 SHA-256 signature verification, the valid signature against random ones
+    Verify,
+    /// Padding check returning at the first bad byte, on the same blocks as `padding`
+    Leaky,
+}
+
+/// PKCS#1 v1.5 padding check the way it must not be written: it gives up at
+/// the first byte that rules the block out, so its time tells how far a block
+/// got. Reference for the `leaky` target.
+pub fn leaky_unpad(em: &[u8]) -> Option<&[u8]> {
+    if em[0] != 0 || em[1] != 2 {
+        return None;
+    }
+
+    let separator = em[2..].iter().position(|&b| b == 0)? + 2;
+    if separator < 10 {
+        return None;
+    }
+
+    Some(&em[separator + 1..])
+}
+
+// 00 02 and no separator: only a check reading the whole block rejects it
+fn unseparated_block(size: usize) -> Vec<u8> {
+    let mut block = vec![0x42; size];
+    block[..2].copy_from_slice(&[0, 2]);
+    block
+}
+
+fn encrypt(key: &PKey<Private>, message: &[u8]) -> Result<Vec<u8>, Error> {
+    cipher::public_encrypt(key, message, &PaddingOptions::default())
+        .map_err(|_| Error::InvalidInput("key too small to encrypt the measured message"))
+}
+
+fn native_key(key: &PKey<Private>) -> Result<RsaPrivateKey, Error> {
+    let der = key
+        .private_key_to_pkcs8()
+        .map_err(|_| Error::InvalidInput("unserializable private key"))?;
+
+    Ok(RsaPrivateKey::from_pkcs8_der(&der)?)
+}
+
+fn run_batches<I, R>(
+    batch_size: usize,
+    batches: usize,
+    mut prepare: impl FnMut(Class) -> Result<I, Error>,
+    mut run: impl FnMut(&I) -> R,
+    mut report: impl FnMut(&Status),
+) -> Result<Status, Error> {
+    let mut dudect = Dudect::new();
+    // calibration
+    dudect.batch(batch_size, &mut prepare, &mut run)?;
+
+    let mut status = dudect.status();
+    for _ in 0..batches {
+        status = dudect.batch(batch_size, &mut prepare, &mut run)?;
+        report(&status);
+
+        if status.verdict() == Verdict::DefiniteLeak {
+            break;
+        }
+    }
+
+    Ok(status)
+}
+
+/// Measures `target` with `key`, on the rsa_core backend if `native`, over
+/// up to `batches` batches after a calibration one. `report` gets the status
+/// after every batch. Stops early once a leak is certain.
+pub fn measure(
+    target: Target,
+    key: &PKey<Private>,
+    native: bool,
+    batch_size: usize,
+    batches: usize,
+    report: impl FnMut(&Status),
+) -> Result<Status, Error> {
+    let size = key.size();
+    let fixed_message = [0x5a; MESSAGE_LEN];
+
+    match target {
+        Target::Decrypt => {
+            let fixed = encrypt(key, &fixed_message)?;
+            let prepare = |class| match class {
+                Class::Fixed => Ok(fixed.clone()),
+                Class::Random => encrypt(key, &random_bytes(MESSAGE_LEN)?),
+            };
+
+            if native {
+                let native_key = native_key(key)?;
+                run_batches(
+                    batch_size,
+                    batches,
+                    prepare,
+                    |cypher| native_key.decrypt(cypher, &EncryptionPadding::Pkcs1v15),
+                    report,
+                )
+            } else {
+                let options = PaddingOptions::default();
+                run_batches(
+                    batch_size,
+                    batches,
+                    prepare,
+                    |cypher| cipher::private_decrypt(key, cypher, &options),
+                    report,
+                )
+            }
+        }
+        Target::Padding | Target::Leaky => {
+            let fixed = unseparated_block(size);
+            let prepare = |class| match class {
+                Class::Fixed => Ok(fixed.clone()),
+                Class::Random => Ok(random_bytes(size)?),
+            };
+
+            if target == Target::Leaky {
+                run_batches(
+                    batch_size,
+                    batches,
+                    prepare,
+                    |em| leaky_unpad(em).is_some(),
+                    report,
+                )
+            } else {
+                run_batches(
+                    batch_size,
+                    batches,
+                    prepare,
+                    |em| padding::pkcs1v15_encrypt_unpad(em),
+                    report,
+                )
+            }
+        }
+        Target::Verify => {
+            let options = SignatureOptions {
+                padding: SignaturePadding::Pkcs1,
+                digest: Digest::Sha256,
+                salt_length: SaltLength::Digest,
+            };
+            let fixed = signature::sign(key, &fixed_message[..], &options)
+                .map_err(|_| Error::InvalidInput("key can't sign the measured message"))?;
+            // random values below the modulus
+            let prepare = |class| match class {
+                Class::Fixed => Ok(fixed.clone()),
+                Class::Random => {
+                    let mut signature = random_bytes(size)?;
+                    signature[0] = 0;
+                    Ok(signature)
+                }
+            };
+
+            if native {
+                let public_key = native_key(key)?.to_public_key();
+                let hash = HashAlgorithm::Sha256;
+                let digest = hash.digest(&fixed_message);
+                run_batches(
+                    batch_size,
+                    batches,
+                    prepare,
+                    |signature| {
+                        public_key.verify_digest(
+                            hash,
+                            &digest,
+                            signature,
+                            rsa_core::SignaturePadding::Pkcs1v15,
+                        )
+                    },
+                    report,
+                )
+            } else {
+                run_batches(
+                    batch_size,
+                    batches,
+                    prepare,
+                    |signature| signature::verify(key, &fixed_message[..], signature, &options),
+                    report,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn test_leaky_padding_check_is_flagged() {
+        let key = PKey::from_rsa(Rsa::generate(1024).unwrap()).unwrap();
+
+        let block = padding::pkcs1v15_encrypt_pad(b"message", 128).unwrap();
+        assert_eq!(leaky_unpad(&block), Some(&b"message"[..]));
+        assert_eq!(leaky_unpad(&unseparated_block(128)), None);
+
+        let mut reports = 0;
+        let status = measure(Target::Leaky, &key, false, 10_000, 20, |_| reports += 1).unwrap();
+        assert_eq!(status.verdict(), Verdict::DefiniteLeak);
+        assert_eq!(status.measurements, reports * 10_000);
+
+        // the real targets run, whatever they show on a loaded test machine
+        for (target, native) in [(Target::Decrypt, false), (Target::Verify, true)] {
+            let status = measure(target, &key, native, 100, 1, |_| {}).unwrap();
+            assert_eq!(status.measurements, 100);
+        }
+    }
+}
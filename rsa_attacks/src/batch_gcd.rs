@@ -0,0 +1,105 @@
+// Bernstein's batch GCD as used by Heninger et al. in "Mining your Ps and Qs":
+// a product tree of all moduli and a remainder tree of P mod n^2 give
+// gcd(n, P / n) for every modulus in quasi-linear time.
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::One;
+
+fn product_tree(moduli: &[BigUint]) -> Vec<Vec<BigUint>> {
+    let mut levels = vec![moduli.to_vec()];
+
+    while levels.last().unwrap().len() > 1 {
+        let level = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| pair.iter().product())
+            .collect();
+        levels.push(level);
+    }
+
+    levels
+}
+
+/// gcd of every modulus with the product of all the others. One means no shared
+/// prime, the modulus itself means both of its primes are shared (or it is
+/// duplicated), anything else is a prime factor.
+pub fn shared_factors(moduli: &[BigUint]) -> Vec<BigUint> {
+    if moduli.is_empty() {
+        return Vec::new();
+    }
+
+    let levels = product_tree(moduli);
+    let mut remainders = levels.last().unwrap().clone();
+
+    for level in levels.iter().rev().skip(1) {
+        remainders = level
+            .iter()
+            .enumerate()
+            .map(|(i, node)| &remainders[i / 2] % (node * node))
+            .collect();
+    }
+
+    remainders
+        .iter()
+        .zip(moduli)
+        .map(|(remainder, n)| (remainder / n).gcd(n))
+        .collect()
+}
+
+/// Pairs of indices of moduli sharing a prime, with the prime. Resolves the
+/// moduli whose both primes are shared by checking them pairwise.
+pub fn shared_primes(moduli: &[BigUint]) -> Vec<(usize, usize, BigUint)> {
+    let weak: Vec<usize> = shared_factors(moduli)
+        .iter()
+        .enumerate()
+        .filter(|(_, factor)| !factor.is_one())
+        .map(|(i, _)| i)
+        .collect();
+    let mut pairs = Vec::new();
+
+    for (position, &i) in weak.iter().enumerate() {
+        for &j in &weak[position + 1..] {
+            let gcd = moduli[i].gcd(&moduli[j]);
+
+            if !gcd.is_one() {
+                pairs.push((i, j, gcd));
+            }
+        }
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::prime::generate_prime;
+
+    #[test]
+    fn test_shared_primes_are_found() {
+        let primes: Vec<BigUint> = (0..8)
+            .map(|_| generate_prime(256, |_| true).unwrap())
+            .collect();
+        let shared = &primes[0];
+        let moduli = vec![
+            shared * &primes[1],
+            &primes[2] * &primes[3],
+            shared * &primes[4],
+            &primes[5] * &primes[6],
+            &primes[6] * &primes[7],
+        ];
+
+        let factors = shared_factors(&moduli);
+        assert_eq!(factors[0], *shared);
+        assert!(factors[1].is_one());
+        assert_eq!(factors[2], *shared);
+        assert_eq!(factors[3], primes[6]);
+        assert_eq!(factors[4], primes[6]);
+
+        assert_eq!(
+            shared_primes(&moduli),
+            vec![(0, 2, shared.clone()), (3, 4, primes[6].clone())]
+        );
+    }
+}
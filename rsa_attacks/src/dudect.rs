@@ -0,0 +1,291 @@
+// Timing leak detection after dudect ("Dude, is my code constant time?",
+// Reparaz, Balasch and Verbauwhede, DATE 2017): an operation is timed on two
+// classes of inputs, one fixed input and random ones, interleaved at random,
+// and Welch's t-test tells whether the two timing distributions differ. The
+// test also runs on timings cropped at a range of percentiles, since the long
+// upper tail is mostly interrupts and scheduling, and on squared deviations
+// from the class mean, which catch leaks showing only in the variance.
+use crate::Error;
+use rsa_core::prime::random_bytes;
+use std::fmt;
+use std::hint::black_box;
+
+/// |t| above which the timing probably depends on the input.
+pub const PROBABLE_LEAK: f64 = 4.5;
+/// |t| above which it certainly does.
+pub const DEFINITE_LEAK: f64 = 10.0;
+
+// tests with fewer samples aren't trusted yet
+const ENOUGH_MEASUREMENTS: f64 = 1000.0;
+const PERCENTILES: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Class {
+    Fixed,
+    Random,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+    NoLeakFound,
+    ProbableLeak,
+    DefiniteLeak,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::NoLeakFound => write!(f, "no leak found so far"),
+            Verdict::ProbableLeak => write!(f, "probably not constant time"),
+            Verdict::DefiniteLeak => write!(f, "definitely not constant time"),
+        }
+    }
+}
+
+/// Where the measurement stands after a batch.
+#[derive(Clone, Copy, Debug)]
+pub struct Status {
+    /// Measurements fed to the tests, the calibration batch isn't counted
+    pub measurements: u64,
+    /// Largest |t| among the tests with enough samples
+    pub max_t: f64,
+}
+
+impl Status {
+    pub fn verdict(&self) -> Verdict {
+        match self.max_t {
+            t if t > DEFINITE_LEAK => Verdict::DefiniteLeak,
+            t if t > PROBABLE_LEAK => Verdict::ProbableLeak,
+            _ => Verdict::NoLeakFound,
+        }
+    }
+}
+
+// Welch's t-test over running means and variances (Welford's method)
+#[derive(Clone, Default)]
+struct TTest {
+    n: [f64; 2],
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl TTest {
+    fn push(&mut self, x: f64, class: Class) {
+        let i = class as usize;
+        self.n[i] += 1.0;
+        let delta = x - self.mean[i];
+        self.mean[i] += delta / self.n[i];
+        self.m2[i] += delta * (x - self.mean[i]);
+    }
+
+    fn samples(&self) -> f64 {
+        self.n[0] + self.n[1]
+    }
+
+    fn t(&self) -> f64 {
+        if self.n[0] < 2.0 || self.n[1] < 2.0 {
+            return 0.0;
+        }
+
+        let variance = |i: usize| self.m2[i] / (self.n[i] - 1.0);
+        let t = (self.mean[0] - self.mean[1])
+            / (variance(0) / self.n[0] + variance(1) / self.n[1]).sqrt();
+
+        // identical constant timings in both classes
+        if t.is_nan() {
+            0.0
+        } else {
+            t
+        }
+    }
+}
+
+/// Cycle counter on x86_64, nanoseconds elsewhere.
+pub fn cycles() -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::arch::x86_64::{_mm_lfence, _rdtsc};
+
+        // SAFETY: every x86_64 CPU has RDTSC and LFENCE, the fences keep the
+        // read from being reordered with the measured code
+        unsafe {
+            _mm_lfence();
+            let cycles = _rdtsc();
+            _mm_lfence();
+            cycles
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_nanos() as u64
+    }
+}
+
+/// Measurement state carried between batches.
+pub struct Dudect {
+    // raw timings, timings cropped at each percentile, then second order
+    tests: Vec<TTest>,
+    thresholds: Vec<u64>,
+    measurements: u64,
+}
+
+impl Default for Dudect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dudect {
+    pub fn new() -> Self {
+        Dudect {
+            tests: vec![TTest::default(); PERCENTILES + 2],
+            thresholds: Vec::new(),
+            measurements: 0,
+        }
+    }
+
+    /// Times `run` on `size` inputs of randomly chosen classes. The inputs are
+    /// all made by `prepare` before the clock starts. The first batch only
+    /// sets the cropping percentiles.
+    pub fn batch<I, R>(
+        &mut self,
+        size: usize,
+        mut prepare: impl FnMut(Class) -> Result<I, Error>,
+        mut run: impl FnMut(&I) -> R,
+    ) -> Result<Status, Error> {
+        if size == 0 {
+            return Err(Error::InvalidInput("batch size must be positive"));
+        }
+
+        let classes: Vec<Class> = random_bytes(size)?
+            .into_iter()
+            .map(|coin| match coin & 1 {
+                0 => Class::Fixed,
+                _ => Class::Random,
+            })
+            .collect();
+        let inputs = classes
+            .iter()
+            .map(|&class| prepare(class))
+            .collect::<Result<Vec<I>, Error>>()?;
+
+        let timings: Vec<u64> = inputs
+            .iter()
+            .map(|input| {
+                let start = cycles();
+                black_box(run(black_box(input)));
+                cycles().wrapping_sub(start)
+            })
+            .collect();
+
+        if self.thresholds.is_empty() {
+            self.calibrate(&timings);
+        } else {
+            self.update(&classes, &timings);
+        }
+
+        Ok(self.status())
+    }
+
+    // dudect's crop points, denser towards the top of the distribution
+    fn calibrate(&mut self, timings: &[u64]) {
+        let mut sorted = timings.to_vec();
+        sorted.sort_unstable();
+
+        self.thresholds = (0..PERCENTILES)
+            .map(|i| {
+                let p = 1.0 - 0.5f64.powf(10.0 * (i + 1) as f64 / PERCENTILES as f64);
+                sorted[(p * sorted.len() as f64) as usize]
+            })
+            .collect();
+    }
+
+    fn update(&mut self, classes: &[Class], timings: &[u64]) {
+        for (&class, &time) in classes.iter().zip(timings) {
+            let x = time as f64;
+
+            // second order once the class means have settled
+            if self.tests[0].samples() > ENOUGH_MEASUREMENTS {
+                let centered = x - self.tests[0].mean[class as usize];
+                self.tests[PERCENTILES + 1].push(centered * centered, class);
+            }
+
+            self.tests[0].push(x, class);
+            for (test, &threshold) in self.tests[1..=PERCENTILES].iter_mut().zip(&self.thresholds) {
+                if time < threshold {
+                    test.push(x, class);
+                }
+            }
+
+            self.measurements += 1;
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        let max_t = self
+            .tests
+            .iter()
+            .filter(|test| test.samples() >= ENOUGH_MEASUREMENTS)
+            .map(|test| test.t().abs())
+            .fold(0.0, f64::max);
+
+        Status {
+            measurements: self.measurements,
+            max_t,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_welch_t() {
+        let mut test = TTest::default();
+        for x in [1.0, 2.0, 3.0, 4.0] {
+            test.push(x, Class::Fixed);
+            test.push(x + 1.0, Class::Random);
+        }
+
+        // means 2.5 and 3.5, both variances 5/3
+        let expected = -1.0 / (2.0 * 5.0 / 3.0 / 4.0f64).sqrt();
+        assert!((test.t() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_early_exit_comparison_is_flagged() {
+        let secret = vec![0x5a; 512];
+        // returns at the first differing byte
+        let leaky_eq = |data: &Vec<u8>| {
+            for (a, b) in data.iter().zip(&secret) {
+                if a != b {
+                    return false;
+                }
+            }
+            true
+        };
+        let prepare = |class| match class {
+            Class::Fixed => Ok(secret.clone()),
+            Class::Random => Ok(random_bytes(secret.len())?),
+        };
+
+        let mut dudect = Dudect::new();
+        let mut status = dudect.batch(10_000, prepare, leaky_eq).unwrap();
+        assert_eq!(status.measurements, 0);
+
+        for _ in 0..20 {
+            status = dudect.batch(10_000, prepare, leaky_eq).unwrap();
+            if status.verdict() == Verdict::DefiniteLeak {
+                break;
+            }
+        }
+        assert_eq!(status.verdict(), Verdict::DefiniteLeak);
+        assert!(dudect.batch(0, prepare, leaky_eq).is_err());
+    }
+}
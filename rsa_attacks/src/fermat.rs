@@ -0,0 +1,69 @@
+// Fermat factoring: n = a^2 - b^2 = (a - b)(a + b) is found after few steps
+// when p and q are close, as a starts at sqrt(n) which is near (p + q) / 2.
+use crate::{private_key_from_factor, Error};
+use num_bigint::BigUint;
+use num_traits::One;
+use rsa_core::RsaPrivateKey;
+
+/// Factors `n` into (p, q) with p >= q, trying at most `iterations` values of a.
+pub fn factor(n: &BigUint, iterations: u64) -> Option<(BigUint, BigUint)> {
+    let mut a = n.sqrt();
+    if &a * &a < *n {
+        a += 1u8;
+    }
+
+    // b^2 = a^2 - n, kept up to date as a grows by one
+    let mut b_square = &a * &a - n;
+    for _ in 0..iterations {
+        let b = b_square.sqrt();
+
+        if &b * &b == b_square {
+            let q = &a - &b;
+            return (!q.is_one()).then(|| (&a + &b, q));
+        }
+
+        b_square += &a * 2u8 + 1u8;
+        a += 1u8;
+    }
+
+    None
+}
+
+/// Recovers the private key when the primes are close to each other.
+pub fn attack(n: &BigUint, e: &BigUint, iterations: u64) -> Result<RsaPrivateKey, Error> {
+    let (p, _) = factor(n, iterations).ok_or(Error::NotVulnerable)?;
+
+    private_key_from_factor(n, e, &p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_integer::Integer;
+    use rsa_core::prime::{generate_prime, is_probable_prime};
+
+    #[test]
+    fn test_close_primes() {
+        let e = BigUint::from(65537u32);
+        let coprime = |p: &BigUint| (p - 1u8).gcd(&e).is_one();
+        let p = generate_prime(512, coprime).unwrap();
+        // next suitable prime a little above p
+        let mut q = &p + (BigUint::one() << 64);
+        while !(coprime(&q) && is_probable_prime(&q, 4).unwrap()) {
+            q += 2u8;
+        }
+        let n = &p * &q;
+
+        assert_eq!(factor(&n, 1000), Some((q.clone(), p.clone())));
+        let key = attack(&n, &e, 1000).unwrap();
+        assert_eq!(key.to_public_key().n(), &n);
+
+        let far = rsa_core::RsaPrivateKey::generate(1024)
+            .unwrap()
+            .to_public_key();
+        assert_eq!(
+            attack(far.n(), far.e(), 1000).unwrap_err(),
+            Error::NotVulnerable
+        );
+    }
+}
@@ -0,0 +1,97 @@
+// Håstad's broadcast attack: the same message encrypted without padding under
+// e keys with exponent e. CRT gives m^e modulo n1 * ... * ne, and since m^e is
+// smaller than that product it is the plain integer power.
+use crate::Error;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+/// x with x = residue (mod modulus) for every pair, the moduli must be pairwise coprime.
+pub fn crt(congruences: &[(BigUint, BigUint)]) -> Result<(BigUint, BigUint), Error> {
+    let mut x = BigUint::zero();
+    let mut product = BigUint::one();
+
+    for (residue, modulus) in congruences {
+        // x + product * t = residue (mod modulus)
+        let inverse = (&product % modulus)
+            .modinv(modulus)
+            .ok_or(Error::InvalidInput("moduli aren't pairwise coprime"))?;
+        let difference = (residue + modulus - &x % modulus) % modulus;
+        let t = difference * inverse % modulus;
+        x += &product * t;
+        product *= modulus;
+    }
+
+    Ok((x, product))
+}
+
+/// Recovers the message from `(cypher, modulus)` pairs encrypted with the
+/// small exponent `e`, at least `e` pairs are needed.
+pub fn attack(e: u32, cyphers: &[(BigUint, BigUint)]) -> Result<BigUint, Error> {
+    if e < 2 || cyphers.len() < e as usize {
+        return Err(Error::InvalidInput(
+            "need at least e cyphers of the same message",
+        ));
+    }
+
+    let (power, _) = crt(&cyphers[..e as usize])?;
+    let m = power.nth_root(e);
+
+    if m.pow(e) != power {
+        return Err(Error::NotVulnerable);
+    }
+    if cyphers
+        .iter()
+        .any(|(c, n)| m.modpow(&BigUint::from(e), n) != *c)
+    {
+        return Err(Error::NotVulnerable);
+    }
+
+    Ok(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::prime::random_range;
+    use rsa_core::RsaPrivateKey;
+
+    #[test]
+    fn test_crt() {
+        let congruences: Vec<(BigUint, BigUint)> = [(2u32, 3u32), (3, 5), (2, 7)]
+            .iter()
+            .map(|&(r, m)| (BigUint::from(r), BigUint::from(m)))
+            .collect();
+
+        assert_eq!(crt(&congruences).unwrap().0, BigUint::from(23u8));
+        assert!(crt(&[
+            (BigUint::from(1u8), BigUint::from(6u8)),
+            (BigUint::from(1u8), BigUint::from(4u8)),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_broadcast_with_exponent_three() {
+        let e = BigUint::from(3u8);
+        let keys: Vec<_> = (0..3)
+            .map(|_| RsaPrivateKey::generate_with_exponent(1024, &e).unwrap())
+            .map(|key| key.to_public_key())
+            .collect();
+
+        // full size message, only m < n for every key is required
+        let smallest = keys.iter().map(|key| key.n()).min().unwrap();
+        let m = random_range(&BigUint::one(), smallest).unwrap();
+        let cyphers: Vec<_> = keys
+            .iter()
+            .map(|key| (m.modpow(&e, key.n()), key.n().clone()))
+            .collect();
+
+        assert_eq!(attack(3, &cyphers).unwrap(), m);
+        assert!(attack(3, &cyphers[..2]).is_err());
+
+        // a different message under one of the keys
+        let mut mixed = cyphers.clone();
+        mixed[2].0 = (&m + 1u8).modpow(&e, &mixed[2].1);
+        assert_eq!(attack(3, &mixed).unwrap_err(), Error::NotVulnerable);
+    }
+}
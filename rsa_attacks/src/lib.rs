@@ -0,0 +1,66 @@
+// Classic attacks on textbook RSA and badly generated keys, for teaching why
+// padding and careful key generation matter.
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rsa_core::RsaPrivateKey;
+use std::fmt;
+
+pub mod batch_gcd;
+pub mod bleichenbacher;
+pub mod common_modulus;
+pub mod dudect;
+pub mod fermat;
+pub mod hastad;
+pub mod wiener;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The attack's precondition doesn't hold for these keys
+    NotVulnerable,
+    InvalidInput(&'static str),
+    Key(rsa_core::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotVulnerable => write!(f, "Keys aren't vulnerable to this attack"),
+            Error::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+            Error::Key(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rsa_core::Error> for Error {
+    fn from(e: rsa_core::Error) -> Self {
+        Error::Key(e)
+    }
+}
+
+/// Rebuilds the private key once one prime factor `p` of `n` is known.
+pub fn private_key_from_factor(
+    n: &BigUint,
+    e: &BigUint,
+    p: &BigUint,
+) -> Result<RsaPrivateKey, Error> {
+    let (q, rest) = n.div_rem(p);
+    if !rest.is_zero() || p.is_one() || q.is_one() {
+        return Err(Error::InvalidInput("not a factor of the modulus"));
+    }
+
+    let lambda = (p - 1u8).lcm(&(&q - 1u8));
+    let d = e
+        .modinv(&lambda)
+        .ok_or(Error::InvalidInput("public exponent isn't invertible"))?;
+
+    Ok(RsaPrivateKey::from_components(
+        n.clone(),
+        e.clone(),
+        d,
+        p.clone(),
+        q,
+    )?)
+}
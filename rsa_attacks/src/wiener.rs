@@ -0,0 +1,113 @@
+// Wiener's attack: d < n^(1/4) / 3 shows up as a convergent k/d of the
+// continued fraction of e/n, since e/n is very close to k/d.
+use crate::{private_key_from_factor, Error};
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rsa_core::RsaPrivateKey;
+
+/// Convergents h/k of the continued fraction of `numerator / denominator`.
+pub fn convergents(numerator: &BigUint, denominator: &BigUint) -> Vec<(BigUint, BigUint)> {
+    let mut result = Vec::new();
+    let (mut a, mut b) = (numerator.clone(), denominator.clone());
+    // h(-1) = 1, h(-2) = 0, k(-1) = 0, k(-2) = 1
+    let (mut h, mut h_prev) = (BigUint::one(), BigUint::zero());
+    let (mut k, mut k_prev) = (BigUint::zero(), BigUint::one());
+
+    while !b.is_zero() {
+        let (quotient, remainder) = a.div_rem(&b);
+        let next_h = &quotient * &h + &h_prev;
+        let next_k = &quotient * &k + &k_prev;
+        h_prev = std::mem::replace(&mut h, next_h);
+        k_prev = std::mem::replace(&mut k, next_k);
+        result.push((h.clone(), k.clone()));
+
+        a = std::mem::replace(&mut b, remainder);
+    }
+
+    result
+}
+
+/// Recovers the private key when the private exponent is small.
+pub fn attack(n: &BigUint, e: &BigUint) -> Result<RsaPrivateKey, Error> {
+    for (k, d) in convergents(e, n) {
+        if k.is_zero() || d.is_even() {
+            continue;
+        }
+
+        // e * d = 1 + k * phi
+        let (phi, rest) = (e * &d - 1u8).div_rem(&k);
+        if !rest.is_zero() || phi >= *n {
+            continue;
+        }
+
+        // p and q are the roots of x^2 - (n - phi + 1) x + n
+        let sum = n - &phi + 1u8;
+        let square = &sum * &sum;
+        if square < n * 4u8 {
+            continue;
+        }
+
+        let discriminant = square - n * 4u8;
+        let root = discriminant.sqrt();
+        if &root * &root != discriminant {
+            continue;
+        }
+
+        let p = (&sum + &root) >> 1;
+        if &p * (&sum - &p) == *n {
+            return private_key_from_factor(n, e, &p);
+        }
+    }
+
+    Err(Error::NotVulnerable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa_core::prime::{generate_prime, random_range};
+
+    #[test]
+    fn test_convergents() {
+        // 649 / 200 = [3; 4, 12, 4]
+        let expected: Vec<(BigUint, BigUint)> = [(3u32, 1u32), (13, 4), (159, 49), (649, 200)]
+            .iter()
+            .map(|&(h, k)| (BigUint::from(h), BigUint::from(k)))
+            .collect();
+
+        assert_eq!(
+            convergents(&BigUint::from(649u32), &BigUint::from(200u32)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_small_private_exponent() {
+        let p = generate_prime(512, |_| true).unwrap();
+        let q = generate_prime(512, |_| true).unwrap();
+        let n = &p * &q;
+        let phi = (&p - 1u8) * (&q - 1u8);
+
+        // d well below n^(1/4) / 3
+        let d = loop {
+            let d = random_range(&(BigUint::one() << 200), &(BigUint::one() << 250)).unwrap();
+            if d.gcd(&phi).is_one() {
+                break d;
+            }
+        };
+        let e = d.modinv(&phi).unwrap();
+
+        let key = attack(&n, &e).unwrap();
+        assert_eq!(key.primes().0 * key.primes().1, n);
+
+        let m = BigUint::from(0xc0ffeeu32);
+        assert_eq!(m.modpow(&e, &n).modpow(key.d(), &n), m);
+
+        let safe = RsaPrivateKey::generate(1024).unwrap().to_public_key();
+        assert_eq!(
+            attack(safe.n(), safe.e()).unwrap_err(),
+            Error::NotVulnerable
+        );
+    }
+}
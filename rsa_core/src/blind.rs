@@ -0,0 +1,511 @@
+// RSA blind signatures (RFC 9474, RSABSSA): the client blinds a PSS encoded
+// message with a random factor r, the server signs it without learning the
+// message, and the client unblinds the result into a plain RSASSA-PSS signature.
+use crate::hash::HashAlgorithm;
+use crate::key::{RsaPrivateKey, RsaPublicKey};
+use crate::padding::{self, EncryptionPadding, SignaturePadding};
+use crate::prime::{random_bytes, random_range};
+use crate::Error;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::One;
+
+const HASH: HashAlgorithm = HashAlgorithm::Sha384;
+
+/// Length of the random prefix of the randomized variants.
+pub const PREFIX_LEN: usize = 32;
+
+/// RSABSSA-SHA384 variants from RFC 9474 section 5.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variant {
+    /// RSABSSA-SHA384-PSS-Randomized
+    PssRandomized,
+    /// RSABSSA-SHA384-PSSZERO-Randomized
+    PssZeroRandomized,
+    /// RSABSSA-SHA384-PSS-Deterministic
+    PssDeterministic,
+    /// RSABSSA-SHA384-PSSZERO-Deterministic
+    PssZeroDeterministic,
+}
+
+impl Variant {
+    pub fn salt_len(self) -> usize {
+        match self {
+            Variant::PssRandomized | Variant::PssDeterministic => HASH.size(),
+            Variant::PssZeroRandomized | Variant::PssZeroDeterministic => 0,
+        }
+    }
+
+    pub fn is_randomized(self) -> bool {
+        matches!(self, Variant::PssRandomized | Variant::PssZeroRandomized)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::PssRandomized => "RSABSSA-SHA384-PSS-Randomized",
+            Variant::PssZeroRandomized => "RSABSSA-SHA384-PSSZERO-Randomized",
+            Variant::PssDeterministic => "RSABSSA-SHA384-PSS-Deterministic",
+            Variant::PssZeroDeterministic => "RSABSSA-SHA384-PSSZERO-Deterministic",
+        }
+    }
+}
+
+/// Output of `blind`: the message for the server and the inverse the client
+/// keeps to unblind its answer, both as long as the modulus.
+#[derive(Debug)]
+pub struct Blinded {
+    pub blinded_msg: Vec<u8>,
+    pub inv: Vec<u8>,
+}
+
+fn to_padded_bytes(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut out = vec![0u8; len - bytes.len()];
+    out.extend(bytes);
+    out
+}
+
+/// Prepare step, prefixes the message with 32 random bytes for the
+/// randomized variants. The result is what gets blinded, finalized and verified.
+pub fn prepare(variant: Variant, msg: &[u8]) -> Result<Vec<u8>, Error> {
+    if !variant.is_randomized() {
+        return Ok(msg.to_vec());
+    }
+
+    let mut prepared = random_bytes(PREFIX_LEN)?;
+    prepared.extend_from_slice(msg);
+    Ok(prepared)
+}
+
+/// Blinds a prepared message for `key` with a fresh salt and blinding factor.
+pub fn blind(key: &RsaPublicKey, variant: Variant, msg: &[u8]) -> Result<Blinded, Error> {
+    let salt = random_bytes(variant.salt_len())?;
+    let r = random_range(&BigUint::one(), key.n())?;
+
+    blind_with(key, variant, msg, &salt, &r)
+}
+
+/// `blind` with the salt and blinding factor `r` chosen by the caller, for
+/// known answer tests. Never reuse `r`, it's all that hides the message.
+pub fn blind_with(
+    key: &RsaPublicKey,
+    variant: Variant,
+    msg: &[u8],
+    salt: &[u8],
+    r: &BigUint,
+) -> Result<Blinded, Error> {
+    if salt.len() != variant.salt_len() {
+        return Err(Error::InvalidInput("salt length doesn't match the variant"));
+    }
+
+    let n = key.n();
+    let encoded = padding::pss_encode_with_salt(&HASH.digest(msg), key.bits() - 1, HASH, salt)?;
+    let m = BigUint::from_bytes_be(&encoded);
+
+    if !m.gcd(n).is_one() {
+        return Err(Error::InvalidInput(
+            "encoded message isn't coprime to the modulus",
+        ));
+    }
+    let inv = r
+        .modinv(n)
+        .ok_or(Error::InvalidInput("blinding factor isn't invertible"))?;
+
+    let z = m * r.modpow(key.e(), n) % n;
+
+    Ok(Blinded {
+        blinded_msg: to_padded_bytes(&z, key.size()),
+        inv: to_padded_bytes(&inv, key.size()),
+    })
+}
+
+/// Server side, the raw RSA signature of a blinded message. The result is
+/// checked against the public key before it's released.
+pub fn blind_sign(key: &RsaPrivateKey, blinded_msg: &[u8]) -> Result<Vec<u8>, Error> {
+    if blinded_msg.len() != key.size() {
+        return Err(Error::InvalidInput(
+            "blinded message length doesn't match the key",
+        ));
+    }
+
+    key.private_encrypt(blinded_msg, &EncryptionPadding::None)
+}
+
+/// Unblinds the server's answer into an RSASSA-PSS signature of `msg`,
+/// which is verified before it's returned.
+pub fn finalize(
+    key: &RsaPublicKey,
+    variant: Variant,
+    msg: &[u8],
+    blind_sig: &[u8],
+    inv: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if blind_sig.len() != key.size() || inv.len() != key.size() {
+        return Err(Error::InvalidInput(
+            "blind signature length doesn't match the key",
+        ));
+    }
+
+    let n = key.n();
+    let z = BigUint::from_bytes_be(blind_sig);
+    let s = z * BigUint::from_bytes_be(inv) % n;
+    let signature = to_padded_bytes(&s, key.size());
+
+    verify(key, variant, msg, &signature)?;
+    Ok(signature)
+}
+
+/// Verifies a finalized signature over the prepared message, a standard
+/// RSASSA-PSS verification with SHA-384 and MGF1-SHA-384.
+pub fn verify(
+    key: &RsaPublicKey,
+    variant: Variant,
+    msg: &[u8],
+    signature: &[u8],
+) -> Result<(), Error> {
+    key.verify(
+        HASH,
+        msg,
+        signature,
+        SignaturePadding::Pss {
+            salt_len: Some(variant.salt_len()),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::{Padding, Rsa};
+    use openssl::sign::{RsaPssSaltlen, Verifier};
+
+    const VARIANTS: [Variant; 4] = [
+        Variant::PssRandomized,
+        Variant::PssZeroRandomized,
+        Variant::PssDeterministic,
+        Variant::PssZeroDeterministic,
+    ];
+
+    #[test]
+    fn test_protocol_round_trip_verifies_with_openssl() {
+        let key = RsaPrivateKey::generate(2048).unwrap();
+        let public = key.to_public_key();
+        let openssl =
+            PKey::from_rsa(Rsa::private_key_from_pem(key.to_pkcs1_pem().as_bytes()).unwrap())
+                .unwrap();
+
+        for variant in VARIANTS {
+            let msg = prepare(variant, b"Some token").unwrap();
+            assert_eq!(msg.len() - 10, if variant.is_randomized() { 32 } else { 0 });
+
+            let blinded = blind(&public, variant, &msg).unwrap();
+            assert_ne!(blinded.blinded_msg, msg);
+            let blind_sig = blind_sign(&key, &blinded.blinded_msg).unwrap();
+            let signature = finalize(&public, variant, &msg, &blind_sig, &blinded.inv).unwrap();
+            verify(&public, variant, &msg, &signature).unwrap();
+
+            let mut verifier = Verifier::new(MessageDigest::sha384(), &openssl).unwrap();
+            verifier.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+            verifier
+                .set_rsa_pss_saltlen(RsaPssSaltlen::custom(variant.salt_len() as i32))
+                .unwrap();
+            verifier.update(&msg).unwrap();
+            assert!(verifier.verify(&signature).unwrap(), "{}", variant.name());
+
+            assert_eq!(
+                verify(&public, variant, b"Other token", &signature),
+                Err(Error::Verification)
+            );
+        }
+    }
+
+    #[test]
+    fn test_unblinded_signature_matches_direct_signature() {
+        let key = RsaPrivateKey::generate(1024).unwrap();
+        let public = key.to_public_key();
+        let msg = b"Some token";
+
+        // PSSZERO is deterministic, the unblinded signature is the plain one
+        let r = random_range(&BigUint::one(), public.n()).unwrap();
+        let blinded = blind_with(&public, Variant::PssZeroDeterministic, msg, &[], &r).unwrap();
+        let blind_sig = blind_sign(&key, &blinded.blinded_msg).unwrap();
+        let signature = finalize(
+            &public,
+            Variant::PssZeroDeterministic,
+            msg,
+            &blind_sig,
+            &blinded.inv,
+        )
+        .unwrap();
+        let direct = key
+            .sign(HASH, msg, SignaturePadding::Pss { salt_len: Some(0) })
+            .unwrap();
+        assert_eq!(signature, direct);
+
+        // a blinded message signed by another key doesn't finalize
+        let other = RsaPrivateKey::generate(1024).unwrap();
+        if let Ok(blind_sig) = blind_sign(&other, &blinded.blinded_msg) {
+            assert!(finalize(
+                &public,
+                Variant::PssZeroDeterministic,
+                msg,
+                &blind_sig,
+                &blinded.inv
+            )
+            .is_err());
+        }
+
+        assert!(blind_with(&public, Variant::PssDeterministic, msg, &[], &r).is_err());
+        assert!(blind_sign(&key, &blinded.blinded_msg[1..]).is_err());
+    }
+
+    struct Vector {
+        variant: Variant,
+        msg_prefix: &'static str,
+        msg: &'static str,
+        salt: &'static str,
+        inv: &'static str,
+        blinded_msg: &'static str,
+        blind_sig: &'static str,
+        sig: &'static str,
+    }
+
+    // RFC 9474 appendix A, all vectors share the 4096 bit key
+    const RFC_P: &str = concat!(
+        "e1f4d7a34802e27c7392a3cea32a262a34dc3691bd87f3f310dc756734889305",
+        "59c120fd0410194fb8a0da55bd0b81227e843fdca6692ae80e5a5d414116d480",
+        "3fca7d8c30eaaae57e44a1816ebb5c5b0606c536246c7f11985d731684150b63",
+        "c9a3ad9e41b04c0b5b27cb188a692c84696b742a80d3cd00ab891f2457443dad",
+        "feba6d6daf108602be26d7071803c67105a5426838e6889d77e8474b29244cef",
+        "af418e381b312048b457d73419213063c60ee7b0d81820165864fef93523c963",
+        "5c22210956e53a8d96322493ffc58d845368e2416e078e5bcb5d2fd68ae6acfa",
+        "54f9627c42e84a9d3f2774017e32ebca06308a12ecc290c7cd1156dcccfb2311",
+    );
+    const RFC_Q: &str = concat!(
+        "c601a9caea66dc3835827b539db9df6f6f5ae77244692780cd334a006ab353c8",
+        "06426b60718c05245650821d39445d3ab591ed10a7339f15d83fe13f6a3dfb20",
+        "b9452c6a9b42eaa62a68c970df3cadb2139f804ad8223d56108dfde30ba7d367",
+        "e9b0a7a80c4fdba2fd9dde6661fc73fc2947569d2029f2870fc02d8325acf28c",
+        "9afa19ecf962daa7916e21afad09eb62fe9f1cf91b77dc879b7974b490d3ebd2",
+        "e95426057f35d0a3c9f45f79ac727ab81a519a8b9285932d9b2e5ccd347e59f3",
+        "f32ad9ca359115e7da008ab7406707bd0e8e185a5ed8758b5ba266e8828f8d86",
+        "3ae133846304a2936ad7bc7c9803879d2fc4a28e69291d73dbd799f8bc238385",
+    );
+
+    // Test vectors 1 and 2, msg_prefix goes in front of msg for the randomized variants
+    const RFC_VECTORS: [Vector; 2] = [
+        Vector {
+            variant: Variant::PssDeterministic,
+            msg_prefix: "",
+            msg: concat!(
+                "8f3dc6fb8c4a02f4d6352edf0907822c1210a9b32f9bdda4c45a698c80023aa6",
+                "b59f8cfec5fdbb36331372ebefedae7d",
+            ),
+            salt: concat!(
+                "051722b35f458781397c3a671a7d3bd3096503940e4c4f1aaa269d60300ce449",
+                "555cd7340100df9d46944c5356825abf",
+            ),
+            inv: concat!(
+                "80682c48982407b489d53d1261b19ec8627d02b8cda5336750b8cee332ae260d",
+                "e57b02d72609c1e0e9f28e2040fc65b6f02d56dbd6aa9af8fde656f70495dfb7",
+                "23ba01173d4707a12fddac628ca29f3e32340bd8f7ddb557cf819f6b01e445ad",
+                "96f874ba235584ee71f6581f62d4f43bf03f910f6510deb85e8ef06c7f09d979",
+                "4a008be7ff2529f0ebb69decef646387dc767b74939265fec0223aa6d84d2a8a",
+                "1cc912d5ca25b4e144ab8f6ba054b54910176d5737a2cff011da431bd5f2a0d2",
+                "d66b9e70b39f4b050e45c0d9c16f02deda9ddf2d00f3e4b01037d7029cd49c2d",
+                "46a8e1fc2c0c17520af1f4b5e25ba396afc4cd60c494a4c426448b35b49635b3",
+                "37cfb08e7c22a39b256dd032c00adddafb51a627f99a0e1704170ac1f1912e49",
+                "d9db10ec04c19c58f420212973e0cb329524223a6aa56c7937c5dffdb5d966b6",
+                "cd4cbc26f3201dd25c80960a1a111b32947bb78973d269fac7f5186530930ed1",
+                "9f68507540eed9e1bab8b00f00d8ca09b3f099aae46180e04e3584bd7ca054df",
+                "18a1504b89d1d1675d0966c4ae1407be325cdf623cf13ff13e4a28b594d59e3e",
+                "adbadf6136eee7a59d6a444c9eb4e2198e8a974f27a39eb63af2c9af3870488b",
+                "8adaad444674f512133ad80b9220e09158521614f1faadfe8505ef57b7df6813",
+                "048603f0dd04f4280177a11380fbfc861dbcbd7418d62155248dad5fdec0991f",
+            ),
+            blinded_msg: concat!(
+                "10c166c6a711e81c46f45b18e5873cc4f494f003180dd7f115585d871a289302",
+                "59654fe28a54dab319cc5011204c8373b50a57b0fdc7a678bd74c523259dfe4f",
+                "d5ea9f52f170e19dfa332930ad1609fc8a00902d725cfe50685c95e5b2968c9a",
+                "2828a21207fcf393d15f849769e2af34ac4259d91dfd98c3a707c509e1af5564",
+                "7efaa31290ddf48e0133b798562af5eabd327270ac2fb6c594734ce339a14ea4",
+                "fe1b9a2f81c0bc230ca523bda17ff42a377266bc2778a274c0ae5ec5a8cbbe36",
+                "4fcf0d2403f7ee178d77ff28b67a20c7ceec009182dbcaa9bc99b51ebbf13b7d",
+                "542be337172c6474f2cd3561219fe0dfa3fb207cff89632091ab841cf38d8aa8",
+                "8af6891539f263adb8eac6402c41b6ebd72984e43666e537f5f5fe27b2b5aa11",
+                "4957e9a580730308a5f5a9c63a1eb599f093ab401d0c6003a451931b6d124180",
+                "305705845060ebba6b0036154fcef3e5e9f9e4b87e8f084542fd1dd67e7782a5",
+                "585150181c01eb6d90cb95883837384a5b91dbb606f266059ecc51b5acbaa280",
+                "e45cfd2eec8cc1cdb1b7211c8e14805ba683f9b78824b2eb005bc8a7d7179a36",
+                "c152cb87c8219e5569bba911bb32a1b923ca83de0e03fb10fba75d85c55907dd",
+                "a5a2606bf918b056c3808ba496a4d95532212040a5f44f37e1097f26dc27b98a",
+                "51837daa78f23e532156296b64352669c94a8a855acf30533d8e0594ace7c442",
+            ),
+            blind_sig: concat!(
+                "364f6a40dbfbc3bbb257943337eeff791a0f290898a6791283bba581d9eac90a",
+                "6376a837241f5f73a78a5c6746e1306ba3adab6067c32ff69115734ce014d354",
+                "e2f259d4cbfb890244fd451a497fe6ecf9aa90d19a2d441162f7eaa7ce3fc4e8",
+                "9fd4e76b7ae585be2a2c0fd6fb246b8ac8d58bcb585634e30c9168a434786fe5",
+                "e0b74bfe8187b47ac091aa571ffea0a864cb906d0e28c77a00e8cd8f6aba4317",
+                "a8cc7bf32ce566bd1ef80c64de041728abe087bee6cadd0b7062bde5ceef308a",
+                "23bd1ccc154fd0c3a26110df6193464fc0d24ee189aea8979d722170ba945fdc",
+                "ce9b1b4b63349980f3a92dc2e5418c54d38a862916926b3f9ca270a8cf40dfb9",
+                "772bfbdd9a3e0e0892369c18249211ba857f35963d0e05d8da98f1aa0c6bba58",
+                "f47487b8f663e395091275f82941830b050b260e4767ce2fa903e75ff8970c98",
+                "bfb3a08d6db91ab1746c86420ee2e909bf681cac173697135983c3594b2def67",
+                "3736220452fde4ddec867d40ff42dd3da36c84e3e52508b891a00f50b4f62d11",
+                "2edb3b6b6cc3dbd546ba10f36b03f06c0d82aeec3b25e127af545fac28e1613a",
+                "0517a6095ad18a98ab79f68801e05c175e15bae21f821e80c80ab4fdec6fb34c",
+                "a315e194502b8f3dcf7892b511aee45060e3994cd15e003861bc7220a2babd7b",
+                "40eda03382548a34a7110f9b1779bf3ef6011361611e6bc5c0dc851e1509de1a",
+            ),
+            sig: concat!(
+                "6fef8bf9bc182cd8cf7ce45c7dcf0e6f3e518ae48f06f3c670c649ac737a8b81",
+                "19a34d51641785be151a697ed7825fdfece82865123445eab03eb4bb91cecf4d",
+                "6951738495f8481151b62de869658573df4e50a95c17c31b52e154ae26a04067",
+                "d5ecdc1592c287550bb982a5bb9c30fd53a768cee6baabb3d483e9f1e2da954c",
+                "7f4cf492fe3944d2fe456c1ecaf0840369e33fb4010e6b44bb1d721840513524",
+                "d8e9a3519f40d1b81ae34fb7a31ee6b7ed641cb16c2ac999004c2191de020145",
+                "7523f5a4700dd649267d9286f5c1d193f1454c9f868a57816bf5ff76c838a2ee",
+                "b616a3fc9976f65d4371deecfbab29362caebdff69c635fe5a2113da4d4d8c24",
+                "f0b16a0584fa05e80e607c5d9a2f765f1f069f8d4da21f27c2a3b5c984b4ab24",
+                "899bef46c6d9323df4862fe51ce300fca40fb539c3bb7fe2dcc9409e425f2d3b",
+                "95e70e9c49c5feb6ecc9d43442c33d50003ee936845892fb8be475647da9a080",
+                "f5bc7f8a716590b3745c2209fe05b17992830ce15f32c7b22cde755c8a2fe50b",
+                "d814a0434130b807dc1b7218d4e85342d70695a5d7f29306f25623ad1e8aa08e",
+                "f71b54b8ee447b5f64e73d09bdd6c3b7ca224058d7c67cc7551e9241688ada12",
+                "d859cb7646fbd3ed8b34312f3b49d69802f0eaa11bc4211c2f7a29cd5c01ed01",
+                "a39001c5856fab36228f5ee2f2e1110811872fe7c865c42ed59029c706195d52",
+            ),
+        },
+        Vector {
+            variant: Variant::PssZeroDeterministic,
+            msg_prefix: "",
+            msg: concat!(
+                "8f3dc6fb8c4a02f4d6352edf0907822c1210a9b32f9bdda4c45a698c80023aa6",
+                "b59f8cfec5fdbb36331372ebefedae7d",
+            ),
+            salt: "",
+            inv: concat!(
+                "80682c48982407b489d53d1261b19ec8627d02b8cda5336750b8cee332ae260d",
+                "e57b02d72609c1e0e9f28e2040fc65b6f02d56dbd6aa9af8fde656f70495dfb7",
+                "23ba01173d4707a12fddac628ca29f3e32340bd8f7ddb557cf819f6b01e445ad",
+                "96f874ba235584ee71f6581f62d4f43bf03f910f6510deb85e8ef06c7f09d979",
+                "4a008be7ff2529f0ebb69decef646387dc767b74939265fec0223aa6d84d2a8a",
+                "1cc912d5ca25b4e144ab8f6ba054b54910176d5737a2cff011da431bd5f2a0d2",
+                "d66b9e70b39f4b050e45c0d9c16f02deda9ddf2d00f3e4b01037d7029cd49c2d",
+                "46a8e1fc2c0c17520af1f4b5e25ba396afc4cd60c494a4c426448b35b49635b3",
+                "37cfb08e7c22a39b256dd032c00adddafb51a627f99a0e1704170ac1f1912e49",
+                "d9db10ec04c19c58f420212973e0cb329524223a6aa56c7937c5dffdb5d966b6",
+                "cd4cbc26f3201dd25c80960a1a111b32947bb78973d269fac7f5186530930ed1",
+                "9f68507540eed9e1bab8b00f00d8ca09b3f099aae46180e04e3584bd7ca054df",
+                "18a1504b89d1d1675d0966c4ae1407be325cdf623cf13ff13e4a28b594d59e3e",
+                "adbadf6136eee7a59d6a444c9eb4e2198e8a974f27a39eb63af2c9af3870488b",
+                "8adaad444674f512133ad80b9220e09158521614f1faadfe8505ef57b7df6813",
+                "048603f0dd04f4280177a11380fbfc861dbcbd7418d62155248dad5fdec0991f",
+            ),
+            blinded_msg: concat!(
+                "982790826556aabe6004467671a864397eea3b95740e9a11c8b80b99ee0cf4db",
+                "c50af860bda81b601a2eceaa6943ef104f13325ad0be2e37f42030b3120e87cf",
+                "ee8cfe59cde1acfb25485a43275ebe777292e2518181ae531e596f988ff16f45",
+                "8daa5a42408939cbe60e7271391a21657276427d195bee6a20054101d4ceb892",
+                "ecdea402ea1a866acf0e451a3336f07e7589330d96c3883fd5bc1a829a715b61",
+                "8b74a86b2a898764246ad081d4c9f1edb8ab5077e315fde2417ec2dd33cad93e",
+                "120340b49be89c18a63e62c6bb289037283d3bf18608be11ee4c823c710b0c6b",
+                "89235fed3f03a7b96ddd25a8f54f20dac37ce8905093ad8e066810f354fb1773",
+                "236e3d3788ba755de2c9bce8d340078bb1831ddc7314a5018673427ced65cb35",
+                "6281aae08b5e6636f3eb2417e09d6ae476a9abcc410bc8c90813d0740e39ae75",
+                "efae4c02eed49dbb7aa51258bb71197445d17a6029bf566ba6b36282173af2c4",
+                "2e9b9631366f22eb6a19ef1d92bd3ce0631d3a7fb3288195b0ba380a3828d541",
+                "1cefd5eba83e52198c001ac9946a333a33d89d4d235fc833239d59837f04eaf0",
+                "65e9563659b00c7624a6263b727d8f2c07959ba2bb592e7ff251b8f09c85995f",
+                "d2e4474e743586576b518230986b6076b762ae77088a37e4bffd2ef41ae68d6d",
+                "4e79205290b4f76c42ef039638c41cdc6fe8af9b429c0dee45b2942e3861da2a",
+            ),
+            blind_sig: concat!(
+                "362ef369f9b8c1487e285514702a7cd6fe03e4a2fb854881f3d3f986b7742a0c",
+                "9bfab6562a6cd5ed71c574af67d7e77e71b33420c08ebb0ff37886b858297f95",
+                "62fc366066c6d8e77bad1918b04756ba03f5c385d44f06759daf1b7a38b2a642",
+                "48dee95d0e3886c8afa1f74afd8ac3c56520d0f3fd206df8e0d257312756803b",
+                "09a79d0cc38112592c3aec32de5a9bc3284c5a0a2d0808b102deafa5cc60f04e",
+                "3d71c0284cba04f17f88aa8e07d5544fe0265807d515877f79d30ed26d522b9d",
+                "9c56597647b0dbca5a69d6418f8d1b51481723f272c2a3d48f6f4fd6beeac357",
+                "6c3edb00e8779964548aeab8e004c7c4f8ef9cb6e680e2d2d49792004bb3e697",
+                "4fa48f241a361ca449c02bd4c0ad4e66252c55e656f16049908efe59acbafa11",
+                "71895dfac64d909808e5420469d622c7253ec1de7522b41634d383bf8786bf88",
+                "1cbf1561627f1e62b2d93300ec30ec0f5f0ab32036fce068bc76b0b0c6452079",
+                "537f8d7f8dcee4b42bbf2d9ad7499d3835cd93cfc7e8ebea3554ab5241e181e5",
+                "d73241b7bebf0a281b63594a35f4993e2b416d60db966b58b648cfcba2c4bee4",
+                "c2830aae4a70ff55012480298f549c13b1b2684277bca12f592471b8a9928517",
+                "4f1c0ebb38fc80e74a10b3f02ec3e6682ba873f7ff0e1e79718b470927c74ed7",
+                "54d4f7c3d9a55e22246e829cdb5a1c6fb2a0a6c896df303063c918bcf5eb0017",
+            ),
+            sig: concat!(
+                "4454b6983ff01cb28545329f394936efa42ed231e15efbc025fdaca00277acf0",
+                "c8e00e3d8b0ecebd35b057b8ebfc14e1a7097368a4abd20b555894ccef3d1b95",
+                "28c6bcbda6b95376bef230d0f1feff0c1064c62c60a7ae7431d1fdfa43a81eed",
+                "9235e363e1ffa0b2797aba6aad6082fcd285e14fc8b71de6b9c87cb4059c7dc1",
+                "e96ae1e63795a1e9af86b9073d1d848aef3eca8a03421bcd116572456b53bcfd",
+                "4dabb0a9691f1fabda3ed0ce357aee2cfee5b1a0eb226f69716d4e011d96eede",
+                "5e38a9acb531a64336a0d5b0bae3ab085b658692579a376740ff6ce69e89b06f",
+                "360520b864e33d82d029c808248a19e18e31f0ecd16fac5cd4870f8d3ebc1c32",
+                "c718124152dc905672ab0b7af48bf7d1ac1ff7b9c742549c91275ab105458ae3",
+                "7621757add83482bbcf779e777bbd61126e93686635d4766aedf5103cf7978f3",
+                "856ccac9e28d21a850dbb03c811128616d315d717be1c2b6254f8509acae8620",
+                "42c034530329ce15ca2e2f6b1f5fd59272746e3918c748c0eb810bf76884fa10",
+                "fcf749326bbfaa5ba285a0186a22e4f628dbf178d3bb5dc7e165ca73f6a55ecc",
+                "14c4f5a26c4693ce5da032264cbec319b12ddb9787d0efa4fcf1e5ccee35ad85",
+                "ecd453182df9ed735893f830b570faae8be0f6fe2e571a4e0d927cba4debd368",
+                "d3b4fca33ec6251897a137cf75474a32ac8256df5e5ffa518b88b43fb6f63a24",
+            ),
+        },
+    ];
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_rfc_vectors() {
+        let p = BigUint::from_bytes_be(&hex(RFC_P));
+        let q = BigUint::from_bytes_be(&hex(RFC_Q));
+        let e = BigUint::from(65537u32);
+        let d = e.modinv(&(&p - 1u8).lcm(&(&q - 1u8))).unwrap();
+        let key = RsaPrivateKey::from_components(&p * &q, e, d, p, q).unwrap();
+        let public = key.to_public_key();
+
+        for vector in RFC_VECTORS {
+            let msg = [hex(vector.msg_prefix), hex(vector.msg)].concat();
+            let r = BigUint::from_bytes_be(&hex(vector.inv))
+                .modinv(public.n())
+                .unwrap();
+
+            let blinded = blind_with(&public, vector.variant, &msg, &hex(vector.salt), &r).unwrap();
+            assert_eq!(
+                blinded.blinded_msg,
+                hex(vector.blinded_msg),
+                "{}",
+                vector.variant.name()
+            );
+            assert_eq!(blinded.inv, hex(vector.inv));
+
+            let blind_sig = blind_sign(&key, &blinded.blinded_msg).unwrap();
+            assert_eq!(
+                blind_sig,
+                hex(vector.blind_sig),
+                "{}",
+                vector.variant.name()
+            );
+
+            let signature =
+                finalize(&public, vector.variant, &msg, &blind_sig, &blinded.inv).unwrap();
+            assert_eq!(signature, hex(vector.sig), "{}", vector.variant.name());
+            verify(&public, vector.variant, &msg, &signature).unwrap();
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// Minimal DER and PEM handling for PKCS#1, PKCS#8 and SubjectPublicKeyInfo RSA keys
+use crate::Error;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use num_bigint::BigUint;
+
+pub const SEQUENCE: u8 = 0x30;
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+
+/// 1.2.840.113549.1.1.1
+pub const RSA_ENCRYPTION_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Tag of the next element.
+    pub fn peek(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Content of the next element, which must carry `tag`.
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8], Error> {
+        let malformed = || Error::InvalidKey("malformed DER");
+
+        if self.data.len() < 2 || self.data[0] != tag {
+            return Err(malformed());
+        }
+
+        let (len, header) = match self.data[1] {
+            len if len < 0x80 => (len as usize, 2),
+            0x81..=0x84 => {
+                let count = (self.data[1] & 0x7f) as usize;
+                let bytes = self.data.get(2..2 + count).ok_or_else(malformed)?;
+
+                (
+                    bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize),
+                    2 + count,
+                )
+            }
+            _ => return Err(malformed()),
+        };
+
+        let content = self.data.get(header..header + len).ok_or_else(malformed)?;
+        self.data = &self.data[header + len..];
+
+        Ok(content)
+    }
+
+    pub fn read_integer(&mut self) -> Result<BigUint, Error> {
+        let bytes = self.read(INTEGER)?;
+
+        if bytes.first().is_some_and(|&b| b & 0x80 != 0) {
+            return Err(Error::InvalidKey("negative integer"));
+        }
+
+        Ok(BigUint::from_bytes_be(bytes))
+    }
+}
+
+pub fn write(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|&b| b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend_from_slice(&bytes);
+    }
+
+    out.extend_from_slice(content);
+    out
+}
+
+pub fn write_integer(value: &BigUint) -> Vec<u8> {
+    let mut bytes = value.to_bytes_be();
+
+    if bytes[0] & 0x80 != 0 {
+        bytes.insert(0, 0);
+    }
+
+    write(INTEGER, &bytes)
+}
+
+pub fn rsa_algorithm_identifier() -> Vec<u8> {
+    let mut content = write(OBJECT_IDENTIFIER, RSA_ENCRYPTION_OID);
+    content.extend(write(NULL, &[]));
+
+    write(SEQUENCE, &content)
+}
+
+/// Checks an AlgorithmIdentifier names rsaEncryption.
+pub fn read_rsa_algorithm(reader: &mut Reader) -> Result<(), Error> {
+    let mut algorithm = Reader::new(reader.read(SEQUENCE)?);
+
+    if algorithm.read(OBJECT_IDENTIFIER)? != RSA_ENCRYPTION_OID {
+        return Err(Error::InvalidKey("not an RSA key"));
+    }
+
+    Ok(())
+}
+
+/// Label and decoded body of the first PEM block.
+pub fn pem_decode(pem: &str) -> Result<(String, Vec<u8>), Error> {
+    let malformed = || Error::InvalidKey("malformed PEM");
+    let begin = pem.find("-----BEGIN ").ok_or_else(malformed)?;
+    let rest = &pem[begin + 11..];
+    let label_end = rest.find("-----").ok_or_else(malformed)?;
+    let label = &rest[..label_end];
+    let body_start = label_end + 5;
+    let end = rest
+        .find(&format!("-----END {}-----", label))
+        .ok_or_else(malformed)?;
+    let body: String = rest[body_start..end]
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    Ok((
+        label.to_string(),
+        STANDARD.decode(body).map_err(|_| malformed())?,
+    ))
+}
+
+pub fn pem_encode(label: &str, der: &[u8]) -> String {
+    let body = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+
+    for line in body.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap());
+        pem.push('\n');
+    }
+
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
@@ -0,0 +1,113 @@
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+use sha3::Sha3_256;
+use std::io::{self, Read};
+
+fn digest_stream<D: Digest>(mut reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut hasher = D::new();
+    let mut buffer = vec![0; 64 * 1024];
+
+    loop {
+        match reader.read(&mut buffer)? {
+            0 => return Ok(hasher.finalize().to_vec()),
+            len => hasher.update(&buffer[..len]),
+        }
+    }
+}
+
+fn mac<M: Mac + hmac::digest::KeyInit>(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    // HMAC takes keys of any length
+    let mut mac = <M as hmac::digest::KeyInit>::new_from_slice(key).unwrap();
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Sha3_256,
+}
+
+impl HashAlgorithm {
+    pub fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+            HashAlgorithm::Sha224 => Sha224::digest(data).to_vec(),
+            HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            HashAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+            HashAlgorithm::Sha3_256 => Sha3_256::digest(data).to_vec(),
+        }
+    }
+
+    /// Digest of everything `reader` yields, read in chunks.
+    pub fn digest_reader(self, reader: impl Read) -> io::Result<Vec<u8>> {
+        match self {
+            HashAlgorithm::Sha1 => digest_stream::<Sha1>(reader),
+            HashAlgorithm::Sha224 => digest_stream::<Sha224>(reader),
+            HashAlgorithm::Sha256 => digest_stream::<Sha256>(reader),
+            HashAlgorithm::Sha384 => digest_stream::<Sha384>(reader),
+            HashAlgorithm::Sha512 => digest_stream::<Sha512>(reader),
+            HashAlgorithm::Sha3_256 => digest_stream::<Sha3_256>(reader),
+        }
+    }
+
+    /// HMAC (RFC 2104) under `key` of the concatenated `parts`.
+    pub fn hmac(self, key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            HashAlgorithm::Sha1 => mac::<Hmac<Sha1>>(key, parts),
+            HashAlgorithm::Sha224 => mac::<Hmac<Sha224>>(key, parts),
+            HashAlgorithm::Sha256 => mac::<Hmac<Sha256>>(key, parts),
+            HashAlgorithm::Sha384 => mac::<Hmac<Sha384>>(key, parts),
+            HashAlgorithm::Sha512 => mac::<Hmac<Sha512>>(key, parts),
+            HashAlgorithm::Sha3_256 => mac::<Hmac<Sha3_256>>(key, parts),
+        }
+    }
+
+    pub fn size(self) -> usize {
+        match self {
+            HashAlgorithm::Sha1 => 20,
+            HashAlgorithm::Sha224 => 28,
+            HashAlgorithm::Sha256 | HashAlgorithm::Sha3_256 => 32,
+            HashAlgorithm::Sha384 => 48,
+            HashAlgorithm::Sha512 => 64,
+        }
+    }
+
+    /// DER encoded DigestInfo header that precedes the hash in PKCS#1 v1.5 signatures.
+    pub fn digest_info_prefix(self) -> &'static [u8] {
+        match self {
+            HashAlgorithm::Sha1 => &[
+                0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04,
+                0x14,
+            ],
+            HashAlgorithm::Sha224 => &[
+                0x30, 0x2d, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x04, 0x05, 0x00, 0x04, 0x1c,
+            ],
+            HashAlgorithm::Sha256 => &[
+                0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x01, 0x05, 0x00, 0x04, 0x20,
+            ],
+            HashAlgorithm::Sha384 => &[
+                0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x02, 0x05, 0x00, 0x04, 0x30,
+            ],
+            HashAlgorithm::Sha512 => &[
+                0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x03, 0x05, 0x00, 0x04, 0x40,
+            ],
+            HashAlgorithm::Sha3_256 => &[
+                0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x08, 0x05, 0x00, 0x04, 0x20,
+            ],
+        }
+    }
+}
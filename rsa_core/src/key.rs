@@ -0,0 +1,767 @@
+use crate::der::{self, Reader, BIT_STRING, OCTET_STRING, SEQUENCE};
+use crate::hash::HashAlgorithm;
+use crate::montgomery::pow_ct;
+use crate::padding::{self, EncryptionPadding, SignaturePadding};
+use crate::pbes2;
+use crate::prime::{generate_prime, random_range};
+use crate::Error;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::One;
+use zeroize::Zeroizing;
+
+pub const PUBLIC_EXPONENT: u32 = 65537;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RsaPublicKey {
+    n: BigUint,
+    e: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RsaPrivateKey {
+    public: RsaPublicKey,
+    d: BigUint,
+    p: BigUint,
+    q: BigUint,
+    dp: BigUint,
+    dq: BigUint,
+    qinv: BigUint,
+}
+
+fn to_padded_bytes(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut out = vec![0u8; len - bytes.len()];
+    out.extend(bytes);
+    out
+}
+
+impl RsaPublicKey {
+    pub fn new(n: BigUint, e: BigUint) -> Result<Self, Error> {
+        if n.is_even() || n.bits() < 512 {
+            return Err(Error::InvalidKey("bad modulus"));
+        }
+        if e.is_even() || e < BigUint::from(3u8) || e >= n {
+            return Err(Error::InvalidKey("bad public exponent"));
+        }
+
+        Ok(RsaPublicKey { n, e })
+    }
+
+    pub fn n(&self) -> &BigUint {
+        &self.n
+    }
+
+    pub fn e(&self) -> &BigUint {
+        &self.e
+    }
+
+    pub fn bits(&self) -> usize {
+        self.n.bits() as usize
+    }
+
+    /// Modulus size in bytes, the length of every cypher and signature.
+    pub fn size(&self) -> usize {
+        self.bits().div_ceil(8)
+    }
+
+    fn public_op(&self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        let value = BigUint::from_bytes_be(input);
+
+        if input.len() > self.size() || value >= self.n {
+            return Err(Error::MessageTooLong);
+        }
+
+        Ok(to_padded_bytes(
+            &value.modpow(&self.e, &self.n),
+            self.size(),
+        ))
+    }
+
+    pub fn encrypt(&self, data: &[u8], padding: &EncryptionPadding) -> Result<Vec<u8>, Error> {
+        let k = self.size();
+        let em = match padding {
+            EncryptionPadding::Pkcs1v15 => padding::pkcs1v15_encrypt_pad(data, k)?,
+            EncryptionPadding::Oaep {
+                hash,
+                mgf1_hash,
+                label,
+            } => padding::oaep_pad(data, k, *hash, *mgf1_hash, label)?,
+            EncryptionPadding::None if data.len() == k => data.to_vec(),
+            EncryptionPadding::None => return Err(Error::MessageTooLong),
+        };
+
+        self.public_op(&em)
+    }
+
+    /// Recovers data encrypted with the private key, the counterpart of
+    /// [`RsaPrivateKey::private_encrypt`].
+    pub fn public_decrypt(
+        &self,
+        cypher: &[u8],
+        padding: &EncryptionPadding,
+    ) -> Result<Vec<u8>, Error> {
+        let em = self.public_op(cypher)?;
+
+        match padding {
+            EncryptionPadding::Pkcs1v15 => padding::pkcs1v15_raw_sign_unpad(&em),
+            EncryptionPadding::None => Ok(em),
+            EncryptionPadding::Oaep { .. } => Err(Error::UnsupportedPadding),
+        }
+    }
+
+    pub fn verify(
+        &self,
+        hash: HashAlgorithm,
+        message: &[u8],
+        signature: &[u8],
+        padding: SignaturePadding,
+    ) -> Result<(), Error> {
+        self.verify_digest(hash, &hash.digest(message), signature, padding)
+    }
+
+    /// Verifies a signature over a message already hashed with `hash`.
+    pub fn verify_digest(
+        &self,
+        hash: HashAlgorithm,
+        digest: &[u8],
+        signature: &[u8],
+        padding: SignaturePadding,
+    ) -> Result<(), Error> {
+        if digest.len() != hash.size() || signature.len() != self.size() {
+            return Err(Error::Verification);
+        }
+
+        let em = self.public_op(signature).map_err(|_| Error::Verification)?;
+
+        match padding {
+            SignaturePadding::Pkcs1v15 => {
+                if padding::pkcs1v15_sign_pad(hash, digest, self.size())? != em {
+                    return Err(Error::Verification);
+                }
+
+                Ok(())
+            }
+            SignaturePadding::Pss { salt_len } => {
+                padding::pss_verify(digest, &em, self.bits() - 1, hash, salt_len)
+            }
+        }
+    }
+
+    pub fn from_pkcs1_der(der: &[u8]) -> Result<Self, Error> {
+        let mut outer = Reader::new(der);
+        let mut key = Reader::new(outer.read(SEQUENCE)?);
+        let n = key.read_integer()?;
+        let e = key.read_integer()?;
+
+        RsaPublicKey::new(n, e)
+    }
+
+    pub fn to_pkcs1_der(&self) -> Vec<u8> {
+        let mut content = der::write_integer(&self.n);
+        content.extend(der::write_integer(&self.e));
+
+        der::write(SEQUENCE, &content)
+    }
+
+    /// X.509 SubjectPublicKeyInfo
+    pub fn from_public_key_der(der: &[u8]) -> Result<Self, Error> {
+        let mut outer = Reader::new(der);
+        let mut info = Reader::new(outer.read(SEQUENCE)?);
+        der::read_rsa_algorithm(&mut info)?;
+        let bits = info.read(BIT_STRING)?;
+
+        match bits.split_first() {
+            Some((0, key)) => RsaPublicKey::from_pkcs1_der(key),
+            _ => Err(Error::InvalidKey("malformed public key bit string")),
+        }
+    }
+
+    pub fn to_public_key_der(&self) -> Vec<u8> {
+        let mut bits = vec![0u8];
+        bits.extend(self.to_pkcs1_der());
+
+        let mut content = der::rsa_algorithm_identifier();
+        content.extend(der::write(BIT_STRING, &bits));
+
+        der::write(SEQUENCE, &content)
+    }
+
+    /// Accepts both "RSA PUBLIC KEY" (PKCS#1) and "PUBLIC KEY" (SubjectPublicKeyInfo) PEM.
+    pub fn from_pem(pem: &str) -> Result<Self, Error> {
+        match der::pem_decode(pem)? {
+            (label, der) if label == "RSA PUBLIC KEY" => RsaPublicKey::from_pkcs1_der(&der),
+            (label, der) if label == "PUBLIC KEY" => RsaPublicKey::from_public_key_der(&der),
+            _ => Err(Error::InvalidKey("not an RSA public key")),
+        }
+    }
+
+    pub fn to_pkcs1_pem(&self) -> String {
+        der::pem_encode("RSA PUBLIC KEY", &self.to_pkcs1_der())
+    }
+
+    pub fn to_public_key_pem(&self) -> String {
+        der::pem_encode("PUBLIC KEY", &self.to_public_key_der())
+    }
+}
+
+impl RsaPrivateKey {
+    /// New key pair with e = 65537 and a modulus of exactly `bits` bits.
+    pub fn generate(bits: usize) -> Result<Self, Error> {
+        Self::generate_with_exponent(bits, &BigUint::from(PUBLIC_EXPONENT))
+    }
+
+    /// New key pair with public exponent `e`, which must be odd and at least 3.
+    pub fn generate_with_exponent(bits: usize, e: &BigUint) -> Result<Self, Error> {
+        if bits < 1024 || !bits.is_multiple_of(2) {
+            return Err(Error::KeySize(bits));
+        }
+        if e.is_even() || *e < BigUint::from(3u8) || e.bits() as usize >= bits / 2 {
+            return Err(Error::InvalidKey("bad public exponent"));
+        }
+
+        let e = e.clone();
+        let coprime = |p: &BigUint| (p - 1u8).gcd(&e).is_one();
+        // FIPS 186-5 A.1.3: primes differ somewhere in their top 100 bits
+        let min_distance = BigUint::one() << (bits / 2 - 100);
+
+        loop {
+            let p = generate_prime(bits as u64 / 2, coprime)?;
+            let q = generate_prime(bits as u64 / 2, coprime)?;
+
+            let distance = if p > q { &p - &q } else { &q - &p };
+            if distance <= min_distance {
+                continue;
+            }
+
+            let lambda = (&p - 1u8).lcm(&(&q - 1u8));
+            let d = e.modinv(&lambda).expect("e is coprime to lambda");
+
+            // d must not be small enough for Wiener style attacks
+            if d.bits() as usize <= bits / 2 {
+                continue;
+            }
+
+            return RsaPrivateKey::from_components(&p * &q, e, d, p, q);
+        }
+    }
+
+    /// Builds a key from its primes and exponents, the CRT values are derived.
+    pub fn from_components(
+        n: BigUint,
+        e: BigUint,
+        d: BigUint,
+        p: BigUint,
+        q: BigUint,
+    ) -> Result<Self, Error> {
+        if &p * &q != n {
+            return Err(Error::InvalidKey("modulus isn't the product of the primes"));
+        }
+
+        let public = RsaPublicKey::new(n, e)?;
+        // holds for d computed modulo either phi(n) or lambda(n)
+        let lambda = (&p - 1u8).lcm(&(&q - 1u8));
+        if (&d * &public.e) % lambda != BigUint::one() {
+            return Err(Error::InvalidKey("private exponent doesn't match"));
+        }
+
+        let qinv = q
+            .modinv(&p)
+            .ok_or(Error::InvalidKey("primes aren't coprime"))?;
+
+        Ok(RsaPrivateKey {
+            dp: &d % (&p - 1u8),
+            dq: &d % (&q - 1u8),
+            qinv,
+            public,
+            d,
+            p,
+            q,
+        })
+    }
+
+    pub fn to_public_key(&self) -> RsaPublicKey {
+        self.public.clone()
+    }
+
+    pub fn d(&self) -> &BigUint {
+        &self.d
+    }
+
+    pub fn primes(&self) -> (&BigUint, &BigUint) {
+        (&self.p, &self.q)
+    }
+
+    pub fn size(&self) -> usize {
+        self.public.size()
+    }
+
+    /// c^d mod n through the CRT with blinding and constant-time exponentiation,
+    /// the result is checked against the public key before it's released.
+    fn private_op(&self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        let n = &self.public.n;
+        let c = BigUint::from_bytes_be(input);
+
+        if input.len() > self.size() || c >= *n {
+            return Err(Error::Decryption);
+        }
+
+        // blinding factor r, the exponentiations only ever see c * r^e
+        let (r, r_inv) = loop {
+            let r = random_range(&BigUint::from(2u8), n)?;
+
+            if let Some(r_inv) = r.modinv(n) {
+                break (r, r_inv);
+            }
+        };
+        let blinded = (&c * r.modpow(&self.public.e, n)) % n;
+
+        let m1 = pow_ct(&(&blinded % &self.p), &self.dp, &self.p);
+        let m2 = pow_ct(&(&blinded % &self.q), &self.dq, &self.q);
+        let h = (&self.qinv * (&m1 + &self.p - (&m2 % &self.p))) % &self.p;
+        let m = ((m2 + h * &self.q) * r_inv) % n;
+
+        // a faulty CRT result would leak a factor of n
+        if m.modpow(&self.public.e, n) != c {
+            return Err(Error::Fault);
+        }
+
+        Ok(to_padded_bytes(&m, self.size()))
+    }
+
+    pub fn decrypt(&self, cypher: &[u8], padding: &EncryptionPadding) -> Result<Vec<u8>, Error> {
+        let em = self.private_op(cypher)?;
+
+        match padding {
+            EncryptionPadding::Pkcs1v15 => padding::pkcs1v15_encrypt_unpad(&em),
+            EncryptionPadding::Oaep {
+                hash,
+                mgf1_hash,
+                label,
+            } => padding::oaep_unpad(&em, *hash, *mgf1_hash, label),
+            EncryptionPadding::None => Ok(em),
+        }
+    }
+
+    /// Type 1 padded (or raw) private key operation without DigestInfo,
+    /// what OpenSSL's `RSA_private_encrypt` produces.
+    pub fn private_encrypt(
+        &self,
+        data: &[u8],
+        padding: &EncryptionPadding,
+    ) -> Result<Vec<u8>, Error> {
+        let em = match padding {
+            EncryptionPadding::Pkcs1v15 => padding::pkcs1v15_raw_sign_pad(data, self.size())?,
+            EncryptionPadding::None if data.len() == self.size() => data.to_vec(),
+            EncryptionPadding::None => return Err(Error::MessageTooLong),
+            EncryptionPadding::Oaep { .. } => return Err(Error::UnsupportedPadding),
+        };
+
+        self.private_op(&em)
+    }
+
+    pub fn sign(
+        &self,
+        hash: HashAlgorithm,
+        message: &[u8],
+        padding: SignaturePadding,
+    ) -> Result<Vec<u8>, Error> {
+        self.sign_digest(hash, &hash.digest(message), padding)
+    }
+
+    /// Signs a message already hashed with `hash`.
+    pub fn sign_digest(
+        &self,
+        hash: HashAlgorithm,
+        digest: &[u8],
+        padding: SignaturePadding,
+    ) -> Result<Vec<u8>, Error> {
+        if digest.len() != hash.size() {
+            return Err(Error::DigestLength);
+        }
+
+        let em = match padding {
+            SignaturePadding::Pkcs1v15 => padding::pkcs1v15_sign_pad(hash, digest, self.size())?,
+            SignaturePadding::Pss { salt_len } => {
+                let em_bits = self.public.bits() - 1;
+                let max_salt_len = em_bits
+                    .div_ceil(8)
+                    .checked_sub(hash.size() + 2)
+                    .ok_or(Error::MessageTooLong)?;
+
+                padding::pss_encode(digest, em_bits, hash, salt_len.unwrap_or(max_salt_len))?
+            }
+        };
+
+        self.private_op(&em)
+    }
+
+    pub fn from_pkcs1_der(der: &[u8]) -> Result<Self, Error> {
+        let mut outer = Reader::new(der);
+        let mut key = Reader::new(outer.read(SEQUENCE)?);
+
+        if key.read_integer()? != BigUint::ZERO {
+            return Err(Error::InvalidKey("multi-prime keys aren't supported"));
+        }
+
+        let n = key.read_integer()?;
+        let e = key.read_integer()?;
+        let d = key.read_integer()?;
+        let p = key.read_integer()?;
+        let q = key.read_integer()?;
+
+        RsaPrivateKey::from_components(n, e, d, p, q)
+    }
+
+    pub fn to_pkcs1_der(&self) -> Vec<u8> {
+        let mut content = der::write(der::INTEGER, &[0]);
+        for value in [
+            &self.public.n,
+            &self.public.e,
+            &self.d,
+            &self.p,
+            &self.q,
+            &self.dp,
+            &self.dq,
+            &self.qinv,
+        ] {
+            content.extend(der::write_integer(value));
+        }
+
+        der::write(SEQUENCE, &content)
+    }
+
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Self, Error> {
+        let mut outer = Reader::new(der);
+        let mut info = Reader::new(outer.read(SEQUENCE)?);
+
+        if info.read_integer()? != BigUint::ZERO {
+            return Err(Error::InvalidKey("unsupported PKCS#8 version"));
+        }
+
+        der::read_rsa_algorithm(&mut info)?;
+        RsaPrivateKey::from_pkcs1_der(info.read(OCTET_STRING)?)
+    }
+
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        let mut content = der::write(der::INTEGER, &[0]);
+        content.extend(der::rsa_algorithm_identifier());
+        content.extend(der::write(OCTET_STRING, &self.to_pkcs1_der()));
+
+        der::write(SEQUENCE, &content)
+    }
+
+    /// PBES2 protected PKCS#8, a key that doesn't parse after decryption is
+    /// reported as a wrong passphrase.
+    pub fn from_encrypted_pkcs8_der(der: &[u8], passphrase: &[u8]) -> Result<Self, Error> {
+        let private_key_info = pbes2::decrypt(der, passphrase)?;
+
+        RsaPrivateKey::from_pkcs8_der(&private_key_info).map_err(|_| Error::Passphrase)
+    }
+
+    pub fn to_encrypted_pkcs8_der(&self, passphrase: &[u8]) -> Result<Vec<u8>, Error> {
+        pbes2::encrypt(&Zeroizing::new(self.to_pkcs8_der()), passphrase)
+    }
+
+    /// Accepts both "RSA PRIVATE KEY" (PKCS#1) and "PRIVATE KEY" (PKCS#8) PEM.
+    pub fn from_pem(pem: &str) -> Result<Self, Error> {
+        match der::pem_decode(pem)? {
+            (label, der) if label == "RSA PRIVATE KEY" => RsaPrivateKey::from_pkcs1_der(&der),
+            (label, der) if label == "PRIVATE KEY" => RsaPrivateKey::from_pkcs8_der(&der),
+            (label, _) if label == "ENCRYPTED PRIVATE KEY" => {
+                Err(Error::InvalidKey("key is encrypted, passphrase required"))
+            }
+            _ => Err(Error::InvalidKey("not an RSA private key")),
+        }
+    }
+
+    /// Like [`RsaPrivateKey::from_pem`] but also accepts "ENCRYPTED PRIVATE KEY" PEM.
+    pub fn from_pem_with_passphrase(pem: &str, passphrase: &[u8]) -> Result<Self, Error> {
+        match der::pem_decode(pem)? {
+            (label, der) if label == "ENCRYPTED PRIVATE KEY" => {
+                RsaPrivateKey::from_encrypted_pkcs8_der(&der, passphrase)
+            }
+            _ => RsaPrivateKey::from_pem(pem),
+        }
+    }
+
+    pub fn to_encrypted_pkcs8_pem(&self, passphrase: &[u8]) -> Result<String, Error> {
+        Ok(der::pem_encode(
+            "ENCRYPTED PRIVATE KEY",
+            &self.to_encrypted_pkcs8_der(passphrase)?,
+        ))
+    }
+
+    pub fn to_pkcs1_pem(&self) -> String {
+        der::pem_encode("RSA PRIVATE KEY", &self.to_pkcs1_der())
+    }
+
+    pub fn to_pkcs8_pem(&self) -> String {
+        der::pem_encode("PRIVATE KEY", &self.to_pkcs8_der())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::encrypt::{Decrypter, Encrypter};
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::{PKey, Private};
+    use openssl::rsa::{Padding, Rsa};
+    use openssl::sign::{RsaPssSaltlen, Signer, Verifier};
+    use openssl::symm::Cipher;
+
+    fn openssl_key() -> (PKey<Private>, RsaPrivateKey) {
+        let rsa = Rsa::generate(2048).unwrap();
+        let pem = String::from_utf8(rsa.private_key_to_pem().unwrap()).unwrap();
+
+        (
+            PKey::from_rsa(rsa).unwrap(),
+            RsaPrivateKey::from_pem(&pem).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_key_formats_match_openssl() {
+        let (openssl, key) = openssl_key();
+        let rsa = openssl.rsa().unwrap();
+        let public = key.to_public_key();
+
+        assert_eq!(key.to_pkcs1_der(), rsa.private_key_to_der().unwrap());
+        assert_eq!(key.to_pkcs8_der(), openssl.private_key_to_pkcs8().unwrap());
+        assert_eq!(
+            public.to_pkcs1_der(),
+            rsa.public_key_to_der_pkcs1().unwrap()
+        );
+        assert_eq!(
+            public.to_public_key_der(),
+            openssl.public_key_to_der().unwrap()
+        );
+
+        let pem = String::from_utf8(openssl.public_key_to_pem().unwrap()).unwrap();
+        assert_eq!(RsaPublicKey::from_pem(&pem).unwrap(), public);
+        assert_eq!(RsaPrivateKey::from_pem(&key.to_pkcs8_pem()).unwrap(), key);
+        assert!(RsaPrivateKey::from_pem(&public.to_pkcs1_pem()).is_err());
+    }
+
+    #[test]
+    fn test_encrypted_keys_interoperate() {
+        let (openssl, key) = openssl_key();
+
+        let pem = openssl
+            .private_key_to_pem_pkcs8_passphrase(Cipher::aes_256_cbc(), b"secret")
+            .unwrap();
+        let pem = String::from_utf8(pem).unwrap();
+        assert_eq!(
+            RsaPrivateKey::from_pem_with_passphrase(&pem, b"secret").unwrap(),
+            key
+        );
+        assert_eq!(
+            RsaPrivateKey::from_pem_with_passphrase(&pem, b"wrong"),
+            Err(Error::Passphrase)
+        );
+        assert!(RsaPrivateKey::from_pem(&pem).is_err());
+
+        let pem = key.to_encrypted_pkcs8_pem(b"secret").unwrap();
+        let decrypted = PKey::private_key_from_pem_passphrase(pem.as_bytes(), b"secret").unwrap();
+        assert!(decrypted.public_eq(&openssl));
+        assert!(PKey::private_key_from_pem_passphrase(pem.as_bytes(), b"wrong").is_err());
+        assert_eq!(
+            RsaPrivateKey::from_pem_with_passphrase(&key.to_pkcs1_pem(), b"ignored").unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn test_generated_key_is_accepted_by_openssl() {
+        let key = RsaPrivateKey::generate(1024).unwrap();
+        let rsa = Rsa::private_key_from_pem(key.to_pkcs1_pem().as_bytes()).unwrap();
+
+        assert_eq!(rsa.size(), 128);
+        assert_eq!(key.to_public_key().e(), &BigUint::from(PUBLIC_EXPONENT));
+        assert!(rsa.check_key().unwrap());
+        assert!(RsaPrivateKey::generate(1000 + 1).is_err());
+
+        let e = BigUint::from(3u8);
+        let key = RsaPrivateKey::generate_with_exponent(1024, &e).unwrap();
+        assert_eq!(key.to_public_key().e(), &e);
+        assert!(RsaPrivateKey::generate_with_exponent(1024, &BigUint::from(4u8)).is_err());
+    }
+
+    #[test]
+    fn test_decrypts_openssl_cyphers() {
+        let (openssl, key) = openssl_key();
+        let message = b"cross-checked with OpenSSL";
+
+        let mut encrypter = Encrypter::new(&openssl).unwrap();
+        encrypter.set_rsa_padding(Padding::PKCS1).unwrap();
+        let mut cypher = vec![0; encrypter.encrypt_len(message).unwrap()];
+        let len = encrypter.encrypt(message, &mut cypher).unwrap();
+        assert_eq!(
+            key.decrypt(&cypher[..len], &EncryptionPadding::Pkcs1v15)
+                .unwrap(),
+            message
+        );
+
+        let mut encrypter = Encrypter::new(&openssl).unwrap();
+        encrypter.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        encrypter.set_rsa_oaep_md(MessageDigest::sha384()).unwrap();
+        encrypter.set_rsa_mgf1_md(MessageDigest::sha1()).unwrap();
+        encrypter.set_rsa_oaep_label(b"label").unwrap();
+        let mut cypher = vec![0; encrypter.encrypt_len(message).unwrap()];
+        let len = encrypter.encrypt(message, &mut cypher).unwrap();
+        let oaep = EncryptionPadding::Oaep {
+            hash: HashAlgorithm::Sha384,
+            mgf1_hash: HashAlgorithm::Sha1,
+            label: b"label".to_vec(),
+        };
+        assert_eq!(key.decrypt(&cypher[..len], &oaep).unwrap(), message);
+
+        let mut tampered = cypher[..len].to_vec();
+        tampered[100] ^= 1;
+        assert_eq!(key.decrypt(&tampered, &oaep), Err(Error::Decryption));
+
+        let rsa = openssl.rsa().unwrap();
+        let mut signed = vec![0; rsa.size() as usize];
+        let len = rsa
+            .private_encrypt(message, &mut signed, Padding::PKCS1)
+            .unwrap();
+        assert_eq!(
+            key.to_public_key()
+                .public_decrypt(&signed[..len], &EncryptionPadding::Pkcs1v15)
+                .unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn test_openssl_decrypts_cyphers() {
+        let (openssl, key) = openssl_key();
+        let public = key.to_public_key();
+        let message = b"cross-checked with OpenSSL";
+
+        let oaep = EncryptionPadding::Oaep {
+            hash: HashAlgorithm::Sha256,
+            mgf1_hash: HashAlgorithm::Sha256,
+            label: Vec::new(),
+        };
+        for (padding, openssl_padding) in [
+            (EncryptionPadding::Pkcs1v15, Padding::PKCS1),
+            (oaep, Padding::PKCS1_OAEP),
+        ] {
+            let cypher = public.encrypt(message, &padding).unwrap();
+
+            let mut decrypter = Decrypter::new(&openssl).unwrap();
+            decrypter.set_rsa_padding(openssl_padding).unwrap();
+            if openssl_padding == Padding::PKCS1_OAEP {
+                decrypter.set_rsa_oaep_md(MessageDigest::sha256()).unwrap();
+                decrypter.set_rsa_mgf1_md(MessageDigest::sha256()).unwrap();
+            }
+            let mut plain = vec![0; decrypter.decrypt_len(&cypher).unwrap()];
+            let len = decrypter.decrypt(&cypher, &mut plain).unwrap();
+
+            assert_eq!(&plain[..len], message);
+        }
+
+        let signed = key
+            .private_encrypt(message, &EncryptionPadding::Pkcs1v15)
+            .unwrap();
+        let mut plain = vec![0; key.size()];
+        let rsa = openssl.rsa().unwrap();
+        let len = rsa
+            .public_decrypt(&signed, &mut plain, Padding::PKCS1)
+            .unwrap();
+        assert_eq!(&plain[..len], message);
+    }
+
+    #[test]
+    fn test_signatures_interoperate() {
+        let (openssl, key) = openssl_key();
+        let public = key.to_public_key();
+        let message = b"signed by both implementations";
+
+        for (padding, salt_len) in [
+            (SignaturePadding::Pkcs1v15, None),
+            (SignaturePadding::Pss { salt_len: Some(32) }, Some(32)),
+            // 2048-bit modulus, the longest salt beside a SHA-256 hash
+            (SignaturePadding::Pss { salt_len: None }, Some(256 - 32 - 2)),
+        ] {
+            let signature = key.sign(HashAlgorithm::Sha256, message, padding).unwrap();
+
+            let mut verifier = Verifier::new(MessageDigest::sha256(), &openssl).unwrap();
+            if let Some(salt_len) = salt_len {
+                verifier.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+                verifier
+                    .set_rsa_pss_saltlen(RsaPssSaltlen::custom(salt_len))
+                    .unwrap();
+            }
+            verifier.update(message).unwrap();
+            assert!(verifier.verify(&signature).unwrap());
+
+            let mut signer = Signer::new(MessageDigest::sha256(), &openssl).unwrap();
+            if let Some(salt_len) = salt_len {
+                signer.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+                signer
+                    .set_rsa_pss_saltlen(RsaPssSaltlen::custom(salt_len))
+                    .unwrap();
+            }
+            signer.update(message).unwrap();
+            let signature = signer.sign_to_vec().unwrap();
+
+            assert!(public
+                .verify(HashAlgorithm::Sha256, message, &signature, padding)
+                .is_ok());
+            assert_eq!(
+                public.verify(
+                    HashAlgorithm::Sha256,
+                    b"another message",
+                    &signature,
+                    padding
+                ),
+                Err(Error::Verification)
+            );
+        }
+    }
+
+    #[test]
+    fn test_sha3_signature_matches_openssl() {
+        let (openssl, key) = openssl_key();
+        let message = b"signed by both implementations";
+        let padding = SignaturePadding::Pkcs1v15;
+
+        // PKCS#1 v1.5 is deterministic, so equal signatures mean equal DigestInfo
+        let mut signer = Signer::new(MessageDigest::sha3_256(), &openssl).unwrap();
+        signer.update(message).unwrap();
+        assert_eq!(
+            key.sign(HashAlgorithm::Sha3_256, message, padding).unwrap(),
+            signer.sign_to_vec().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sign_streamed_digest() {
+        let (_, key) = openssl_key();
+        let public = key.to_public_key();
+        let message = vec![0x5a; 200 * 1024];
+        let padding = SignaturePadding::Pkcs1v15;
+
+        for hash in [
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Sha384,
+            HashAlgorithm::Sha512,
+        ] {
+            let digest = hash.digest_reader(&message[..]).unwrap();
+            assert_eq!(digest, hash.digest(&message));
+
+            let signature = key.sign_digest(hash, &digest, padding).unwrap();
+            assert_eq!(signature, key.sign(hash, &message, padding).unwrap());
+            assert!(public
+                .verify_digest(hash, &digest, &signature, padding)
+                .is_ok());
+            assert_eq!(
+                key.sign_digest(hash, &digest[1..], padding),
+                Err(Error::DigestLength)
+            );
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// RSA without OpenSSL: prime generation, key handling, constant-time private
+// key operations and PKCS#1 v2.2 encodings on top of num-bigint.
+use std::fmt;
+
+pub mod blind;
+pub mod der;
+pub mod hash;
+pub mod key;
+pub mod montgomery;
+pub mod padding;
+pub mod pbes2;
+pub mod prime;
+pub mod shamir;
+
+pub use hash::HashAlgorithm;
+pub use key::{RsaPrivateKey, RsaPublicKey};
+pub use padding::{EncryptionPadding, SignaturePadding};
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    InvalidKey(&'static str),
+    KeySize(usize),
+    /// Operating system random generator failed
+    Random,
+    MessageTooLong,
+    InvalidInput(&'static str),
+    /// Digest length doesn't match the hash algorithm
+    DigestLength,
+    UnsupportedPadding,
+    Decryption,
+    Verification,
+    /// Private key operation failed its consistency check
+    Fault,
+    /// Encrypted key didn't decrypt, the passphrase is most likely wrong
+    Passphrase,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidKey(reason) => write!(f, "Invalid RSA key: {}", reason),
+            Error::KeySize(bits) => write!(f, "Unsupported key size: {} bits", bits),
+            Error::Random => write!(f, "Random generator failure"),
+            Error::MessageTooLong => write!(f, "Message too long for the key"),
+            Error::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+            Error::DigestLength => write!(f, "Digest length doesn't match the hash algorithm"),
+            Error::UnsupportedPadding => write!(f, "Padding not supported for this operation"),
+            Error::Decryption => write!(f, "Decryption error"),
+            Error::Verification => write!(f, "Signature verification failed"),
+            Error::Fault => write!(f, "Private key operation produced an invalid result"),
+            Error::Passphrase => write!(f, "Wrong passphrase or corrupted encrypted key"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,202 @@
+// Fixed-width Montgomery arithmetic for exponentiation with secret exponents.
+//
+// Every operation touches all limbs and uses masks instead of branches, so the
+// running time depends on the modulus size only, never on exponent bits.
+use num_bigint::BigUint;
+
+const WINDOW: usize = 4;
+
+pub struct Montgomery {
+    modulus: Vec<u64>,
+    // -modulus^-1 mod 2^64
+    n0_inv: u64,
+    // R^2 mod modulus with R = 2^(64 * limbs)
+    r2: Vec<u64>,
+}
+
+fn to_limbs(value: &BigUint, limbs: usize) -> Vec<u64> {
+    let mut digits = value.to_u64_digits();
+    assert!(digits.len() <= limbs, "Value is wider than the modulus");
+    digits.resize(limbs, 0);
+    digits
+}
+
+fn from_limbs(limbs: &[u64]) -> BigUint {
+    let bytes: Vec<u8> = limbs.iter().flat_map(|l| l.to_le_bytes()).collect();
+    BigUint::from_bytes_le(&bytes)
+}
+
+// all ones when `flag` is 1, zero otherwise
+fn mask(flag: u64) -> u64 {
+    0u64.wrapping_sub(flag)
+}
+
+fn select(mask: u64, a: &[u64], b: &[u64], out: &mut [u64]) {
+    for i in 0..out.len() {
+        out[i] = (a[i] & mask) | (b[i] & !mask);
+    }
+}
+
+impl Montgomery {
+    pub fn new(modulus: &BigUint) -> Self {
+        assert!(modulus.bit(0), "Montgomery modulus should be odd");
+
+        let limbs = modulus.to_u64_digits().len();
+        let n = to_limbs(modulus, limbs);
+
+        // Newton iteration doubles the correct low bits each step
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        let r2 = (BigUint::from(1u8) << (128 * limbs)) % modulus;
+
+        Montgomery {
+            r2: to_limbs(&r2, limbs),
+            modulus: n,
+            n0_inv: inv.wrapping_neg(),
+        }
+    }
+
+    fn limbs(&self) -> usize {
+        self.modulus.len()
+    }
+
+    /// a * b * R^-1 mod n (CIOS)
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let s = self.limbs();
+        let n = &self.modulus;
+        let mut t = vec![0u64; s + 2];
+
+        for &bi in b.iter().take(s) {
+            let mut carry = 0u128;
+            for j in 0..s {
+                let sum = t[j] as u128 + a[j] as u128 * bi as u128 + carry;
+                t[j] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[s] as u128 + carry;
+            t[s] = sum as u64;
+            t[s + 1] = (sum >> 64) as u64;
+
+            let m = t[0].wrapping_mul(self.n0_inv);
+            let sum = t[0] as u128 + m as u128 * n[0] as u128;
+            let mut carry = sum >> 64;
+            for j in 1..s {
+                let sum = t[j] as u128 + m as u128 * n[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[s] as u128 + carry;
+            t[s - 1] = sum as u64;
+            t[s] = t[s + 1] + (sum >> 64) as u64;
+        }
+
+        // t < 2n, subtract n unless that borrows
+        let mut reduced = vec![0u64; s];
+        let mut borrow = 0u64;
+        for j in 0..s {
+            let (d1, b1) = t[j].overflowing_sub(n[j]);
+            let (d2, b2) = d1.overflowing_sub(borrow);
+            reduced[j] = d2;
+            borrow = (b1 | b2) as u64;
+        }
+        let (_, b) = t[s].overflowing_sub(borrow);
+
+        let mut out = vec![0u64; s];
+        select(mask(b as u64), &t[..s], &reduced, &mut out);
+        out
+    }
+
+    /// base^exponent mod n, `exponent` is treated as `exponent_bits` wide no matter its value.
+    pub fn pow(&self, base: &BigUint, exponent: &BigUint, exponent_bits: usize) -> BigUint {
+        let s = self.limbs();
+        let base = to_limbs(&(base % from_limbs(&self.modulus)), s);
+        let mut one = vec![0u64; s];
+        one[0] = 1;
+
+        let mut table = vec![self.mul(&one, &self.r2)];
+        table.push(self.mul(&base, &self.r2));
+        for i in 2..1 << WINDOW {
+            let next = self.mul(&table[i - 1], &table[1]);
+            table.push(next);
+        }
+
+        let windows = exponent_bits.max(exponent.bits() as usize).div_ceil(WINDOW);
+        let exponent = to_limbs(exponent, (windows * WINDOW).div_ceil(64));
+        let mut acc = table[0].clone();
+        let mut entry = vec![0u64; s];
+
+        for w in (0..windows).rev() {
+            for _ in 0..WINDOW {
+                acc = self.mul(&acc, &acc);
+            }
+
+            let bit = w * WINDOW;
+            let index = (exponent[bit / 64] >> (bit % 64)) & ((1 << WINDOW) - 1);
+
+            // scan the whole table so the memory access pattern doesn't leak the index
+            for (i, value) in table.iter().enumerate() {
+                let hit = mask(((i as u64 ^ index).wrapping_sub(1) >> 63) & 1);
+                let current = entry.clone();
+                select(hit, value, &current, &mut entry);
+            }
+
+            acc = self.mul(&acc, &entry);
+        }
+
+        from_limbs(&self.mul(&acc, &one))
+    }
+}
+
+/// Modular exponentiation with running time independent of the exponent value.
+pub fn pow_ct(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+    Montgomery::new(modulus).pow(base, exponent, modulus.bits() as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_modpow() {
+        let modulus = BigUint::parse_bytes(
+            b"c7f1e4a3b1d8f6a7e9c2b4d6f8a1c3e5f7a9b2d4f6e8a1c3b5d7f9e2a4c6e8f1",
+            16,
+        )
+        .unwrap();
+        let base = BigUint::parse_bytes(b"123456789abcdef0fedcba9876543210", 16).unwrap();
+
+        for exponent in [0u64, 1, 2, 3, 65537, u64::MAX] {
+            let exponent = BigUint::from(exponent);
+
+            assert_eq!(
+                pow_ct(&base, &exponent, &modulus),
+                base.modpow(&exponent, &modulus)
+            );
+        }
+
+        let exponent = &modulus - 2u8;
+        assert_eq!(
+            pow_ct(&base, &exponent, &modulus),
+            base.modpow(&exponent, &modulus)
+        );
+        assert_eq!(
+            pow_ct(&(&modulus + 5u8), &exponent, &modulus),
+            BigUint::from(5u8).modpow(&exponent, &modulus)
+        );
+    }
+
+    #[test]
+    fn test_single_limb_modulus() {
+        let modulus = BigUint::from(1_000_000_007u64);
+        let base = BigUint::from(2u8);
+        let exponent = BigUint::from(1_000_000_005u64);
+
+        assert_eq!(
+            pow_ct(&base, &exponent, &modulus),
+            BigUint::from(500_000_004u64)
+        );
+    }
+}
@@ -0,0 +1,358 @@
+// PKCS#1 v2.2 (RFC 8017) encoding methods
+use crate::hash::HashAlgorithm;
+use crate::prime::random_bytes;
+use crate::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EncryptionPadding {
+    Pkcs1v15,
+    Oaep {
+        hash: HashAlgorithm,
+        mgf1_hash: HashAlgorithm,
+        label: Vec<u8>,
+    },
+    /// Raw RSA, the data must be exactly the modulus size
+    None,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SignaturePadding {
+    Pkcs1v15,
+    /// `None` signs with the longest salt that fits and accepts any length on verification
+    Pss {
+        salt_len: Option<usize>,
+    },
+}
+
+/// Mask generation function MGF1.
+pub fn mgf1(hash: HashAlgorithm, seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(len + hash.size());
+    let mut counter = 0u32;
+
+    while mask.len() < len {
+        let mut input = seed.to_vec();
+        input.extend_from_slice(&counter.to_be_bytes());
+        mask.extend(hash.digest(&input));
+        counter += 1;
+    }
+
+    mask.truncate(len);
+    mask
+}
+
+fn xor(data: &mut [u8], mask: &[u8]) {
+    for (d, m) in data.iter_mut().zip(mask) {
+        *d ^= m;
+    }
+}
+
+// 0xff when equal, 0 otherwise, without branching on the values
+fn ct_eq(a: u8, b: u8) -> u8 {
+    let x = (a ^ b) as u32;
+
+    ((x.wrapping_sub(1) >> 8) & 0xff) as u8
+}
+
+// widens a 0x00/0xff byte mask to a full usize mask
+fn widen(mask: u8) -> usize {
+    0usize.wrapping_sub((mask & 1) as usize)
+}
+
+/// EME-PKCS1-v1_5 encoding for encryption: 00 02 PS 00 M with non-zero random PS.
+pub fn pkcs1v15_encrypt_pad(message: &[u8], k: usize) -> Result<Vec<u8>, Error> {
+    if message.len() + 11 > k {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut em = vec![0u8; k];
+    em[1] = 2;
+
+    for byte in em[2..k - message.len() - 1].iter_mut() {
+        while *byte == 0 {
+            *byte = random_bytes(1)?[0];
+        }
+    }
+
+    em[k - message.len()..].copy_from_slice(message);
+    Ok(em)
+}
+
+/// Scans the whole block before deciding, every failure reports the same error.
+pub fn pkcs1v15_encrypt_unpad(em: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut valid = ct_eq(em[0], 0) & ct_eq(em[1], 2);
+    let mut looking = 0xffu8;
+    let mut index = 0usize;
+
+    for (i, &byte) in em.iter().enumerate().skip(2) {
+        let zero = ct_eq(byte, 0);
+        index |= i & widen(looking & zero);
+        looking &= !zero;
+    }
+
+    // separator must exist and at least 8 padding bytes precede it
+    valid &= !looking;
+    valid &= if index >= 10 { 0xff } else { 0 };
+
+    if valid == 0 {
+        return Err(Error::Decryption);
+    }
+
+    Ok(em[index + 1..].to_vec())
+}
+
+/// EMSA-PKCS1-v1_5 signature encoding: 00 01 FF..FF 00 DigestInfo.
+pub fn pkcs1v15_sign_pad(hash: HashAlgorithm, digest: &[u8], k: usize) -> Result<Vec<u8>, Error> {
+    let prefix = hash.digest_info_prefix();
+    let t_len = prefix.len() + digest.len();
+
+    if k < t_len + 11 {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut em = vec![0xffu8; k];
+    em[0] = 0;
+    em[1] = 1;
+    em[k - t_len - 1] = 0;
+    em[k - t_len..k - digest.len()].copy_from_slice(prefix);
+    em[k - digest.len()..].copy_from_slice(digest);
+
+    Ok(em)
+}
+
+/// Type 1 padding without DigestInfo, what OpenSSL's private_encrypt produces.
+pub fn pkcs1v15_raw_sign_pad(data: &[u8], k: usize) -> Result<Vec<u8>, Error> {
+    if data.len() + 11 > k {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut em = vec![0xffu8; k];
+    em[0] = 0;
+    em[1] = 1;
+    em[k - data.len() - 1] = 0;
+    em[k - data.len()..].copy_from_slice(data);
+
+    Ok(em)
+}
+
+pub fn pkcs1v15_raw_sign_unpad(em: &[u8]) -> Result<Vec<u8>, Error> {
+    if em.len() < 11 || em[0] != 0 || em[1] != 1 {
+        return Err(Error::Verification);
+    }
+
+    let separator = em[2..]
+        .iter()
+        .position(|&b| b != 0xff)
+        .map(|p| p + 2)
+        .ok_or(Error::Verification)?;
+
+    if separator < 10 || em[separator] != 0 {
+        return Err(Error::Verification);
+    }
+
+    Ok(em[separator + 1..].to_vec())
+}
+
+/// EME-OAEP encoding.
+pub fn oaep_pad(
+    message: &[u8],
+    k: usize,
+    hash: HashAlgorithm,
+    mgf1_hash: HashAlgorithm,
+    label: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let h_len = hash.size();
+
+    if message.len() + 2 * h_len + 2 > k {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut db = hash.digest(label);
+    db.resize(k - message.len() - h_len - 2, 0);
+    db.push(1);
+    db.extend_from_slice(message);
+
+    let mut seed = random_bytes(h_len)?;
+    xor(&mut db, &mgf1(mgf1_hash, &seed, k - h_len - 1));
+    xor(&mut seed, &mgf1(mgf1_hash, &db, h_len));
+
+    let mut em = vec![0u8];
+    em.extend(seed);
+    em.extend(db);
+    Ok(em)
+}
+
+/// EME-OAEP decoding, checks every byte before reporting a single error.
+pub fn oaep_unpad(
+    em: &[u8],
+    hash: HashAlgorithm,
+    mgf1_hash: HashAlgorithm,
+    label: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let h_len = hash.size();
+    let k = em.len();
+
+    if k < 2 * h_len + 2 {
+        return Err(Error::Decryption);
+    }
+
+    let mut seed = em[1..=h_len].to_vec();
+    let mut db = em[h_len + 1..].to_vec();
+    xor(&mut seed, &mgf1(mgf1_hash, &db, h_len));
+    xor(&mut db, &mgf1(mgf1_hash, &seed, k - h_len - 1));
+
+    let l_hash = hash.digest(label);
+    let mut valid = ct_eq(em[0], 0);
+    for (a, b) in db[..h_len].iter().zip(&l_hash) {
+        valid &= ct_eq(*a, *b);
+    }
+
+    let mut looking = 0xffu8;
+    let mut index = 0usize;
+    for (i, &byte) in db.iter().enumerate().skip(h_len) {
+        let one = ct_eq(byte, 1);
+        let zero = ct_eq(byte, 0);
+        index |= i & widen(looking & one);
+        // anything other than zeros before the separator is invalid
+        valid &= !looking | zero | one;
+        looking &= !one;
+    }
+    valid &= !looking;
+
+    if valid == 0 {
+        return Err(Error::Decryption);
+    }
+
+    Ok(db[index + 1..].to_vec())
+}
+
+/// EMSA-PSS encoding of a message hash into `em_bits` bits.
+pub fn pss_encode(
+    digest: &[u8],
+    em_bits: usize,
+    hash: HashAlgorithm,
+    salt_len: usize,
+) -> Result<Vec<u8>, Error> {
+    pss_encode_with_salt(digest, em_bits, hash, &random_bytes(salt_len)?)
+}
+
+/// EMSA-PSS encoding with a caller chosen salt, for deterministic signatures
+/// and known answer tests.
+pub fn pss_encode_with_salt(
+    digest: &[u8],
+    em_bits: usize,
+    hash: HashAlgorithm,
+    salt: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let h_len = hash.size();
+    let em_len = em_bits.div_ceil(8);
+    let salt_len = salt.len();
+
+    if em_len < h_len + salt_len + 2 {
+        return Err(Error::MessageTooLong);
+    }
+
+    let mut m_prime = vec![0u8; 8];
+    m_prime.extend_from_slice(digest);
+    m_prime.extend_from_slice(salt);
+    let h = hash.digest(&m_prime);
+
+    let mut db = vec![0u8; em_len - salt_len - h_len - 2];
+    db.push(1);
+    db.extend_from_slice(salt);
+    xor(&mut db, &mgf1(hash, &h, em_len - h_len - 1));
+    db[0] &= 0xff >> (8 * em_len - em_bits);
+
+    let mut em = db;
+    em.extend(h);
+    em.push(0xbc);
+    Ok(em)
+}
+
+/// EMSA-PSS verification, `salt_len` of `None` recovers the salt length from the block.
+pub fn pss_verify(
+    digest: &[u8],
+    em: &[u8],
+    em_bits: usize,
+    hash: HashAlgorithm,
+    salt_len: Option<usize>,
+) -> Result<(), Error> {
+    let h_len = hash.size();
+    let em_len = em_bits.div_ceil(8);
+    let (prefix, em) = em.split_at(em.len() - em_len);
+    let top_mask = 0xffu8 >> (8 * em_len - em_bits);
+
+    if prefix.iter().any(|&b| b != 0)
+        || em_len < h_len + 2
+        || em[em_len - 1] != 0xbc
+        || em[0] & !top_mask != 0
+    {
+        return Err(Error::Verification);
+    }
+
+    let (masked_db, h) = em[..em_len - 1].split_at(em_len - h_len - 1);
+    let mut db = masked_db.to_vec();
+    let mask = mgf1(hash, h, db.len());
+    xor(&mut db, &mask);
+    db[0] &= top_mask;
+
+    let separator = db.iter().position(|&b| b != 0).ok_or(Error::Verification)?;
+    let salt = &db[separator + 1..];
+
+    if db[separator] != 1 || salt_len.is_some_and(|len| len != salt.len()) {
+        return Err(Error::Verification);
+    }
+
+    let mut m_prime = vec![0u8; 8];
+    m_prime.extend_from_slice(digest);
+    m_prime.extend_from_slice(salt);
+
+    if hash.digest(&m_prime) != h {
+        return Err(Error::Verification);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trips() {
+        let message = b"padding test";
+        let em = pkcs1v15_encrypt_pad(message, 128).unwrap();
+        assert_eq!(pkcs1v15_encrypt_unpad(&em).unwrap(), message);
+
+        let em = oaep_pad(
+            message,
+            128,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Sha1,
+            b"l",
+        )
+        .unwrap();
+        assert_eq!(
+            oaep_unpad(&em, HashAlgorithm::Sha256, HashAlgorithm::Sha1, b"l").unwrap(),
+            message
+        );
+        assert!(oaep_unpad(&em, HashAlgorithm::Sha256, HashAlgorithm::Sha1, b"x").is_err());
+
+        let digest = HashAlgorithm::Sha256.digest(message);
+        let em = pss_encode(&digest, 1023, HashAlgorithm::Sha256, 32).unwrap();
+        assert!(pss_verify(&digest, &em, 1023, HashAlgorithm::Sha256, Some(32)).is_ok());
+        assert!(pss_verify(&digest, &em, 1023, HashAlgorithm::Sha256, None).is_ok());
+        assert!(pss_verify(&digest, &em, 1023, HashAlgorithm::Sha256, Some(20)).is_err());
+    }
+
+    #[test]
+    fn test_pkcs1v15_rejects_malformed_blocks() {
+        let mut em = pkcs1v15_encrypt_pad(b"message", 64).unwrap();
+        em[1] = 1;
+        assert!(pkcs1v15_encrypt_unpad(&em).is_err());
+
+        // separator within the first eight padding bytes
+        let mut em = vec![0u8, 2, 1, 1, 1, 0];
+        em.extend_from_slice(&[1; 58]);
+        assert!(pkcs1v15_encrypt_unpad(&em).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+// PKCS#5 v2.1 PBES2 (RFC 8018) for PKCS#8 EncryptedPrivateKeyInfo, limited to
+// PBKDF2 with HMAC-SHA1/SHA-256 and AES-CBC, the combinations OpenSSL writes.
+use crate::der::{self, Reader, INTEGER, NULL, OBJECT_IDENTIFIER, OCTET_STRING, SEQUENCE};
+use crate::prime::random_bytes;
+use crate::Error;
+use aes::cipher::block_padding::Pkcs7;
+use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+use sha1::Sha1;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+/// 1.2.840.113549.1.5.13
+const PBES2_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0d];
+/// 1.2.840.113549.1.5.12
+const PBKDF2_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x05, 0x0c];
+/// 1.2.840.113549.2.7
+const HMAC_SHA1_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x07];
+/// 1.2.840.113549.2.9
+const HMAC_SHA256_OID: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x02, 0x09];
+/// 2.16.840.1.101.3.4.1.2
+const AES_128_CBC_OID: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x02];
+/// 2.16.840.1.101.3.4.1.42
+const AES_256_CBC_OID: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2a];
+
+pub const ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+const IV_LEN: usize = 16;
+
+fn algorithm(oid: &[u8], params: &[u8]) -> Vec<u8> {
+    let mut content = der::write(OBJECT_IDENTIFIER, oid);
+    content.extend_from_slice(params);
+
+    der::write(SEQUENCE, &content)
+}
+
+/// Wraps a PKCS#8 PrivateKeyInfo into an EncryptedPrivateKeyInfo with
+/// PBKDF2-HMAC-SHA256 and AES-256-CBC.
+pub fn encrypt(private_key_info: &[u8], passphrase: &[u8]) -> Result<Vec<u8>, Error> {
+    let salt = random_bytes(SALT_LEN)?;
+    let iv = random_bytes(IV_LEN)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase, &salt, ITERATIONS, key.as_mut());
+
+    let cypher = cbc::Encryptor::<aes::Aes256>::new(key.as_ref().into(), iv.as_slice().into())
+        .encrypt_padded_vec_mut::<Pkcs7>(private_key_info);
+
+    let mut kdf_params = der::write(OCTET_STRING, &salt);
+    kdf_params.extend(der::write_integer(&ITERATIONS.into()));
+    kdf_params.extend(algorithm(HMAC_SHA256_OID, &der::write(NULL, &[])));
+
+    let mut pbes2_params = algorithm(PBKDF2_OID, &der::write(SEQUENCE, &kdf_params));
+    pbes2_params.extend(algorithm(AES_256_CBC_OID, &der::write(OCTET_STRING, &iv)));
+
+    let mut content = algorithm(PBES2_OID, &der::write(SEQUENCE, &pbes2_params));
+    content.extend(der::write(OCTET_STRING, &cypher));
+
+    Ok(der::write(SEQUENCE, &content))
+}
+
+fn read_u32(reader: &mut Reader) -> Result<u32, Error> {
+    let value = reader.read_integer()?;
+
+    u32::try_from(value).map_err(|_| Error::InvalidKey("integer out of range"))
+}
+
+/// Recovers the PKCS#8 PrivateKeyInfo from an EncryptedPrivateKeyInfo.
+pub fn decrypt(encrypted: &[u8], passphrase: &[u8]) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let unsupported = || Error::InvalidKey("unsupported key encryption");
+
+    let mut outer = Reader::new(encrypted);
+    let mut info = Reader::new(outer.read(SEQUENCE)?);
+    let mut scheme = Reader::new(info.read(SEQUENCE)?);
+    let cypher = info.read(OCTET_STRING)?;
+
+    if scheme.read(OBJECT_IDENTIFIER)? != PBES2_OID {
+        return Err(unsupported());
+    }
+
+    let mut params = Reader::new(scheme.read(SEQUENCE)?);
+    let mut kdf = Reader::new(params.read(SEQUENCE)?);
+    let mut cipher = Reader::new(params.read(SEQUENCE)?);
+
+    if kdf.read(OBJECT_IDENTIFIER)? != PBKDF2_OID {
+        return Err(unsupported());
+    }
+
+    let mut kdf_params = Reader::new(kdf.read(SEQUENCE)?);
+    let salt = kdf_params.read(OCTET_STRING)?;
+    let iterations = read_u32(&mut kdf_params)?;
+    if kdf_params.peek() == Some(INTEGER) {
+        // key length is implied by the cipher
+        read_u32(&mut kdf_params)?;
+    }
+    let sha256 = match kdf_params.peek() {
+        None => false,
+        Some(_) => match Reader::new(kdf_params.read(SEQUENCE)?).read(OBJECT_IDENTIFIER)? {
+            oid if oid == HMAC_SHA1_OID => false,
+            oid if oid == HMAC_SHA256_OID => true,
+            _ => return Err(unsupported()),
+        },
+    };
+
+    let key_len = match cipher.read(OBJECT_IDENTIFIER)? {
+        oid if oid == AES_128_CBC_OID => 16,
+        oid if oid == AES_256_CBC_OID => 32,
+        _ => return Err(unsupported()),
+    };
+    let iv = cipher.read(OCTET_STRING)?;
+    if iv.len() != IV_LEN {
+        return Err(Error::InvalidKey("malformed cipher parameters"));
+    }
+
+    let mut key = Zeroizing::new(vec![0u8; key_len]);
+    if sha256 {
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase, salt, iterations, &mut key);
+    } else {
+        pbkdf2::pbkdf2_hmac::<Sha1>(passphrase, salt, iterations, &mut key);
+    }
+
+    let plain = match key_len {
+        16 => cbc::Decryptor::<aes::Aes128>::new(key.as_slice().into(), iv.into())
+            .decrypt_padded_vec_mut::<Pkcs7>(cypher),
+        _ => cbc::Decryptor::<aes::Aes256>::new(key.as_slice().into(), iv.into())
+            .decrypt_padded_vec_mut::<Pkcs7>(cypher),
+    };
+
+    plain.map(Zeroizing::new).map_err(|_| Error::Passphrase)
+}
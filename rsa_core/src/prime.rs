@@ -0,0 +1,288 @@
+use crate::Error;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+const SMALL_PRIMES: [u32; 53] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251,
+];
+
+/// Random bytes from the operating system.
+pub fn random_bytes(len: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![0; len];
+    getrandom::getrandom(&mut bytes).map_err(|_| Error::Random)?;
+
+    Ok(bytes)
+}
+
+/// Uniformly random number in [low, high).
+pub fn random_range(low: &BigUint, high: &BigUint) -> Result<BigUint, Error> {
+    let span = high - low;
+    let bits = span.bits();
+
+    loop {
+        let mut bytes = random_bytes(bits.div_ceil(8) as usize)?;
+        let extra = bytes.len() as u64 * 8 - bits;
+        bytes[0] &= 0xff >> extra;
+        let candidate = BigUint::from_bytes_be(&bytes);
+
+        if candidate < span {
+            return Ok(low + candidate);
+        }
+    }
+}
+
+/// Miller-Rabin strong probable prime test to `base`, `n` must be odd and above 3.
+pub fn miller_rabin(n: &BigUint, base: &BigUint) -> bool {
+    let n_minus_one = n - 1u8;
+    let s = n_minus_one.trailing_zeros().unwrap();
+    let d = &n_minus_one >> s;
+    let mut x = base.modpow(&d, n);
+
+    if x.is_one() || x == n_minus_one {
+        return true;
+    }
+
+    for _ in 1..s {
+        x = x.modpow(&BigUint::from(2u8), n);
+
+        if x == n_minus_one {
+            return true;
+        }
+        if x.is_one() {
+            return false;
+        }
+    }
+
+    false
+}
+
+fn jacobi(a: &BigInt, n: &BigUint) -> i32 {
+    let mut n = n.clone();
+    let mut a = a
+        .mod_floor(&BigInt::from_biguint(Sign::Plus, n.clone()))
+        .to_biguint()
+        .unwrap();
+    let mut result = 1;
+
+    while !a.is_zero() {
+        while a.is_even() {
+            a >>= 1;
+            let r = (&n % 8u8).to_u32_digits().first().copied().unwrap_or(0);
+
+            if r == 3 || r == 5 {
+                result = -result;
+            }
+        }
+
+        std::mem::swap(&mut a, &mut n);
+
+        if (&a % 4u8) == BigUint::from(3u8) && (&n % 4u8) == BigUint::from(3u8) {
+            result = -result;
+        }
+        a %= &n;
+    }
+
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+// x / 2 mod n for odd n
+fn half(x: BigUint, n: &BigUint) -> BigUint {
+    if x.is_odd() {
+        (x + n) >> 1
+    } else {
+        x >> 1
+    }
+}
+
+/// Strong Lucas probable prime test with Selfridge's parameters, `n` must be odd.
+pub fn strong_lucas(n: &BigUint) -> bool {
+    // D never turns up for perfect squares
+    let root = n.sqrt();
+    if &root * &root == *n {
+        return false;
+    }
+
+    // first D in 5, -7, 9, -11, ... with (D/n) = -1
+    let mut d = BigInt::from(5);
+    loop {
+        match jacobi(&d, n) {
+            -1 => break,
+            0 if d.abs().to_biguint().unwrap() != *n => return false,
+            _ => {}
+        }
+
+        d = if d.is_positive() {
+            -(d + 2u8)
+        } else {
+            -d + 2u8
+        };
+    }
+
+    let modulus = BigInt::from_biguint(Sign::Plus, n.clone());
+    let reduce = |x: BigInt| x.mod_floor(&modulus).to_biguint().unwrap();
+    let q = reduce((BigInt::one() - &d) / 4);
+    let d = reduce(d);
+    let p = BigUint::one();
+
+    let n_plus_one = n + 1u8;
+    let s = n_plus_one.trailing_zeros().unwrap();
+    let k = &n_plus_one >> s;
+
+    let mut u = BigUint::one();
+    let mut v = p.clone();
+    let mut qk = q.clone();
+
+    for bit in (0..k.bits() - 1).rev() {
+        u = (&u * &v) % n;
+        v = (&v * &v + n * 2u8 - (&qk * 2u8) % n) % n;
+        qk = (&qk * &qk) % n;
+
+        if k.bit(bit) {
+            let next_u = half(&p * &u + &v, n);
+            let next_v = half(&d * &u + &p * &v, n);
+            u = next_u % n;
+            v = next_v % n;
+            qk = (&qk * &q) % n;
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+
+    for _ in 1..s {
+        v = (&v * &v + n * 2u8 - (&qk * 2u8) % n) % n;
+        qk = (&qk * &qk) % n;
+
+        if v.is_zero() {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Baillie-PSW: trial division, Miller-Rabin to base 2 and a strong Lucas test.
+/// No composite passing it is known.
+pub fn baillie_psw(n: &BigUint) -> bool {
+    if *n < BigUint::from(2u8) {
+        return false;
+    }
+    if n.is_even() {
+        return *n == BigUint::from(2u8);
+    }
+
+    for &p in SMALL_PRIMES.iter() {
+        if *n == BigUint::from(p) {
+            return true;
+        }
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+
+    miller_rabin(n, &BigUint::from(2u8)) && strong_lucas(n)
+}
+
+/// Baillie-PSW followed by `rounds` Miller-Rabin tests to random bases.
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> Result<bool, Error> {
+    if !baillie_psw(n) {
+        return Ok(false);
+    }
+    if *n < BigUint::from(256u16) {
+        return Ok(true);
+    }
+
+    for _ in 0..rounds {
+        let base = random_range(&BigUint::from(2u8), &(n - 2u8))?;
+
+        if !miller_rabin(n, &base) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Random prime of exactly `bits` bits with the two top bits set, so the product
+/// of two such primes has exactly `2 * bits` bits.
+pub fn generate_prime(bits: u64, accept: impl Fn(&BigUint) -> bool) -> Result<BigUint, Error> {
+    if bits < 16 {
+        return Err(Error::KeySize(bits as usize * 2));
+    }
+
+    // FIPS 186-5 table B.1 rounds on top of Baillie-PSW
+    let rounds = if bits >= 1024 { 4 } else { 5 };
+
+    loop {
+        let mut candidate = BigUint::from_bytes_be(&random_bytes(bits.div_ceil(8) as usize)?);
+        candidate &= (BigUint::one() << bits) - 1u8;
+        candidate |= BigUint::from(3u8) << (bits - 2);
+        candidate |= BigUint::one();
+
+        if accept(&candidate) && is_probable_prime(&candidate, rounds)? {
+            return Ok(candidate);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_numbers() {
+        let primes: Vec<u32> = (0..1000)
+            .filter(|&n| baillie_psw(&BigUint::from(n)))
+            .collect();
+        let expected: Vec<u32> = (0..1000u32)
+            .filter(|&n| n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0))
+            .collect();
+
+        assert_eq!(primes, expected);
+    }
+
+    #[test]
+    fn test_pseudoprimes_are_rejected() {
+        // strong pseudoprimes to base 2
+        for n in [2047u64, 3277, 4033, 4681, 8321, 3215031751, 2152302898747] {
+            let n = BigUint::from(n);
+
+            assert!(miller_rabin(&n, &BigUint::from(2u8)));
+            assert!(!baillie_psw(&n));
+        }
+
+        // strong Lucas pseudoprimes
+        for n in [5459u64, 5777, 10877, 16109, 18971, 22499, 24569] {
+            let n = BigUint::from(n);
+
+            assert!(strong_lucas(&n));
+            assert!(!baillie_psw(&n));
+        }
+
+        // Carmichael numbers
+        for n in [561u64, 1105, 1729, 2465, 2821, 6601, 8911] {
+            assert!(!baillie_psw(&BigUint::from(n)));
+        }
+    }
+
+    #[test]
+    fn test_large_primes() {
+        let mersenne = (BigUint::one() << 521) - 1u8;
+        let composite = (BigUint::one() << 523) - 1u8;
+
+        assert!(is_probable_prime(&mersenne, 4).unwrap());
+        assert!(!is_probable_prime(&composite, 4).unwrap());
+
+        let prime = generate_prime(256, |_| true).unwrap();
+        assert_eq!(prime.bits(), 256);
+        assert!(prime.bit(254));
+    }
+}